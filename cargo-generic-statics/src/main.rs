@@ -0,0 +1,321 @@
+//! `cargo generic-statics`: Lists the generic statics of built binaries.
+//!
+//! ```text
+//! cargo generic-statics target/release/app
+//! ```
+//!
+//! The storage reserved by `generic-statics` is labeled with hidden symbols, which are kept in the
+//! symbol table of unstripped binaries:
+//!
+//! - `__generic_statics.{key}.{size}.{align}` for `Identity::Process` namespaces, where `key` is a
+//!   hash of the namespace and type. On ELF and COFF, the storage is also labeled with a local
+//!   symbol named after `generic_statics::asm::generic_static::<N, T>`.
+//! - `{generic_statics::asm::crate_static::<N, T>}.generic_static` for `Identity::Crate`
+//!   namespaces.
+//!
+//! The namespace and type are only part of the (demangled) names with v0 symbol mangling
+//! (`-Csymbol-mangling-version=v0`).
+
+use std::{collections::HashMap, env, fs, process::ExitCode};
+
+use object::{Object, ObjectSection, ObjectSymbol, SectionIndex};
+
+/// A symbol labeling a storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Symbol {
+    /// The storage of an `Identity::Process` namespace.
+    Process { key: u64, size: u64, align: u64 },
+    /// The storage of an `Identity::Crate` namespace, with the demangled `crate_static`
+    /// instantiation.
+    Crate { demangled: String },
+    /// The local symbol naming the storage of an `Identity::Process` namespace.
+    Named { namespace: String, ty: String },
+}
+
+/// A generic static found in a binary.
+#[derive(Debug)]
+struct Static {
+    addr: u64,
+    /// The size, if the symbol tells it (otherwise see `max_size`).
+    size: Option<u64>,
+    /// The distance to the next symbol or to the end of the section.
+    max_size: u64,
+    section: String,
+    /// The symbol labeling the storage (not [`Symbol::Named`]).
+    symbol: Symbol,
+    /// The namespace and type, if the symbols name them.
+    name: Option<(String, String)>,
+}
+
+/// Parses the name of a symbol labeling a storage, `None` for other symbols.
+fn parse_symbol(name: &str) -> Option<Symbol> {
+    // Mach-O prefixes symbols with an underscore.
+    if let Some(rest) = name
+        .strip_prefix("__generic_statics.")
+        .or_else(|| name.strip_prefix("___generic_statics."))
+    {
+        let mut parts = rest.split('.').map(str::parse::<u64>);
+        let (Some(Ok(key)), Some(Ok(size)), Some(Ok(align)), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        return Some(Symbol::Process {
+            key,
+            size,
+            align: 1u64.checked_shl(align.try_into().ok()?)?,
+        });
+    }
+
+    let mangled = name.strip_suffix(".generic_static")?;
+    let demangled = format!("{:#}", rustc_demangle::try_demangle(mangled).ok()?);
+    if let Some(args) = demangled.strip_prefix("generic_statics::asm::generic_static::<") {
+        let (namespace, ty) = split_args(args.strip_suffix('>')?)?;
+        return Some(Symbol::Named {
+            namespace: namespace.to_owned(),
+            ty: ty.to_owned(),
+        });
+    }
+    Some(Symbol::Crate { demangled })
+}
+
+/// Splits `generic_statics::asm::crate_static::<N, T>` into `N` and `T`.
+fn split_crate_static(demangled: &str) -> Option<(&str, &str)> {
+    let start = demangled.find("::crate_static::<")? + "::crate_static::<".len();
+    split_args(demangled[start..].strip_suffix('>')?)
+}
+
+/// Splits the generic arguments `N, T` into `N` and `T`.
+fn split_args(args: &str) -> Option<(&str, &str)> {
+    // Splits at the first top-level comma.
+    let mut depth = 0usize;
+    for (i, c) in args.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => return Some((args[..i].trim(), args[i + 1..].trim())),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the generic statics of the binary `data`, ordered by address.
+fn statics(data: &[u8]) -> Result<Vec<Static>, String> {
+    let file = object::File::parse(data).map_err(|err| err.to_string())?;
+    if file.symbols().next().is_none() {
+        return Err("no symbol table (is the binary stripped?)".to_owned());
+    }
+
+    // The addresses of all symbols per section, to bound the size of unsized storage.
+    let mut addrs = HashMap::<SectionIndex, Vec<u64>>::new();
+    for symbol in file.symbols() {
+        if let Some(index) = symbol.section_index() {
+            addrs.entry(index).or_default().push(symbol.address());
+        }
+    }
+    for addrs in addrs.values_mut() {
+        addrs.sort_unstable();
+    }
+
+    let mut names = HashMap::new();
+    let mut statics = Vec::new();
+    for symbol in file.symbols() {
+        let (Ok(name), Some(index)) = (symbol.name(), symbol.section_index()) else {
+            continue;
+        };
+        let addr = symbol.address();
+        let parsed = match parse_symbol(name) {
+            None => continue,
+            Some(Symbol::Named { namespace, ty }) => {
+                names.insert(addr, (namespace, ty));
+                continue;
+            }
+            Some(parsed) => parsed,
+        };
+        let (size, name) = match &parsed {
+            Symbol::Process { size, .. } => (Some(*size), None),
+            Symbol::Crate { demangled } => (
+                Some(symbol.size()).filter(|&size| size != 0),
+                split_crate_static(demangled).map(|(ns, ty)| (ns.to_owned(), ty.to_owned())),
+            ),
+            Symbol::Named { .. } => unreachable!(),
+        };
+        let section = file
+            .section_by_index(index)
+            .map_err(|err| err.to_string())?;
+
+        let end = addrs[&index]
+            .iter()
+            .find(|&&next| next > addr)
+            .copied()
+            .unwrap_or(section.address() + section.size());
+
+        statics.push(Static {
+            addr,
+            size,
+            max_size: end.saturating_sub(addr),
+            section: section.name().unwrap_or("?").to_owned(),
+            symbol: parsed,
+            name,
+        });
+    }
+
+    statics.sort_unstable_by_key(|s| s.addr);
+    statics.dedup_by_key(|s| s.addr);
+    for s in &mut statics {
+        if s.name.is_none() {
+            s.name = names.remove(&s.addr);
+        }
+    }
+    Ok(statics)
+}
+
+fn print(statics: &[Static]) {
+    // Storage of the same `Identity::Process` static at multiple addresses wasn't deduplicated.
+    let mut keys = HashMap::<u64, usize>::new();
+    for s in statics {
+        if let Symbol::Process { key, .. } = s.symbol {
+            *keys.entry(key).or_default() += 1;
+        }
+    }
+
+    println!(
+        "{:<18}  {:>8}  {:>5}  {:<24}  STATIC",
+        "ADDRESS", "SIZE", "ALIGN", "SECTION"
+    );
+    let mut total = 0;
+    for s in statics {
+        let size = match s.size {
+            Some(size) => size.to_string(),
+            None => format!("<={}", s.max_size),
+        };
+        total += s.size.unwrap_or(s.max_size);
+
+        let (align, duplicate) = match s.symbol {
+            Symbol::Process { key, align, .. } => (align.to_string(), keys[&key] > 1),
+            _ => ("?".to_owned(), false),
+        };
+        let mut name = match (&s.name, &s.symbol) {
+            (Some((namespace, ty)), _) => format!("{ty} in {namespace}"),
+            (None, Symbol::Process { key, .. }) => format!("process key {key:#x}"),
+            (None, Symbol::Crate { demangled }) => demangled.clone(),
+            (None, Symbol::Named { .. }) => unreachable!(),
+        };
+        if duplicate {
+            name.push_str(" (duplicate)");
+        }
+
+        println!(
+            "{:#018x}  {size:>8}  {align:>5}  {:<24}  {name}",
+            s.addr, s.section
+        );
+    }
+    println!("{} statics, {total} bytes", statics.len());
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1).peekable();
+    // `cargo generic-statics` passes the subcommand name.
+    if args.peek().map(String::as_str) == Some("generic-statics") {
+        args.next();
+    }
+
+    let paths = args.collect::<Vec<_>>();
+    if paths.is_empty() {
+        eprintln!("usage: cargo generic-statics <BINARY>...");
+        return ExitCode::from(2);
+    }
+
+    let mut code = ExitCode::SUCCESS;
+    for path in &paths {
+        let result = fs::read(path)
+            .map_err(|err| err.to_string())
+            .and_then(|data| statics(&data));
+        match result {
+            Ok(statics) => {
+                if paths.len() > 1 {
+                    println!("{path}:");
+                }
+                print(&statics);
+            }
+            Err(err) => {
+                eprintln!("error: {path}: {err}");
+                code = ExitCode::FAILURE;
+            }
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_symbol, split_crate_static, statics, Symbol};
+
+    #[test]
+    fn symbols() {
+        assert_eq!(
+            parse_symbol("__generic_statics.1234.24.3"),
+            Some(Symbol::Process {
+                key: 1234,
+                size: 24,
+                align: 8
+            })
+        );
+        assert_eq!(
+            parse_symbol("___generic_statics.1234.1.0"),
+            Some(Symbol::Process {
+                key: 1234,
+                size: 1,
+                align: 1
+            })
+        );
+        assert_eq!(parse_symbol("__generic_statics.1234.24"), None);
+        assert_eq!(parse_symbol("main"), None);
+        assert_eq!(
+            parse_symbol(
+                "_RINvNtCs1234_15generic_statics3asm14generic_staticNtCs5678_3app4TestmEB6_.\
+                 generic_static"
+            ),
+            Some(Symbol::Named {
+                namespace: "app::Test".to_owned(),
+                ty: "u32".to_owned()
+            })
+        );
+
+        assert_eq!(
+            split_crate_static(
+                "generic_statics::asm::crate_static::<app::Test, (u8, alloc::vec::Vec<u32>)>"
+            ),
+            Some(("app::Test", "(u8, alloc::vec::Vec<u32>)"))
+        );
+        assert_eq!(
+            split_crate_static("generic_statics::asm::crate_static"),
+            None
+        );
+    }
+
+    #[test]
+    fn current_exe() {
+        use generic_statics::{define_namespace, Namespace};
+
+        define_namespace!(
+            #[unsafe(identity(process))]
+            Test
+        );
+
+        let _ = Test::generic_static::<[u64; 3]>();
+
+        let data = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let statics = statics(&data).unwrap();
+        let found = statics
+            .iter()
+            .find(|s| s.size == Some(24) && matches!(s.symbol, Symbol::Process { align: 8, .. }))
+            .expect("the static wasn't found");
+        // The name depends on the symbol mangling version.
+        if let Some((namespace, ty)) = &found.name {
+            assert!(namespace.ends_with("::Test"), "{namespace}");
+            assert_eq!(ty, "[u64; 3]");
+        }
+    }
+}
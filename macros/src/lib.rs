@@ -0,0 +1,181 @@
+//! Procedural macros for [`generic-statics`](https://docs.rs/generic-statics), enabled with its
+//! `macros` feature. Use them through the re-exports of `generic-statics`.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote, Attribute, Error, GenericParam, Ident, ItemMod, ReturnType,
+    Signature, Token, Type, Visibility,
+};
+
+#[proc_macro_attribute]
+pub fn namespace(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let name = if attr.is_empty() {
+        Ident::new("Statics", proc_macro2::Span::call_site())
+    } else {
+        parse_macro_input!(attr as Ident)
+    };
+    let mut module = parse_macro_input!(item as ItemMod);
+
+    let Some((_, items)) = &mut module.content else {
+        return Error::new_spanned(&module, "`#[namespace]` requires an inline module")
+            .to_compile_error()
+            .into();
+    };
+
+    let doc = format!(
+        "The namespace of the generic statics of the `{}` module.",
+        module.ident
+    );
+    items.insert(
+        0,
+        parse_quote! {
+            ::generic_statics::define_namespace!(
+                #[doc = #doc]
+                pub(crate) #name
+            );
+        },
+    );
+    items.insert(
+        1,
+        parse_quote! {
+            /// Returns the generic static of `T` in the namespace of this module.
+            #[allow(dead_code)]
+            #[inline]
+            pub(crate) fn statics<T: 'static + ::generic_statics::Zeroable + ::core::marker::Sync>(
+            ) -> &'static T {
+                <#name as ::generic_statics::Namespace>::generic_static::<T>()
+            }
+        },
+    );
+
+    quote!(#module).into()
+}
+
+/// A function declaration without a body.
+struct FnDecl {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    sig: Signature,
+}
+
+impl Parse for FnDecl {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let sig = input.parse()?;
+        input.parse::<Token![;]>()?;
+        Ok(Self { attrs, vis, sig })
+    }
+}
+
+#[proc_macro_attribute]
+pub fn generic_static_accessor(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let namespace = parse_macro_input!(attr as Type);
+    let FnDecl {
+        attrs,
+        vis,
+        mut sig,
+    } = parse_macro_input!(item as FnDecl);
+
+    if !sig.inputs.is_empty() || sig.variadic.is_some() {
+        return Error::new_spanned(&sig.inputs, "generic static accessors can't have arguments")
+            .to_compile_error()
+            .into();
+    }
+    if let Some(token) = sig
+        .constness
+        .as_ref()
+        .map(|c| c.span)
+        .or(sig.asyncness.map(|a| a.span))
+    {
+        return Error::new(
+            token,
+            "generic static accessors can't be `const` or `async`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let ty = match &sig.output {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Reference(reference)
+                if reference.mutability.is_none()
+                    && reference
+                        .lifetime
+                        .as_ref()
+                        .is_some_and(|lifetime| lifetime.ident == "static") =>
+            {
+                (*reference.elem).clone()
+            }
+            _ => {
+                return Error::new_spanned(ty, "expected a `&'static` return type")
+                    .to_compile_error()
+                    .into()
+            }
+        },
+        ReturnType::Default => {
+            return Error::new_spanned(&sig, "expected a `&'static` return type")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    if let Some(lifetime) = sig.generics.lifetimes().next() {
+        return Error::new_spanned(lifetime, "generic static accessors can't have lifetimes")
+            .to_compile_error()
+            .into();
+    }
+
+    // The static is keyed by a marker type with the generic parameters of the accessor, so every
+    // accessor (and instantiation) has its own static even if the type of the static doesn't
+    // mention all generic parameters.
+    let marker = &sig.ident;
+    let mut marker_params = Vec::new();
+    let mut marker_args = Vec::new();
+    let mut marker_types = Vec::new();
+    for param in sig.generics.params.iter() {
+        match param {
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                marker_params.push(quote!(#ident: ?::core::marker::Sized));
+                marker_args.push(quote!(#ident));
+                marker_types.push(quote!(fn() -> *const #ident));
+            }
+            GenericParam::Const(param) => {
+                let (ident, ty) = (&param.ident, &param.ty);
+                marker_params.push(quote!(const #ident: #ty));
+                marker_args.push(quote!(#ident));
+            }
+            GenericParam::Lifetime(_) => unreachable!(),
+        }
+    }
+
+    for param in sig.generics.type_params_mut() {
+        param.bounds.push(parse_quote!('static));
+    }
+    sig.generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote! {
+            #ty: 'static + ::generic_statics::Zeroable + ::core::marker::Sync
+        });
+
+    quote! {
+        #(#attrs)*
+        #[inline]
+        #vis #sig {
+            #[allow(non_camel_case_types)]
+            struct #marker<#(#marker_params),*>(
+                ::core::marker::PhantomData<(#(#marker_types,)*)>,
+            );
+
+            <#namespace as ::generic_statics::Namespace>::generic_static_keyed::<
+                #ty,
+                #marker<#(#marker_args),*>,
+            >()
+        }
+    }
+    .into()
+}
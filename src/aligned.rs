@@ -0,0 +1,115 @@
+//! Over-aligned statics, see
+//! [`Namespace::generic_static_aligned`](crate::Namespace::generic_static_aligned).
+//!
+//! Rust can't derive `#[repr(align)]` from a const parameter, so [`Aligned`] gets its alignment
+//! from a zero-length array of a marker type, which [`Alignment`] maps every supported alignment
+//! to.
+
+use crate::Zeroable;
+
+/// The largest page size of the target: 16 KiB on Apple `aarch64` targets, 64 KiB on other
+/// `aarch64` targets (whose kernels can be configured with 4, 16 or 64 KiB pages) and 4 KiB
+/// otherwise.
+///
+/// See [`Namespace::generic_static_page_aligned`](crate::Namespace::generic_static_page_aligned).
+#[cfg(all(
+    target_arch = "aarch64",
+    any(target_os = "macos", target_os = "ios", target_os = "tvos")
+))]
+pub const MAX_PAGE_SIZE: usize = 1 << 14;
+#[cfg(all(
+    target_arch = "aarch64",
+    not(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "tvos",
+        target_os = "windows"
+    ))
+))]
+pub const MAX_PAGE_SIZE: usize = 1 << 16;
+#[cfg(not(all(target_arch = "aarch64", not(target_os = "windows"))))]
+pub const MAX_PAGE_SIZE: usize = 1 << 12;
+
+/// An alignment in bytes, the supported ones implement [`Alignment`].
+///
+/// See [`Namespace::generic_static_aligned`](crate::Namespace::generic_static_aligned).
+#[derive(Copy, Clone, Debug)]
+pub struct Align<const N: usize>;
+
+/// Implemented by [`Align`] for powers of two up to 64 KiB.
+///
+/// The storage of generic statics supports alignments up to 64 KiB on ELF targets, 32 KiB on
+/// Apple targets and 4 KiB on Windows (see the [caveats](crate#caveats-and-limitations)), larger
+/// ones fail to compile.
+pub trait Alignment: sealed::Sealed {
+    /// A zero-sized type with the alignment.
+    #[doc(hidden)]
+    type Marker: Copy + Send + Sync + 'static;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+mod markers {
+    macro_rules! markers {
+        ($($name:ident = $align:literal),* $(,)?) => {
+            $(
+                #[repr(align($align))]
+                #[derive(Copy, Clone)]
+                pub struct $name;
+
+                impl super::sealed::Sealed for super::Align<$align> {}
+
+                impl super::Alignment for super::Align<$align> {
+                    type Marker = $name;
+                }
+            )*
+        };
+    }
+
+    markers!(
+        A1 = 1,
+        A2 = 2,
+        A4 = 4,
+        A8 = 8,
+        A16 = 16,
+        A32 = 32,
+        A64 = 64,
+        A128 = 128,
+        A256 = 256,
+        A512 = 512,
+        A1K = 1024,
+        A2K = 2048,
+        A4K = 4096,
+        A8K = 8192,
+        A16K = 16384,
+        A32K = 32768,
+        A64K = 65536,
+    );
+}
+
+/// Storage of `T` aligned to at least `ALIGN` bytes, so it doesn't share the storage of `T`.
+///
+/// The size is rounded up to a multiple of the alignment.
+#[repr(C)]
+pub(crate) struct Aligned<T, const ALIGN: usize>
+where
+    Align<ALIGN>: Alignment,
+{
+    _align: [<Align<ALIGN> as Alignment>::Marker; 0],
+    pub(crate) value: T,
+}
+
+unsafe impl<T: Zeroable, const ALIGN: usize> Zeroable for Aligned<T, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            _align: [],
+            value: T::zeroed(),
+        }
+    }
+}
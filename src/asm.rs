@@ -0,0 +1,1155 @@
+use std::{
+    any::{self, TypeId},
+    mem,
+    ptr::NonNull,
+};
+
+use crate::{
+    hash::{hash, SEED},
+    init::Storage,
+    Identity, Namespace,
+};
+#[cfg(feature = "storage-range")]
+use std::ptr;
+
+const fn cmp_max(a: usize, b: usize) -> usize {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+/// The name of the symbol labeling the storage, and of the symbol naming it after
+/// [`generic_static`] (see [`reserve`]).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __storage {
+    (process) => {
+        "__generic_statics.{key}.{size}.{align}"
+    };
+    (crate) => {
+        "{id}.generic_static"
+    };
+    (named) => {
+        "{name}.generic_static"
+    };
+}
+
+/// Records the storage labeled with `$sym` in the startup verification registry under `$key`
+/// (see [`crate::verify`]).
+#[cfg(feature = "verify-at-startup")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register {
+    (elf, [$($sym:tt)*], [$($key:tt)*]) => {
+        ::core::concat!(
+            ".pushsection generic_statics_registry,\"awoG\",@progbits,",
+            $($sym)*,
+            ",",
+            $($sym)*,
+            ",comdat\n",
+            ".p2align 3\n",
+            ".quad ", $($key)*, "\n",
+            ".quad ", $($sym)*, "\n",
+            ".popsection",
+        )
+    };
+    (macho, [$($sym:tt)*], [$($key:tt)*]) => {
+        ::core::concat!(
+            ".pushsection __DATA,__gs_registry,regular,no_dead_strip\n",
+            ".p2align 3\n",
+            ".quad ", $($key)*, "\n",
+            ".quad ", $($sym)*, "\n",
+            ".popsection",
+        )
+    };
+    (coff, [$($sym:tt)*], [$($key:tt)*]) => {
+        ::core::concat!(
+            ".pushsection .gsreg$m,\"dr\",associative,",
+            $($sym)*,
+            "\n",
+            ".p2align 3\n",
+            ".quad ", $($key)*, "\n",
+            ".quad ", $($sym)*, "\n",
+            ".popsection",
+        )
+    };
+}
+
+#[cfg(not(feature = "verify-at-startup"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register {
+    ($($tt:tt)*) => {
+        ""
+    };
+}
+
+/// The canary after the storage labeled with `$sym` and its entry in the canary section (see
+/// [`crate::canaries`]).
+///
+/// The canary directly follows the data of the storage (which may be longer than its size, see
+/// [`__data`]), so it isn't aligned.
+#[cfg(feature = "canaries")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __canary {
+    (elf, [$($sym:tt)*]) => {
+        ::core::concat!(
+            "2: .zero 8\n",
+            ".pushsection generic_statics_canaries,\"awoG\",@progbits,",
+            $($sym)*,
+            ",",
+            $($sym)*,
+            ",comdat\n",
+            $crate::__canary!(@entry [$($sym)*]),
+        )
+    };
+    (macho, [$($sym:tt)*]) => {
+        ::core::concat!(
+            "2: .zero 8\n",
+            ".pushsection __DATA,__gs_canaries,regular,no_dead_strip\n",
+            $crate::__canary!(@entry [$($sym)*]),
+        )
+    };
+    (coff, [$($sym:tt)*]) => {
+        ::core::concat!(
+            "2: .zero 8\n",
+            ".pushsection .gscan$m,\"dr\",associative,",
+            $($sym)*,
+            "\n",
+            $crate::__canary!(@entry [$($sym)*]),
+        )
+    };
+    (@entry [$($sym:tt)*]) => {
+        ::core::concat!(
+            ".p2align 3\n",
+            ".quad ", $($sym)*, "\n",
+            ".quad 2b\n",
+            ".popsection",
+        )
+    };
+}
+
+#[cfg(not(feature = "canaries"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __canary {
+    ($($tt:tt)*) => {
+        ""
+    };
+}
+
+/// The entry of the storage labeled with `$sym` in the budget section of its namespace, if it
+/// has a [budget](Namespace::BUDGET) (the `budget` operand is the budget plus one).
+///
+/// The entry is as large as the storage (at least 4 bytes) and starts with an absolute 32-bit
+/// relocation referring to its end. The budget section isn't allocated, so the linker resolves
+/// the relocation to the offset of the end in the concatenated section, i.e. the size of all
+/// entries up to and including this one. The addend makes the offsets beyond the budget
+/// overflow the relocation, which fails the link. Budgets are only enforced on ELF.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __budget {
+    (elf, [$($sym:tt)*]) => {
+        ::core::concat!(
+            ".if {budget}\n",
+            ".pushsection generic_statics_budget_{ns},\"oG\",@progbits,",
+            $($sym)*,
+            ",",
+            $($sym)*,
+            ",comdat\n",
+            ".long 5f + (0x100000000 - {budget})\n",
+            ".if {size} > 4\n",
+            ".zero {size} - 4\n",
+            ".endif\n",
+            "5:\n",
+            ".popsection\n",
+            ".endif",
+        )
+    };
+    ($format:ident, [$($sym:tt)*]) => {
+        "/* the budget ({budget}) of namespace {ns} isn't enforced */"
+    };
+}
+
+/// The definition of the local symbol `$name` naming the storage (see [`__emit`]), if any.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __named {
+    ($format:ident, []) => {
+        ""
+    };
+    (elf, [$($name:tt)+]) => {
+        ::core::concat!(
+            ".type ", $($name)+, ", @object\n",
+            ".size ", $($name)+, ", {size}\n",
+            $($name)+, ":",
+        )
+    };
+    (coff, [$($name:tt)+]) => {
+        ::core::concat!($($name)+, ":")
+    };
+    // The linker would split the storage at the symbol.
+    (macho, [$($name:tt)+]) => {
+        ::core::concat!("/* ", $($name)+, " */")
+    };
+}
+
+/// The section directive for storage of `$kind` in `$section`, which is either `default` or the
+/// name of a custom section (see [`__emit`]).
+///
+/// Zeroed and uninitialized storage is emitted into custom sections as zero-fill section, storage
+/// with an initial value as regular section.
+///
+/// On COFF, the default sections are grouped sections (`.bss$gs` and `.data$gs`), which the linker
+/// merges into `.bss` and `.data` after the ungrouped sections of the same name, ordered by the
+/// suffix after the `$`. Sections without a `$` are placed into an image section of their own,
+/// and would be truncated to 8 characters by `link.exe` (see [`crate::__check_section`]).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __section {
+    (elf, (zero), [$($sym:tt)*], default) => {
+        ::core::concat!(
+            ".pushsection .bss.", $($sym)*, ",\"awG\",@nobits,", $($sym)*, ",comdat"
+        )
+    };
+    (elf, (init $($i:literal)*), [$($sym:tt)*], default) => {
+        ::core::concat!(
+            ".pushsection .data.", $($sym)*, ",\"awG\",@progbits,", $($sym)*, ",comdat"
+        )
+    };
+    (elf, (noinit), [$($sym:tt)*], default) => {
+        ::core::concat!(
+            ".pushsection .noinit.", $($sym)*, ",\"awG\",@nobits,", $($sym)*, ",comdat"
+        )
+    };
+    (elf, (range), [$($sym:tt)*], default) => {
+        ::core::concat!(".pushsection generic_statics_{ns},\"awG\",@nobits,", $($sym)*, ",comdat")
+    };
+    (macho, (zero), [$($sym:tt)*], default) => {
+        ".pushsection __DATA,__bss,zerofill"
+    };
+    (macho, (noinit), [$($sym:tt)*], default) => {
+        $crate::__section!(macho, (zero), [$($sym)*], default)
+    };
+    (macho, (range), [$($sym:tt)*], default) => {
+        ".pushsection __DATA,__gs{ns},zerofill"
+    };
+    (macho, (init $($i:literal)*), [$($sym:tt)*], default) => {
+        ".pushsection __DATA,__data"
+    };
+    (coff, (zero), [$($sym:tt)*], default) => {
+        ::core::concat!(".pushsection .bss$gs,\"bw\",discard,", $($sym)*)
+    };
+    (coff, (noinit), [$($sym:tt)*], default) => {
+        $crate::__section!(coff, (zero), [$($sym)*], default)
+    };
+    (coff, (range), [$($sym:tt)*], default) => {
+        ::core::concat!(".pushsection .bss$gs{ns}m,\"bw\",discard,", $($sym)*)
+    };
+    (coff, (init $($i:literal)*), [$($sym:tt)*], default) => {
+        ::core::concat!(".pushsection .data$gs,\"dw\",discard,", $($sym)*)
+    };
+    (elf, (init $($i:literal)*), [$($sym:tt)*], $section:literal) => {
+        ::core::concat!(".pushsection ", $section, ",\"awG\",@progbits,", $($sym)*, ",comdat")
+    };
+    (elf, $kind:tt, [$($sym:tt)*], $section:literal) => {
+        ::core::concat!(".pushsection ", $section, ",\"awG\",@nobits,", $($sym)*, ",comdat")
+    };
+    (macho, (init $($i:literal)*), [$($sym:tt)*], $section:literal) => {
+        ::core::concat!(".pushsection ", $section)
+    };
+    (macho, $kind:tt, [$($sym:tt)*], $section:literal) => {
+        ::core::concat!(".pushsection ", $section, ",zerofill")
+    };
+    (coff, (init $($i:literal)*), [$($sym:tt)*], $section:literal) => {
+        ::core::concat!(".pushsection ", $section, ",\"dw\",discard,", $($sym)*)
+    };
+    (coff, $kind:tt, [$($sym:tt)*], $section:literal) => {
+        ::core::concat!(".pushsection ", $section, ",\"bw\",discard,", $($sym)*)
+    };
+}
+
+/// The definition of storage of `$kind` (see [`__emit`]).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __data {
+    ((zero), [$($sym:tt)*]) => {
+        ::core::concat!($($sym)*, ": .zero {size}")
+    };
+    ((noinit), [$($sym:tt)*]) => {
+        $crate::__data!((zero), [$($sym)*])
+    };
+    ((range), [$($sym:tt)*]) => {
+        $crate::__data!((zero), [$($sym)*])
+    };
+    ((init $($i:literal)*), [$($sym:tt)*]) => {
+        ::core::concat!(
+            $($sym)*,
+            ": /* {size} bytes */\n",
+            $(".if ", $i, " < {words}\n.quad {", $i, "}\n.endif\n",)*
+        )
+    };
+}
+
+/// Emits the storage labeled with the symbol `$sym` and stores its address in `$addr`.
+///
+/// The storage is either zeroed (`$kind` is `(zero)`), initialized with the words passed as
+/// positional operands (`$kind` is `(init 0 1 ..)`, see [`__emit_init`]) or left uninitialized
+/// (`$kind` is `(noinit)`, which is zeroed on targets without a no-init section). Zeroed storage
+/// can also be emitted into the section of its namespace (`$kind` is `(range)`, see
+/// [`storage_range`]), which requires an `ns` operand. The storage is emitted into `$section`, see
+/// [`__section`].
+///
+/// The storage is registered for startup verification under `$key` (`0` opts out), and followed
+/// by a canary with the `canaries` feature. Storage of namespaces with a budget is accounted in
+/// the budget section of its namespace, see [`__budget`]. Storage of [weak](Namespace::WEAK)
+/// namespaces (the `weak` operand is `1`) has no canary, and is emitted as weak external on COFF,
+/// whose default is the storage labeled `$sym.default`.
+///
+/// On ELF and COFF, the storage is also labeled with the local symbol `$name` (if not empty), see
+/// [`reserve`].
+///
+/// `$operand`s are passed through to [`std::arch::asm`] and must provide the `weak`, `budget`
+/// and `ns` operands and the operands referenced by `$sym` and `$key`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __emit {
+    (
+        $addr:ident,
+        [$($sym:tt)*],
+        [$($key:tt)*],
+        [$($name:tt)*],
+        $kind:tt,
+        $section:tt,
+        $($operand:tt)*
+    ) => {
+        #[cfg(all(
+            target_arch = "aarch64",
+            any(target_os = "macos", target_os = "ios", target_os = "tvos")
+        ))]
+        unsafe {
+            ::core::arch::asm!(
+                ::core::concat!("adrp {x}, ", $($sym)*, "@PAGE"),
+                ::core::concat!("add {x}, {x}, ", $($sym)*, "@PAGEOFF"),
+                ::core::concat!(".ifndef ", $($sym)*),
+                $crate::__section!(macho, $kind, [$($sym)*], $section),
+                ::core::concat!(".globl ", $($sym)*),
+                ::core::concat!(".weak_definition ", $($sym)*),
+                ::core::concat!(".private_extern ", $($sym)*),
+                ".p2align {align}, 0",
+                $crate::__named!(macho, [$($name)*]),
+                $crate::__data!($kind, [$($sym)*]),
+                ".if {weak} == 0",
+                $crate::__canary!(macho, [$($sym)*]),
+                ".endif",
+                ".popsection",
+                $crate::__register!(macho, [$($sym)*], [$($key)*]),
+                $crate::__budget!(macho, [$($sym)*]),
+                ".endif",
+                $($operand)*
+                x = out(reg) $addr,
+                options(pure, nomem, nostack, preserves_flags)
+            );
+        }
+
+        #[cfg(all(
+            target_arch = "aarch64",
+            any(target_os = "none", target_os = "linux", target_os = "freebsd")
+        ))]
+        unsafe {
+            ::core::arch::asm!(
+                ::core::concat!("adrp {x}, ", $($sym)*),
+                ::core::concat!("add {x}, {x}, :lo12:", $($sym)*),
+                ::core::concat!(".ifndef ", $($sym)*),
+                $crate::__section!(elf, $kind, [$($sym)*], $section),
+                ::core::concat!(".weak ", $($sym)*),
+                ::core::concat!(".hidden ", $($sym)*),
+                ::core::concat!(".type ", $($sym)*, ", @object"),
+                ::core::concat!(".size ", $($sym)*, ", {size}"),
+                ".p2align {align}, 0",
+                $crate::__named!(elf, [$($name)*]),
+                $crate::__data!($kind, [$($sym)*]),
+                ".if {weak} == 0",
+                $crate::__canary!(elf, [$($sym)*]),
+                ".endif",
+                ".popsection",
+                $crate::__register!(elf, [$($sym)*], [$($key)*]),
+                $crate::__budget!(elf, [$($sym)*]),
+                ".endif",
+                $($operand)*
+                x = out(reg) $addr,
+                options(pure, nomem, nostack, preserves_flags)
+            );
+        }
+
+        #[cfg(all(
+            target_arch = "x86_64",
+            any(target_os = "macos", target_os = "ios", target_os = "tvos")
+        ))]
+        unsafe {
+            ::core::arch::asm!(
+                ::core::concat!("lea {x}, [rip + ", $($sym)*, "]"),
+                ::core::concat!(".ifndef ", $($sym)*),
+                $crate::__section!(macho, $kind, [$($sym)*], $section),
+                ::core::concat!(".globl ", $($sym)*),
+                ::core::concat!(".weak_definition ", $($sym)*),
+                ::core::concat!(".private_extern ", $($sym)*),
+                ".p2align {align}, 0",
+                $crate::__named!(macho, [$($name)*]),
+                $crate::__data!($kind, [$($sym)*]),
+                ".if {weak} == 0",
+                $crate::__canary!(macho, [$($sym)*]),
+                ".endif",
+                ".popsection",
+                $crate::__register!(macho, [$($sym)*], [$($key)*]),
+                $crate::__budget!(macho, [$($sym)*]),
+                ".endif",
+                $($operand)*
+                x = out(reg) $addr,
+                options(pure, nomem, nostack, preserves_flags)
+            );
+        }
+
+        #[cfg(all(
+            target_arch = "x86_64",
+            any(target_os = "none", target_os = "linux", target_os = "freebsd")
+        ))]
+        unsafe {
+            ::core::arch::asm!(
+                ::core::concat!("lea {x}, [rip + ", $($sym)*, "]"),
+                ::core::concat!(".ifndef ", $($sym)*),
+                $crate::__section!(elf, $kind, [$($sym)*], $section),
+                ::core::concat!(".weak ", $($sym)*),
+                ::core::concat!(".hidden ", $($sym)*),
+                ::core::concat!(".type ", $($sym)*, ", @object"),
+                ::core::concat!(".size ", $($sym)*, ", {size}"),
+                ".p2align {align}, 0",
+                $crate::__named!(elf, [$($name)*]),
+                $crate::__data!($kind, [$($sym)*]),
+                ".if {weak} == 0",
+                $crate::__canary!(elf, [$($sym)*]),
+                ".endif",
+                ".popsection",
+                $crate::__register!(elf, [$($sym)*], [$($key)*]),
+                $crate::__budget!(elf, [$($sym)*]),
+                ".endif",
+                $($operand)*
+                x = out(reg) $addr,
+                options(pure, nomem, nostack, preserves_flags)
+            );
+        }
+
+        // The Intel syntax drops the `$` of grouped sections (see `reserve`).
+        #[cfg(all(target_arch = "x86_64", target_os = "windows"))]
+        unsafe {
+            ::core::arch::asm!(
+                ::core::concat!("lea ", $($sym)*, "(%rip), {x}"),
+                ::core::concat!(".ifndef ", $($sym)*),
+                ".if {weak}",
+                $crate::__section!(coff, $kind, [$($sym)*, ".default"], $section),
+                ::core::concat!(".weak ", $($sym)*),
+                ::core::concat!($($sym)*, " = ", $($sym)*, ".default"),
+                ::core::concat!(".globl ", $($sym)*, ".default"),
+                ".p2align {align}, 0",
+                $crate::__named!(coff, [$($name)*]),
+                $crate::__data!($kind, [$($sym)*, ".default"]),
+                ".else",
+                $crate::__section!(coff, $kind, [$($sym)*], $section),
+                ::core::concat!(".globl ", $($sym)*),
+                ".p2align {align}, 0",
+                $crate::__named!(coff, [$($name)*]),
+                $crate::__data!($kind, [$($sym)*]),
+                $crate::__canary!(coff, [$($sym)*]),
+                ".endif",
+                ".popsection",
+                $crate::__register!(coff, [$($sym)*], [$($key)*]),
+                $crate::__budget!(coff, [$($sym)*]),
+                ".endif",
+                $($operand)*
+                x = out(reg) $addr,
+                options(att_syntax, pure, nomem, nostack, preserves_flags)
+            );
+        }
+    };
+}
+
+/// The flags of the descriptor section, which is either `dependent` on the storage labeled with
+/// `$sym` (see [`reserve`]) or `retained`.
+///
+/// Descriptors can only depend on storage defined before in the same object file, which isn't
+/// the case if [`describe`] wasn't inlined together with [`reserve`].
+#[cfg(feature = "introspection")]
+macro_rules! descriptors_flags {
+    (elf, dependent, [$($sym:tt)*]) => {
+        concat!(",\"awoG\",@progbits,", $($sym)*, ",", $($sym)*, ",comdat")
+    };
+    (elf, retained, [$($sym:tt)*]) => {
+        concat!(",\"awRG\",@progbits,", $($sym)*, ",comdat")
+    };
+    (macho, $dependency:ident, [$($sym:tt)*]) => {
+        ",regular,no_dead_strip"
+    };
+    (coff, dependent, [$($sym:tt)*]) => {
+        concat!(",\"dr\",associative,", $($sym)*)
+    };
+    (coff, retained, [$($sym:tt)*]) => {
+        ",\"dr\""
+    };
+}
+
+/// The section directive for the descriptor of the storage labeled with `$sym` (see
+/// [`describe`] and [`descriptors_flags`]).
+#[cfg(all(feature = "introspection", not(feature = "linkme")))]
+macro_rules! descriptors_section {
+    (elf, $dependency:ident, [$($sym:tt)*]) => {
+        concat!(
+            ".pushsection generic_statics_descriptors",
+            descriptors_flags!(elf, $dependency, [$($sym)*]),
+        )
+    };
+    (freebsd, $dependency:ident, [$($sym:tt)*]) => {
+        descriptors_section!(elf, $dependency, [$($sym)*])
+    };
+    (macho, $dependency:ident, [$($sym:tt)*]) => {
+        concat!(
+            ".pushsection __DATA,__gs_descs",
+            descriptors_flags!(macho, $dependency, [$($sym)*]),
+        )
+    };
+    (coff, $dependency:ident, [$($sym:tt)*]) => {
+        concat!(
+            ".pushsection .gsdesc$m",
+            descriptors_flags!(coff, $dependency, [$($sym)*]),
+        )
+    };
+}
+
+/// The section directive for the descriptor of the storage labeled with `$sym`, which are the
+/// elements of [`crate::GENERIC_STATICS`] with the `linkme` feature.
+///
+/// These are the section names used by `linkme` for a distributed slice named `GENERIC_STATICS`.
+/// On Mach-O, the name contains a hash of the slice's name.
+#[cfg(feature = "linkme")]
+macro_rules! descriptors_section {
+    (elf, $dependency:ident, [$($sym:tt)*]) => {
+        concat!(
+            ".pushsection linkme_GENERIC_STATICS",
+            descriptors_flags!(elf, $dependency, [$($sym)*]),
+        )
+    };
+    (freebsd, $dependency:ident, [$($sym:tt)*]) => {
+        concat!(
+            ".pushsection set_linkme_GENERIC_STATICS",
+            descriptors_flags!(elf, $dependency, [$($sym)*]),
+        )
+    };
+    (macho, $dependency:ident, [$($sym:tt)*]) => {
+        concat!(
+            ".pushsection __DATA,__linkmeAVLg3GAU",
+            descriptors_flags!(macho, $dependency, [$($sym)*]),
+        )
+    };
+    (coff, $dependency:ident, [$($sym:tt)*]) => {
+        concat!(
+            ".pushsection .linkme_GENERIC_STATICS$b",
+            descriptors_flags!(coff, $dependency, [$($sym)*]),
+        )
+    };
+}
+
+/// Emits the descriptor of the storage labeled with `$sym` into the descriptor section (see
+/// [`crate::introspect`]), once per object file.
+///
+/// `$operand`s must provide the operands referenced by `$sym` and the `describe` function.
+#[cfg(feature = "introspection")]
+macro_rules! describe {
+    ([$($sym:tt)*], $($operand:tt)*) => {
+        describe!(
+            @emit [any(target_os = "none", target_os = "linux")], elf, [], [$($sym)*], $($operand)*
+        );
+        describe!(@emit [target_os = "freebsd"], freebsd, [], [$($sym)*], $($operand)*);
+        describe!(
+            @emit
+            [any(target_os = "macos", target_os = "ios", target_os = "tvos")],
+            macho,
+            [],
+            [$($sym)*],
+            $($operand)*
+        );
+        // The Intel syntax drops the `$` of grouped sections (see `reserve`).
+        describe!(@emit [target_os = "windows"], coff, [att_syntax,], [$($sym)*], $($operand)*);
+    };
+    (
+        @emit [$($cfg:tt)*], $format:ident, [$($option:ident,)*], [$($sym:tt)*],
+        $($operand:tt)*
+    ) => {
+        #[cfg($($cfg)*)]
+        unsafe {
+            std::arch::asm!(
+                concat!(".ifndef ", $($sym)*, ".desc"),
+                concat!(".ifdef ", $($sym)*),
+                descriptors_section!($format, dependent, [$($sym)*]),
+                ".else",
+                descriptors_section!($format, retained, [$($sym)*]),
+                ".endif",
+                ".p2align 3",
+                concat!($($sym)*, ".desc:"),
+                ".quad {describe}",
+                concat!(".quad ", $($sym)*),
+                ".popsection",
+                ".endif",
+                $($operand)*
+                options($($option,)* nomem, nostack, preserves_flags)
+            );
+        }
+    };
+}
+
+/// Invokes [`__emit`] for storage initialized with [`Storage::WORDS`] of `$storage`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __emit_init {
+    ($addr:ident, $sym:tt, $key:tt, $name:tt, $section:tt, $storage:ty, $($operand:tt)*) => {
+        $crate::__emit_init!(
+            @[
+                0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30
+                31 32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57
+                58 59 60 61 62 63
+            ]
+            $addr, $sym, $key, $name, $section, $storage, $($operand)*
+        )
+    };
+    (
+        @[$($i:literal)*]
+        $addr:ident, $sym:tt, $key:tt, $name:tt, $section:tt, $storage:ty, $($operand:tt)*
+    ) => {
+        $crate::__emit!(
+            $addr,
+            $sym,
+            $key,
+            $name,
+            (init $($i)*),
+            $section,
+            $(const { <$storage as $crate::__Storage>::WORDS[$i] },)*
+            words = const { ::core::mem::size_of::<$storage>().div_ceil(8) },
+            $($operand)*
+        )
+    };
+}
+
+/// Names the storage of `T` in namespace `N` in symbol tables (see [`reserve`]).
+///
+/// This returns a value distinct for every instantiation, so the instantiations can't be merged
+/// (which would leave the storage of different instantiations with the same name).
+pub fn generic_static<N: 'static, T: 'static>() -> TypeId {
+    TypeId::of::<(N, T)>()
+}
+
+/// Labels the storage of `T` in [`Identity::Crate`] namespace `N` (see [`reserve`]).
+///
+/// `TypeId::of` is small enough to be copied into every codegen unit as local symbol, which
+/// ThinLTO renames when it's referenced from another module, so every codegen unit would label
+/// its own storage. This is instantiated once per crate instead. It returns a value distinct for
+/// every instantiation, so the instantiations can't be merged either.
+#[inline(never)]
+pub fn crate_static<N: 'static, T: 'static>() -> TypeId {
+    TypeId::of::<(N, T)>()
+}
+
+/// The key of the storage of `T` in namespace `N`, which is part of its symbol (see [`reserve`]).
+pub const fn storage_key<N: Namespace, T>() -> u64 {
+    hash(SEED ^ N::SALT, any::type_name::<(N, T)>().as_bytes())
+}
+
+/// The size of the storage of `T` (zero-sized types reserve one byte).
+pub const fn storage_size<T>() -> usize {
+    cmp_max(mem::size_of::<T>(), 1)
+}
+
+/// Whether the storage of namespace `N` is emitted as overridable weak definition (`1`) or not
+/// (`0`), see [`Namespace::WEAK`].
+pub const fn weak<N: Namespace>() -> u8 {
+    assert!(
+        !N::WEAK || matches!(N::IDENTITY, Identity::Process),
+        "weak namespaces must have `Identity::Process`"
+    );
+    N::WEAK as u8
+}
+
+/// The [budget](Namespace::BUDGET) of namespace `N` plus one, `0` without budget (see
+/// [`__budget`]).
+pub const fn budget<N: Namespace>() -> u64 {
+    match N::BUDGET {
+        Some(budget) => {
+            assert!(
+                budget < u32::MAX as usize,
+                "the budget of a namespace must be less than 4 GiB"
+            );
+            budget as u64 + 1
+        }
+        None => 0,
+    }
+}
+
+/// Reserves the storage for `$T` in namespace `$N` in `$section` (see [`__section`]) and returns
+/// its address, see [`reserve`].
+///
+/// This is exported for namespaces with a custom section, whose storage is reserved by an
+/// expansion in the crate defining the namespace, as the section name is part of the asm.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __reserve {
+    ($N:ty, $T:ty, $section:tt) => {{
+        $crate::__reserve!(@check $section);
+        #[allow(unused_assignments)]
+        let mut addr: *mut () = ::core::ptr::null_mut();
+        if <$T as $crate::__Storage>::NOINIT {
+            $crate::__reserve!(@emit addr, $N, $T, (noinit), $section);
+        } else if <$T as $crate::__Storage>::ZEROED {
+            $crate::__reserve!(@emit addr, $N, $T, (zero), $section);
+        } else {
+            $crate::__reserve!(@emit addr, $N, $T, (init), $section);
+        }
+        ::core::ptr::NonNull::new(addr.cast::<$T>()).expect("unsupported platform")
+    }};
+    (@check default) => {};
+    (@check $section:literal) => {
+        const { $crate::__check_section($section) };
+    };
+    (@emit $addr:ident, $N:ty, $T:ty, (init), $section:tt) => {
+        match <$N as $crate::Namespace>::IDENTITY {
+            $crate::Identity::Process => {
+                $crate::__emit_init!(
+                    $addr,
+                    [$crate::__storage!(process)],
+                    ["{key}"],
+                    [$crate::__storage!(named)],
+                    $section,
+                    $T,
+                    size = const { $crate::__storage_size::<$T>() },
+                    align = const { ::core::mem::align_of::<$T>().ilog2() },
+                    name = sym $crate::__generic_static::<$N, $T>,
+                    key = const { $crate::__storage_key::<$N, $T>() },
+                    weak = const { $crate::__weak::<$N>() },
+                    budget = const { $crate::__budget::<$N>() },
+                    ns = const { $crate::__namespace_key::<$N>() },
+                );
+            }
+            $crate::Identity::Crate => {
+                $crate::__emit_init!(
+                    $addr,
+                    [$crate::__storage!(crate)],
+                    ["0"],
+                    [],
+                    $section,
+                    $T,
+                    size = const { $crate::__storage_size::<$T>() },
+                    align = const { ::core::mem::align_of::<$T>().ilog2() },
+                    id = sym $crate::__crate_static::<$N, $T>,
+                    weak = const { $crate::__weak::<$N>() },
+                    budget = const { $crate::__budget::<$N>() },
+                    ns = const { $crate::__namespace_key::<$N>() },
+                );
+            }
+        }
+    };
+    (@emit $addr:ident, $N:ty, $T:ty, $kind:tt, $section:tt) => {
+        match <$N as $crate::Namespace>::IDENTITY {
+            $crate::Identity::Process => {
+                $crate::__emit!(
+                    $addr,
+                    [$crate::__storage!(process)],
+                    ["{key}"],
+                    [$crate::__storage!(named)],
+                    $kind,
+                    $section,
+                    size = const { $crate::__storage_size::<$T>() },
+                    align = const { ::core::mem::align_of::<$T>().ilog2() },
+                    name = sym $crate::__generic_static::<$N, $T>,
+                    key = const { $crate::__storage_key::<$N, $T>() },
+                    weak = const { $crate::__weak::<$N>() },
+                    budget = const { $crate::__budget::<$N>() },
+                    ns = const { $crate::__namespace_key::<$N>() },
+                );
+            }
+            $crate::Identity::Crate => {
+                $crate::__emit!(
+                    $addr,
+                    [$crate::__storage!(crate)],
+                    ["0"],
+                    [],
+                    $kind,
+                    $section,
+                    size = const { $crate::__storage_size::<$T>() },
+                    align = const { ::core::mem::align_of::<$T>().ilog2() },
+                    id = sym $crate::__crate_static::<$N, $T>,
+                    weak = const { $crate::__weak::<$N>() },
+                    budget = const { $crate::__budget::<$N>() },
+                    ns = const { $crate::__namespace_key::<$N>() },
+                );
+            }
+        }
+    };
+}
+
+/// Exports the storage of `$T` in namespace `$N` under the global symbol `$name`, see
+/// [`crate::export_generic_static`].
+///
+/// ELF and Mach-O can't alias a symbol defined in another object file, so the storage is defined
+/// here as a strong symbol labeled with `$name`, which takes precedence over the weak definitions
+/// emitted by [`reserve`]. On COFF, `$name` is a weak external aliasing the storage symbol, except
+/// for [weak](Namespace::WEAK) namespaces, whose storage is defined here as well.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export {
+    ($N:ty, $T:ty, $name:literal) => {
+        const _: () = ::core::assert!(
+            ::core::matches!(
+                <$N as $crate::Namespace>::IDENTITY,
+                $crate::Identity::Process
+            ) && <$N as $crate::Namespace>::SECTION.is_none(),
+            "only statics of `Identity::Process` namespaces in the default sections can be exported"
+        );
+
+        #[cfg(any(target_os = "none", target_os = "linux", target_os = "freebsd"))]
+        ::core::arch::global_asm!(
+            ::core::concat!(".pushsection .bss.", $crate::__storage!(process), ",\"aw\",@nobits"),
+            ::core::concat!(".globl ", $crate::__storage!(process)),
+            ::core::concat!(".hidden ", $crate::__storage!(process)),
+            ::core::concat!(".type ", $crate::__storage!(process), ", @object"),
+            ::core::concat!(".size ", $crate::__storage!(process), ", {size}"),
+            ::core::concat!(".globl ", $name),
+            ::core::concat!(".type ", $name, ", @object"),
+            ::core::concat!(".size ", $name, ", {size}"),
+            ".p2align {align}, 0",
+            ::core::concat!($crate::__storage!(process), ":"),
+            ::core::concat!($name, ": .zero {size}"),
+            ".popsection",
+            size = const $crate::__storage_size::<$T>(),
+            align = const ::core::mem::align_of::<$T>().ilog2(),
+            key = const $crate::__storage_key::<$N, $T>(),
+        );
+
+        #[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos"))]
+        ::core::arch::global_asm!(
+            ".pushsection __DATA,__bss,zerofill",
+            ::core::concat!(".globl ", $crate::__storage!(process)),
+            ::core::concat!(".private_extern ", $crate::__storage!(process)),
+            ::core::concat!(".globl _", $name),
+            // Keeps the linker from splitting the storage at the second symbol.
+            ::core::concat!(".alt_entry _", $name),
+            ".p2align {align}, 0",
+            ::core::concat!($crate::__storage!(process), ":"),
+            ::core::concat!("_", $name, ": .zero {size}"),
+            ".popsection",
+            size = const $crate::__storage_size::<$T>(),
+            align = const ::core::mem::align_of::<$T>().ilog2(),
+            key = const $crate::__storage_key::<$N, $T>(),
+        );
+
+        // The Intel syntax drops the `$` of grouped sections (see `reserve`).
+        #[cfg(all(target_arch = "x86_64", target_os = "windows"))]
+        ::core::arch::global_asm!(
+            ".if {weak}",
+            ".pushsection .bss$gs,\"bw\"",
+            ::core::concat!(".globl ", $crate::__storage!(process)),
+            ::core::concat!(".globl ", $name),
+            ".p2align {align}, 0",
+            ::core::concat!($crate::__storage!(process), ":"),
+            ::core::concat!($name, ": .zero {size}"),
+            ".popsection",
+            ".else",
+            ::core::concat!(".weak ", $name),
+            ::core::concat!($name, " = ", $crate::__storage!(process)),
+            ".endif",
+            size = const $crate::__storage_size::<$T>(),
+            align = const ::core::mem::align_of::<$T>().ilog2(),
+            key = const $crate::__storage_key::<$N, $T>(),
+            weak = const $crate::__weak::<$N>(),
+            options(att_syntax)
+        );
+    };
+}
+
+/// Reserves the static storage for `T` in namespace `N` and returns its address.
+///
+/// Every copy of this function (and of its callers) refers to the same, linker-deduplicated
+/// storage, so it can be freely inlined.
+#[inline(always)]
+pub(crate) fn reserve<N: Namespace, T: Storage>() -> NonNull<T> {
+    // The storage is labeled with a symbol that is distinct for every instantiation. The symbol
+    // is part of the asm itself, so neither the compiler (e.g. by merging instantiations) nor the
+    // linker (e.g. by identical code folding) can merge the storage of different instantiations:
+    //
+    // - `Identity::Crate` (the default): The mangled name of `crate_static::<N, T>`, which is
+    //   unique for every type and contains the instantiating crate (unless the instantiation is
+    //   shared with an upstream crate).
+    // - `Identity::Process`: A hash of the namespace's salt and the type name of `(N, T)` plus the
+    //   size and alignment of `T`, which is the same in every crate. The salt only covers the
+    //   crate defining the namespace, and type names are not unique (e.g. for the types of
+    //   semver-incompatible versions of a crate or for types defined in blocks), so this identity
+    //   is an unsafe opt-in (see `Identity::Process`).
+    //
+    // The symbol is emitted as a hidden linkonce (weak + COMDAT) definition, guarded by `.ifndef`:
+    // If this asm block ends up duplicated within an object file (e.g. due to inlining), only the
+    // first copy defines the storage, and copies in other object files (i.e. other codegen units
+    // or crates) are deduplicated by the linker. Therefore all copies resolve to the same storage.
+    //
+    // A strong definition of the symbol in another object file takes precedence over the weak
+    // definitions on ELF and Mach-O, but conflicts with the COMDAT definitions on COFF. The
+    // storage of weak namespaces (see `Namespace::WEAK`) is therefore a weak external on COFF,
+    // whose default is the COMDAT definition of `<symbol>.default`. The canary of that storage
+    // would only guard the unused default, so it's left out.
+    //
+    // On ELF and COFF, the storage of `Identity::Process` namespaces is also labeled with a local
+    // symbol named after the mangled name of `generic_static::<N, T>`, so tools like `nm`,
+    // debuggers and crash reporters can attribute it. With v0 symbol mangling, this demangles to
+    // `generic_statics::asm::generic_static::<N, T>`, i.e. it names the namespace and the type
+    // (as does the symbol of `Identity::Crate` storage). It's left out on Mach-O, where the linker
+    // would split the storage at the additional symbol.
+    //
+    // Zeroed storage is emitted into `.bss` (`__DATA,__bss` as zero-fill section on Mach-O, the
+    // grouped section `.bss$gs` on COFF), so it doesn't take up space in the binary, storage with
+    // an initial value into `.data`. On ELF, uninitialized storage is emitted into `.noinit`,
+    // which the linker script has to place into a region that isn't cleared at startup.
+    //
+    // Every storage is a section of its own (named after its symbol on ELF, like with
+    // `-fdata-sections`, e.g. `.bss.<symbol>`), or an atom of its own on Mach-O (which LLVM
+    // splits at every symbol with `.subsections_via_symbols`). So the linker's garbage collection
+    // (`--gc-sections`, `/OPT:REF` and `-dead_strip`, which rustc passes by default) drops the
+    // storage of instantiations that aren't reachable anymore after LTO. The entries emitted next
+    // to the storage (`verify-at-startup`, `canaries` and `introspection` features) depend on the
+    // storage instead of keeping it alive: They are `SHF_LINK_ORDER` sections linked to the
+    // storage on ELF and associative COMDAT sections on COFF. GNU ld keeps every section
+    // referenced by `__start_`/`__stop_` symbols (and therefore the storage) unless linked with
+    // `-z start-stop-gc`, and on Mach-O, the entries are `no_dead_strip` and keep all storage.
+    //
+    // The `.p2align` of the storage also raises the alignment of its section, which the linker
+    // honors when placing the section. On COFF, the assembler records the alignment in the
+    // `IMAGE_SCN_ALIGN_*` flags, but the linker aligns the storage relative to the start of its
+    // image section, which is only aligned to the section alignment of the image (4 KiB unless
+    // linked with `/ALIGN`). Larger alignments are rejected by `check_layout`.
+    //
+    // The asm referring to grouped COFF sections (`name$suffix`) or to Mach-O section bounds
+    // (`section$start$..`) uses the AT&T syntax on `x86_64`, as LLVM drops every `$` from asm in
+    // the Intel syntax. The entries emitted next to the storage into grouped sections are
+    // read-only, like the statics bounding them (e.g. `.gsreg$a`), as the linker only merges
+    // sections with the same flags.
+    //
+    // With the `storage-range` feature, zeroed storage is emitted into a section per namespace
+    // instead, whose bounds are returned by `storage_range`. Namespaces with a custom section
+    // emit their storage into that section instead (see `Namespace::SECTION`).
+    //
+    // The asm only computes the address of the storage (which is constant), so it's `pure` and
+    // `nomem`: LLVM hoists it out of loops, merges repeated resolutions and drops it if the address
+    // is unused. The storage is still defined by any copy of the asm that remains (see above), and
+    // isn't needed without one.
+    #[cfg(feature = "storage-range")]
+    if T::ZEROED && !T::NOINIT {
+        #[allow(unused_assignments)]
+        let mut addr: *mut () = ptr::null_mut();
+        match N::IDENTITY {
+            Identity::Process => {
+                crate::__emit!(
+                    addr,
+                    [crate::__storage!(process)],
+                    ["{key}"],
+                    [crate::__storage!(named)],
+                    (range),
+                    default,
+                    size = const { storage_size::<T>() },
+                    align = const { mem::align_of::<T>().ilog2() },
+                    name = sym generic_static::<N, T>,
+                    key = const { storage_key::<N, T>() },
+                    ns = const { namespace_key::<N>() },
+                    weak = const { weak::<N>() },
+                    budget = const { budget::<N>() },
+                );
+            }
+            Identity::Crate => {
+                crate::__emit!(
+                    addr,
+                    [crate::__storage!(crate)],
+                    ["0"],
+                    [],
+                    (range),
+                    default,
+                    size = const { storage_size::<T>() },
+                    align = const { mem::align_of::<T>().ilog2() },
+                    id = sym crate_static::<N, T>,
+                    ns = const { namespace_key::<N>() },
+                    weak = const { weak::<N>() },
+                    budget = const { budget::<N>() },
+                );
+            }
+        }
+        return NonNull::new(addr.cast::<T>()).expect("unsupported platform");
+    }
+
+    #[cfg(not(any(
+        target_os = "none",
+        target_os = "linux",
+        target_os = "freebsd",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "tvos",
+        target_os = "windows",
+    )))]
+    std::compile_error!("static-generics is not supported on this platform");
+
+    crate::__reserve!(N, T, default)
+}
+
+/// The key of the sections of namespace `N` (see [`storage_range`] and [`__budget`]).
+///
+/// This is truncated to 12 digits, as Mach-O section names have at most 16 characters.
+pub const fn namespace_key<N: Namespace>() -> u64 {
+    hash(SEED ^ N::SALT, any::type_name::<N>().as_bytes()) % 1_000_000_000_000
+}
+
+/// Returns the bounds of the section holding the zeroed storage of namespace `N` in this linked
+/// image.
+///
+/// On ELF and Mach-O, the bounds are provided by the linker. On COFF, the linker sorts the
+/// `.bss$gs{ns}*` sections by their suffix, so the storage (`m`) ends up between the (empty)
+/// sections labeled as the bounds (`a` and `z`).
+#[cfg(feature = "storage-range")]
+#[inline(always)]
+pub(crate) fn storage_range<N: Namespace>() -> std::ops::Range<*const u8> {
+    #[allow(unused_assignments)]
+    let (mut start, mut stop): (*const u8, *const u8) = (ptr::null(), ptr::null());
+
+    // The (empty) section makes sure the bounds exist even without any storage.
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_os = "none", target_os = "linux", target_os = "freebsd")
+    ))]
+    unsafe {
+        std::arch::asm!(
+            ".pushsection generic_statics_{ns},\"awR\",@nobits",
+            ".popsection",
+            ".hidden __start_generic_statics_{ns}",
+            ".hidden __stop_generic_statics_{ns}",
+            "lea {start}, [rip + __start_generic_statics_{ns}]",
+            "lea {stop}, [rip + __stop_generic_statics_{ns}]",
+            ns = const { namespace_key::<N>() },
+            start = out(reg) start,
+            stop = out(reg) stop,
+            options(pure, nomem, nostack, preserves_flags)
+        );
+    }
+
+    #[cfg(all(
+        target_arch = "aarch64",
+        any(target_os = "none", target_os = "linux", target_os = "freebsd")
+    ))]
+    unsafe {
+        std::arch::asm!(
+            ".pushsection generic_statics_{ns},\"awR\",@nobits",
+            ".popsection",
+            ".hidden __start_generic_statics_{ns}",
+            ".hidden __stop_generic_statics_{ns}",
+            "adrp {start}, __start_generic_statics_{ns}",
+            "add {start}, {start}, :lo12:__start_generic_statics_{ns}",
+            "adrp {stop}, __stop_generic_statics_{ns}",
+            "add {stop}, {stop}, :lo12:__stop_generic_statics_{ns}",
+            ns = const { namespace_key::<N>() },
+            start = out(reg) start,
+            stop = out(reg) stop,
+            options(pure, nomem, nostack, preserves_flags)
+        );
+    }
+
+    // The Intel syntax drops the `$` of the section bounds (see `reserve`).
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_os = "macos", target_os = "ios", target_os = "tvos")
+    ))]
+    unsafe {
+        std::arch::asm!(
+            ".pushsection __DATA,__gs{ns},zerofill",
+            ".popsection",
+            "lea \"section$start$__DATA$__gs{ns}\"(%rip), {start}",
+            "lea \"section$end$__DATA$__gs{ns}\"(%rip), {stop}",
+            ns = const { namespace_key::<N>() },
+            start = out(reg) start,
+            stop = out(reg) stop,
+            options(att_syntax, pure, nomem, nostack, preserves_flags)
+        );
+    }
+
+    #[cfg(all(
+        target_arch = "aarch64",
+        any(target_os = "macos", target_os = "ios", target_os = "tvos")
+    ))]
+    unsafe {
+        std::arch::asm!(
+            ".pushsection __DATA,__gs{ns},zerofill",
+            ".popsection",
+            "adrp {start}, \"section$start$__DATA$__gs{ns}\"@PAGE",
+            "add {start}, {start}, \"section$start$__DATA$__gs{ns}\"@PAGEOFF",
+            "adrp {stop}, \"section$end$__DATA$__gs{ns}\"@PAGE",
+            "add {stop}, {stop}, \"section$end$__DATA$__gs{ns}\"@PAGEOFF",
+            ns = const { namespace_key::<N>() },
+            start = out(reg) start,
+            stop = out(reg) stop,
+            options(pure, nomem, nostack, preserves_flags)
+        );
+    }
+
+    // The Intel syntax drops the `$` of the grouped sections (see `reserve`).
+    #[cfg(all(target_arch = "x86_64", target_os = "windows"))]
+    unsafe {
+        std::arch::asm!(
+            ".ifndef __generic_statics_start.{ns}",
+            ".pushsection .bss$gs{ns}a,\"bw\",discard,__generic_statics_start.{ns}",
+            ".globl __generic_statics_start.{ns}",
+            "__generic_statics_start.{ns}:",
+            ".popsection",
+            ".pushsection .bss$gs{ns}z,\"bw\",discard,__generic_statics_stop.{ns}",
+            ".globl __generic_statics_stop.{ns}",
+            "__generic_statics_stop.{ns}:",
+            ".popsection",
+            ".endif",
+            "lea __generic_statics_start.{ns}(%rip), {start}",
+            "lea __generic_statics_stop.{ns}(%rip), {stop}",
+            ns = const { namespace_key::<N>() },
+            start = out(reg) start,
+            stop = out(reg) stop,
+            options(att_syntax, pure, nomem, nostack, preserves_flags)
+        );
+    }
+
+    start..stop
+}
+
+/// Emits the descriptor of the storage of `T` in namespace `N` (see [`crate::introspect`]).
+///
+/// This has to be inlined together with (and after) [`reserve`], so the storage symbol is defined
+/// in the same object file before the descriptor, which can then depend on it (see
+/// [`descriptors_flags`]).
+#[cfg(feature = "introspection")]
+#[inline(always)]
+pub(crate) fn describe<N: Namespace, T: Storage>() {
+    match N::IDENTITY {
+        Identity::Process => {
+            describe!(
+                [crate::__storage!(process)],
+                size = const { storage_size::<T>() },
+                align = const { mem::align_of::<T>().ilog2() },
+                key = const { storage_key::<N, T>() },
+                describe = sym crate::descriptor::describe::<N, T>,
+            );
+        }
+        Identity::Crate => {
+            describe!(
+                [crate::__storage!(crate)],
+                id = sym crate_static::<N, T>,
+                describe = sym crate::descriptor::describe::<N, T>,
+            );
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Asynchronously initialized storage, see
+//! [`Namespace::generic_async_lazy`](crate::Namespace::generic_async_lazy).
+
+use std::{
+    cell::UnsafeCell,
+    future::{poll_fn, Future},
+    mem::{self, MaybeUninit},
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Mutex,
+    },
+    task::{Poll, Waker},
+};
+
+use crate::{GenericOnceLock, Zeroable};
+
+const UNINIT: u8 = 0;
+const RUNNING: u8 = 1;
+const DONE: u8 = 2;
+
+/// A value that is initialized asynchronously on first access.
+///
+/// Unlike `tokio::sync::OnceCell`, this is zero-initializable so it can be used as a generic
+/// static. It doesn't depend on a specific executor, waiting tasks are woken through their
+/// [`Waker`].
+pub(crate) struct AsyncLazy<T> {
+    state: AtomicU8,
+    value: UnsafeCell<MaybeUninit<T>>,
+    /// Tasks waiting for the initialization, only allocated on contention.
+    waiters: GenericOnceLock<Mutex<Vec<Waker>>>,
+}
+
+// SAFETY: The value is only written once (by the task moving the state to `RUNNING`) and only
+// shared after the state has been moved to `DONE`.
+unsafe impl<T: Send + Sync> Sync for AsyncLazy<T> {}
+
+unsafe impl<T> Zeroable for AsyncLazy<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            state: AtomicU8::new(UNINIT),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+            waiters: GenericOnceLock::zeroed(),
+        }
+    }
+}
+
+impl<T> AsyncLazy<T> {
+    /// Returns the value, initializing it with the future returned by `init` if this is the
+    /// first access.
+    ///
+    /// Concurrent callers wait for the initialization to complete. If the initializing future
+    /// panics or is dropped before completion, one of the waiting callers (or the next caller)
+    /// retries the initialization.
+    pub(crate) async fn get_or_init<F, Fut>(&self, init: F) -> &T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        if self.state.load(Ordering::Acquire) != DONE
+            && poll_fn(|cx| self.poll_acquire(cx.waker())).await
+        {
+            let reset = Reset(self);
+            let value = init().await;
+            unsafe { (*self.value.get()).write(value) };
+            mem::forget(reset);
+
+            self.release(DONE);
+        }
+
+        unsafe { (*self.value.get()).assume_init_ref() }
+    }
+
+    /// Returns `true` if the caller should initialize the value, `false` if it's initialized.
+    fn poll_acquire(&self, waker: &Waker) -> Poll<bool> {
+        loop {
+            match self
+                .state
+                .compare_exchange(UNINIT, RUNNING, Ordering::Acquire, Ordering::Acquire)
+            {
+                Ok(_) => return Poll::Ready(true),
+                Err(DONE) => return Poll::Ready(false),
+                Err(_) => {
+                    let mut waiters = self.waiters().lock().unwrap_or_else(|e| e.into_inner());
+                    // Checked while holding the lock, `release` updates the state before taking
+                    // it, so the wakeup can't be missed.
+                    if self.state.load(Ordering::Acquire) != RUNNING {
+                        continue;
+                    }
+                    if !waiters.iter().any(|w| w.will_wake(waker)) {
+                        waiters.push(waker.clone());
+                    }
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+
+    /// Moves the state to `state` and wakes all waiting tasks.
+    fn release(&self, state: u8) {
+        self.state.store(state, Ordering::Release);
+
+        if let Some(waiters) = self.waiters.get() {
+            let waiters = mem::take(&mut *waiters.lock().unwrap_or_else(|e| e.into_inner()));
+            waiters.into_iter().for_each(Waker::wake);
+        }
+    }
+
+    #[inline]
+    fn waiters(&self) -> &Mutex<Vec<Waker>> {
+        self.waiters.get_or_init(Default::default)
+    }
+}
+
+/// Resets the state if the initialization panicked or was cancelled.
+struct Reset<'a, T>(&'a AsyncLazy<T>);
+
+impl<T> Drop for Reset<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.release(UNINIT);
+    }
+}
@@ -0,0 +1,44 @@
+//! Batch resolution, see [`Namespace::generic_statics_of`](crate::Namespace::generic_statics_of).
+
+use crate::{Namespace, Zeroable};
+
+/// Tuples of up to 8 types whose statics can be resolved together with
+/// [`Namespace::generic_statics_of`](crate::Namespace::generic_statics_of).
+pub trait StaticTuple: sealed::Sealed {
+    /// The tuple of references to the statics of the element types.
+    type Refs;
+
+    #[doc(hidden)]
+    fn __resolve<N: Namespace>() -> Self::Refs;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_tuples {
+    ($t1:ident) => {
+        impl_tuples!(@impl $t1);
+    };
+    ($t1:ident, $($tr:ident),+) => {
+        impl_tuples!(@impl $t1, $($tr),+);
+        impl_tuples!($($tr),+);
+    };
+    (@impl $($t:ident),+) => {
+        impl<$($t),+> sealed::Sealed for ($($t,)+) where $($t: 'static + Zeroable + Sync),+ {}
+
+        impl<$($t),+> StaticTuple for ($($t,)+)
+        where
+            $($t: 'static + Zeroable + Sync),+
+        {
+            type Refs = ($(&'static $t,)+);
+
+            #[inline(never)]
+            fn __resolve<N: Namespace>() -> Self::Refs {
+                ($(N::generic_static::<$t>(),)+)
+            }
+        }
+    };
+}
+
+impl_tuples!(A, B, C, D, E, F, G, H);
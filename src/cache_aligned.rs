@@ -0,0 +1,73 @@
+//! Cache-line aligned statics, see
+//! [`Namespace::generic_static_padded`](crate::Namespace::generic_static_padded).
+
+use std::ops::{Deref, DerefMut};
+
+use crate::Zeroable;
+
+/// Aligns and pads `T` to the size of a cache line, so it doesn't share a cache line with other
+/// data.
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use generic_statics::{define_namespace, CacheAligned, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// let hits = Test::generic_static::<CacheAligned<AtomicU64>>();
+/// hits.fetch_add(1, Ordering::Relaxed);
+/// assert_eq!(hits as *const _ as usize % CacheAligned::<AtomicU64>::ALIGN, 0);
+/// ```
+///
+/// The alignment is 128 bytes on `x86_64` (whose spatial prefetcher pulls pairs of 64-byte
+/// lines) and `aarch64` (whose Apple cores have 128-byte lines), 64 bytes otherwise. As the
+/// storage of generic statics is aligned to the alignment of the type, this also holds for
+/// generic statics.
+#[cfg_attr(any(target_arch = "x86_64", target_arch = "aarch64"), repr(align(128)))]
+#[cfg_attr(
+    not(any(target_arch = "x86_64", target_arch = "aarch64")),
+    repr(align(64))
+)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CacheAligned<T>(pub T);
+
+impl<T> CacheAligned<T> {
+    /// The alignment (and the granularity of the size) of `CacheAligned`.
+    pub const ALIGN: usize = std::mem::align_of::<Self>();
+
+    /// Wraps `value`.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Unwraps the value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+// SAFETY: The padding has no validity requirements.
+unsafe impl<T: Zeroable> Zeroable for CacheAligned<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(T::zeroed())
+    }
+}
+
+impl<T> Deref for CacheAligned<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for CacheAligned<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
@@ -0,0 +1,52 @@
+//! Cached addresses, see
+//! [`Namespace::generic_static_cached`](crate::Namespace::generic_static_cached).
+
+use std::{marker::PhantomData, ptr::NonNull};
+
+use crate::{
+    init::Storage,
+    sync::atomic::{AtomicPtr, Ordering},
+    Namespace, Zeroable,
+};
+
+/// Per-copy cache of the resolved address of the storage of `T` in namespace `N`, null until the
+/// first resolution.
+///
+/// The address is type-erased, so the cache can also be used by the shared resolver (see
+/// [`crate::resolver`]).
+struct Cache<N, T>(AtomicPtr<()>, PhantomData<fn() -> (N, T)>);
+
+unsafe impl<N, T> Zeroable for Cache<N, T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(AtomicPtr::zeroed(), PhantomData)
+    }
+}
+
+/// Returns the address cache of `T`'s storage in namespace `N`.
+#[inline]
+pub(crate) fn cache<N: Namespace, T: Storage>() -> &'static AtomicPtr<()> {
+    unsafe { &crate::reserve_bookkeeping::<N, Cache<N, T>>().as_ref().0 }
+}
+
+/// Returns the address of `T`'s storage in namespace `N`, resolving it only on the first call.
+#[inline]
+pub(crate) fn resolve<N: Namespace, T: Storage>() -> NonNull<T> {
+    let cache = cache::<N, T>();
+
+    // Pairs with the release store in `resolve_slow`, so the initialization of storage written by
+    // the resolution (e.g. of freezable namespaces and by the miri backend) is visible.
+    match NonNull::new(cache.load(Ordering::Acquire)) {
+        Some(addr) => addr.cast(),
+        None => resolve_slow::<N, T>(cache),
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn resolve_slow<N: Namespace, T: Storage>(cache: &AtomicPtr<()>) -> NonNull<T> {
+    // Racing threads resolve the same address, so it doesn't matter which one is stored.
+    let addr = crate::resolve_storage::<N, T>();
+    cache.store(addr.as_ptr().cast(), Ordering::Release);
+    addr
+}
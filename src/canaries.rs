@@ -0,0 +1,190 @@
+//! Canaries after the asm-reserved storage (`canaries` feature).
+//!
+//! Every storage definition emitted by [`crate::asm`] is followed by an 8-byte canary and emits
+//! an [`Entry`] referring to both into a dedicated section. A constructor fills the canaries of
+//! this linked image with [`CANARY`] before `main`, [`check_canaries`] compares them.
+//!
+//! The canaries are emitted into the sections of the storage, so a canary also precedes the
+//! storage following it (unless they are separated by alignment padding). Storage that isn't
+//! reserved at link time (e.g. of freezable namespaces) has no canary.
+
+use std::{fmt, ptr, slice};
+
+/// The value of intact canaries.
+const CANARY: u64 = 0xc0de_ca4a_5afe_c0de;
+
+/// A canary after a storage definition.
+#[repr(C)]
+pub(crate) struct Entry {
+    /// The address of the storage, null for padding and sentinels.
+    storage: *const u8,
+    /// The address of the canary.
+    canary: *mut u8,
+}
+
+// SAFETY: Entries are never mutated.
+unsafe impl Sync for Entry {}
+
+impl Entry {
+    #[allow(dead_code)]
+    const SENTINEL: Self = Self {
+        storage: ptr::null(),
+        canary: ptr::null_mut(),
+    };
+}
+
+#[cfg(any(target_os = "none", target_os = "linux", target_os = "freebsd"))]
+mod section {
+    use super::Entry;
+
+    extern "C" {
+        #[link_name = "__start_generic_statics_canaries"]
+        pub(super) static START: Entry;
+        #[link_name = "__stop_generic_statics_canaries"]
+        pub(super) static STOP: Entry;
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos"))]
+mod section {
+    use super::Entry;
+
+    // Makes sure the section (and therefore its bounds) exists even without any storage.
+    #[used]
+    #[link_section = "__DATA,__gs_canaries,regular,no_dead_strip"]
+    static ANCHOR: Entry = Entry::SENTINEL;
+
+    extern "C" {
+        #[link_name = "\x01section$start$__DATA$__gs_canaries"]
+        pub(super) static START: Entry;
+        #[link_name = "\x01section$end$__DATA$__gs_canaries"]
+        pub(super) static STOP: Entry;
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod section {
+    use super::Entry;
+
+    // The linker sorts the `.gscan$*` sections by their suffix, so the entries (`$m`) end up
+    // between these bounds.
+    #[used]
+    #[link_section = ".gscan$a"]
+    pub(super) static START: Entry = Entry::SENTINEL;
+    #[used]
+    #[link_section = ".gscan$z"]
+    pub(super) static STOP: Entry = Entry::SENTINEL;
+}
+
+/// Returns the entries of this linked image, ordered by the address of the storage.
+fn entries() -> Vec<&'static Entry> {
+    // Makes sure the section (and therefore its bounds) exists even without any storage. This
+    // has to live in the same object file as the references to the bounds.
+    #[cfg(any(target_os = "none", target_os = "linux", target_os = "freebsd"))]
+    unsafe {
+        std::arch::asm!(
+            ".pushsection generic_statics_canaries,\"awR\",@progbits",
+            ".p2align 3",
+            ".quad 0",
+            ".quad 0",
+            ".popsection",
+            options(nomem, nostack, preserves_flags),
+        );
+    }
+
+    let start = ptr::addr_of!(section::START);
+    let stop = ptr::addr_of!(section::STOP);
+    let entries = unsafe { slice::from_raw_parts(start, stop.offset_from(start) as usize) };
+
+    // Entries are emitted once per object file (and deduplicated by the linker on ELF and COFF).
+    let mut entries = entries
+        .iter()
+        .filter(|entry| !entry.storage.is_null())
+        .collect::<Vec<_>>();
+    entries.sort_unstable_by_key(|entry| entry.storage);
+    entries.dedup_by_key(|entry| entry.storage);
+    entries
+}
+
+/// Fills the canaries of this linked image.
+extern "C" fn fill() {
+    for entry in entries() {
+        unsafe { entry.canary.cast::<u64>().write_unaligned(CANARY) };
+    }
+}
+
+#[used]
+#[cfg_attr(
+    any(target_os = "none", target_os = "linux", target_os = "freebsd"),
+    link_section = ".init_array"
+)]
+#[cfg_attr(
+    any(target_os = "macos", target_os = "ios", target_os = "tvos"),
+    link_section = "__DATA,__mod_init_func"
+)]
+#[cfg_attr(target_os = "windows", link_section = ".CRT$XCU")]
+static FILL: extern "C" fn() = fill;
+
+/// A generic static whose canary was overwritten, see [`check_canaries`].
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct CorruptedCanary {
+    storage: *const (),
+    len: usize,
+}
+
+// SAFETY: The address is only exposed as an opaque value.
+unsafe impl Send for CorruptedCanary {}
+unsafe impl Sync for CorruptedCanary {}
+
+impl CorruptedCanary {
+    /// The address of the static.
+    #[inline]
+    pub fn addr(&self) -> *const () {
+        self.storage
+    }
+
+    /// The offset of the canary from the address of the static, i.e. the size of the static
+    /// rounded up to whole words for statics with an initial value.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.len
+    }
+}
+
+impl fmt::Debug for CorruptedCanary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CorruptedCanary")
+            .field("addr", &self.storage)
+            .field("offset", &self.len)
+            .finish()
+    }
+}
+
+/// Returns the generic statics of this linked image whose canary was overwritten, ordered by
+/// address (`canaries` feature).
+///
+/// ```rust
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// let _ = Test::generic_static::<[u32; 16]>();
+/// assert!(generic_statics::check_canaries().is_empty());
+/// ```
+///
+/// Every generic static is followed by an 8-byte canary, so out-of-bounds writes past the end of
+/// a static (e.g. due to index math into per-type arrays) overwrite it instead of the static
+/// after it. This is meant to be called in tests or periodically in hardened builds, as writes
+/// are only detected once checked (and writes skipping the canary aren't detected at all).
+pub fn check_canaries() -> Vec<CorruptedCanary> {
+    entries()
+        .into_iter()
+        .filter(|entry| unsafe { entry.canary.cast::<u64>().read_unaligned() } != CANARY)
+        .map(|entry| CorruptedCanary {
+            storage: entry.storage.cast(),
+            // The nightly toolchains this crate requires have `<*const T>::addr`.
+            #[allow(clippy::incompatible_msrv)]
+            len: entry.canary.addr() - entry.storage.addr(),
+        })
+        .collect()
+}
@@ -0,0 +1,43 @@
+//! One-time claimable storage, see [`Namespace::claim`](crate::Namespace::claim).
+
+use std::cell::UnsafeCell;
+
+use crate::{
+    sync::atomic::{AtomicBool, Ordering},
+    Zeroable,
+};
+
+/// Storage of `T` that can be claimed mutably once, so it doesn't share the storage of
+/// [`Namespace::generic_static`](crate::Namespace::generic_static).
+pub(crate) struct Claim<T> {
+    claimed: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: The value is only accessed by the (single) thread that claimed it.
+unsafe impl<T: Send> Sync for Claim<T> {}
+
+unsafe impl<T: Zeroable> Zeroable for Claim<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            claimed: AtomicBool::zeroed(),
+            value: UnsafeCell::new(T::zeroed()),
+        }
+    }
+}
+
+impl<T> Claim<T> {
+    /// Returns the value if this is the first call.
+    ///
+    /// The flag guarantees that the mutable reference is unique.
+    #[allow(clippy::mut_from_ref)]
+    #[inline]
+    pub(crate) fn claim(&'static self) -> Option<&'static mut T> {
+        if self.claimed.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+
+        Some(unsafe { &mut *self.value.get() })
+    }
+}
@@ -0,0 +1,180 @@
+//! Per-type component metadata, see [`ComponentMeta`].
+//!
+//! The metadata of every type is stored in a generic static of its own (in a private namespace,
+//! keyed by the namespace and the type). Registered metadata is linked into a per-namespace
+//! intrusive list, so registration and iteration don't allocate.
+
+use std::{
+    alloc::Layout,
+    any::{self, TypeId},
+    fmt, iter,
+    marker::PhantomData,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, Ordering},
+};
+
+use crate::{define_namespace, Namespace, Zeroable};
+
+define_namespace!(Components);
+
+/// Metadata of a component type, as needed by type-erased storage (e.g. the columns of an ECS).
+///
+/// ```rust
+/// use std::alloc::Layout;
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(World);
+///
+/// struct Position(f32, f32);
+/// struct Name(String);
+///
+/// World::register_component::<Position>();
+/// World::register_component::<Name>();
+///
+/// let position = World::component::<Position>();
+/// assert_eq!(position.layout(), Layout::new::<Position>());
+/// assert!(!position.needs_drop());
+/// assert!(World::component::<Name>().needs_drop());
+///
+/// let mut names: Vec<_> = World::components().map(|meta| meta.type_name()).collect();
+/// names.sort();
+/// assert_eq!(names.len(), 2);
+/// ```
+pub struct ComponentMeta {
+    layout: Layout,
+    drop: Option<unsafe fn(*mut u8)>,
+    type_name: &'static str,
+    type_id: TypeId,
+    index: usize,
+    registered: AtomicBool,
+    next: AtomicPtr<ComponentMeta>,
+}
+
+impl ComponentMeta {
+    fn new<N: Namespace, T: 'static>() -> Self {
+        Self {
+            layout: Layout::new::<T>(),
+            drop: std::mem::needs_drop::<T>().then_some(drop_erased::<T> as unsafe fn(*mut u8)),
+            type_name: any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            index: N::type_index::<T>(),
+            registered: AtomicBool::new(false),
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// The layout of the component type.
+    #[inline]
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// The type name of the component type.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// The [`TypeId`] of the component type.
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// The [`type_index`](Namespace::type_index) of the component type in its namespace.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Whether values of the component type need to be dropped.
+    #[inline]
+    pub fn needs_drop(&self) -> bool {
+        self.drop.is_some()
+    }
+
+    /// The drop function of the component type, if it needs to be dropped.
+    #[inline]
+    pub fn drop_fn(&self) -> Option<unsafe fn(*mut u8)> {
+        self.drop
+    }
+
+    /// Drops the value at `ptr` in place.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`ptr::drop_in_place`], `ptr` must point to a valid value of the component type.
+    #[inline]
+    pub unsafe fn drop_in_place(&self, ptr: *mut u8) {
+        if let Some(drop) = self.drop {
+            drop(ptr);
+        }
+    }
+
+    /// Whether the component type has been registered with
+    /// [`Namespace::register_component`].
+    #[inline]
+    pub fn is_registered(&self) -> bool {
+        self.registered.load(Ordering::Acquire)
+    }
+}
+
+impl fmt::Debug for ComponentMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComponentMeta")
+            .field("type_name", &self.type_name)
+            .field("layout", &self.layout)
+            .field("index", &self.index)
+            .field("needs_drop", &self.needs_drop())
+            .field("registered", &self.is_registered())
+            .finish()
+    }
+}
+
+unsafe fn drop_erased<T>(ptr: *mut u8) {
+    ptr::drop_in_place(ptr.cast::<T>());
+}
+
+/// Storage of the metadata of `T` in namespace `N`.
+struct Meta<N, T>(ComponentMeta, PhantomData<fn() -> (N, T)>);
+
+/// Head of the list of registered components of namespace `N`.
+struct Head<N>(AtomicPtr<ComponentMeta>, PhantomData<fn() -> N>);
+
+unsafe impl<N> Zeroable for Head<N> {}
+
+/// Returns the metadata of `T` in namespace `N`.
+#[inline]
+pub(crate) fn meta<N: Namespace, T: 'static>() -> &'static ComponentMeta {
+    &Components::generic_lazy::<Meta<N, T>>(|| Meta(ComponentMeta::new::<N, T>(), PhantomData)).0
+}
+
+/// Registers `T` with namespace `N`, returning whether it wasn't registered before.
+pub(crate) fn register<N: Namespace, T: 'static>() -> bool {
+    let meta = meta::<N, T>();
+    if meta.registered.swap(true, Ordering::AcqRel) {
+        return false;
+    }
+
+    let head = &Components::generic_static::<Head<N>>().0;
+    let mut next = head.load(Ordering::Relaxed);
+    loop {
+        meta.next.store(next, Ordering::Relaxed);
+        let new = meta as *const ComponentMeta as *mut ComponentMeta;
+        match head.compare_exchange_weak(next, new, Ordering::Release, Ordering::Relaxed) {
+            Ok(_) => return true,
+            Err(current) => next = current,
+        }
+    }
+}
+
+/// Iterates over the registered components of namespace `N`.
+pub(crate) fn registered<N: Namespace>() -> impl Iterator<Item = &'static ComponentMeta> {
+    let head = Components::generic_static::<Head<N>>()
+        .0
+        .load(Ordering::Acquire);
+    // SAFETY: The list only links metadata in generic statics, which are never freed.
+    iter::successors(unsafe { head.as_ref() }, |meta| unsafe {
+        meta.next.load(Ordering::Acquire).as_ref()
+    })
+}
@@ -0,0 +1,11 @@
+//! Per-tag counters, see [`Namespace::counter`](crate::Namespace::counter).
+
+use std::{marker::PhantomData, sync::atomic::AtomicU64};
+
+use crate::Zeroable;
+
+/// Storage of the counter for `Tag`, so tags don't have to be [`Zeroable`] themselves.
+#[repr(transparent)]
+pub(crate) struct Counter<Tag>(pub(crate) AtomicU64, PhantomData<fn() -> Tag>);
+
+unsafe impl<Tag> Zeroable for Counter<Tag> {}
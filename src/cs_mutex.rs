@@ -0,0 +1,81 @@
+//! A zero-initializable mutex based on [`critical_section`] (`critical-section` feature).
+
+use std::cell::{Cell, UnsafeCell};
+
+use crate::Zeroable;
+
+/// A mutex that is locked by entering a critical section.
+///
+/// This is meant to be used as a generic static on single-core embedded targets, where a
+/// critical section (e.g. disabling interrupts) is enough to get exclusive access:
+///
+/// ```rust
+/// use generic_statics::{define_namespace, GenericCsMutex, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// let count = Test::generic_static::<GenericCsMutex<u32>>().lock(|count| {
+///     *count += 1;
+///     *count
+/// });
+/// assert_eq!(count, 1);
+/// ```
+pub struct GenericCsMutex<T> {
+    locked: Cell<bool>,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: The value is only accessed within a critical section and the lock flag prevents
+// reentrant access.
+unsafe impl<T: Send> Sync for GenericCsMutex<T> {}
+
+unsafe impl<T: Zeroable> Zeroable for GenericCsMutex<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new(T::zeroed())
+    }
+}
+
+impl<T> GenericCsMutex<T> {
+    /// Creates a new, unlocked mutex.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self {
+            locked: Cell::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Enters a critical section and calls `f` with exclusive access to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is already locked (i.e. `lock` is called reentrantly from `f`).
+    pub fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        critical_section::with(|_| {
+            assert!(
+                !self.locked.replace(true),
+                "GenericCsMutex is already locked"
+            );
+            let _guard = Unlock(&self.locked);
+
+            f(unsafe { &mut *self.value.get() })
+        })
+    }
+
+    /// Returns a mutable reference to the value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+/// Unlocks the mutex on drop, even if the closure passed to [`GenericCsMutex::lock`] panicked.
+struct Unlock<'a>(&'a Cell<bool>);
+
+impl Drop for Unlock<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
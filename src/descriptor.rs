@@ -0,0 +1,126 @@
+//! Descriptions of generic statics, used by the `introspection` and `touched-statics` features.
+
+use std::{any, fmt, mem, ptr};
+
+use crate::{init::Storage, Namespace, NamespaceInfo};
+
+/// Describes a generic static, see `statics` (`introspection` feature) and `touched_statics`
+/// (`touched-statics` feature).
+#[derive(Copy, Clone)]
+pub struct StaticDescriptor {
+    namespace: NamespaceInfo,
+    type_name: &'static str,
+    size: usize,
+    align: usize,
+    addr: *const (),
+    /// Formats the static at the address, for `Debug` types.
+    #[cfg(feature = "dump")]
+    debug: Option<unsafe fn(*const ()) -> &'static dyn fmt::Debug>,
+    /// Resets the static at the address to its initial value, if it has one.
+    #[cfg(all(feature = "reset", not(any(feature = "loom", miri))))]
+    reset: Option<unsafe fn(*const ())>,
+}
+
+// SAFETY: The address is only exposed as an opaque value.
+unsafe impl Send for StaticDescriptor {}
+unsafe impl Sync for StaticDescriptor {}
+
+impl StaticDescriptor {
+    /// Sets the address of the static.
+    #[inline]
+    pub(crate) fn at(self, addr: *const ()) -> Self {
+        Self { addr, ..self }
+    }
+
+    /// The namespace of the static.
+    #[inline]
+    pub fn namespace(&self) -> NamespaceInfo {
+        self.namespace
+    }
+
+    /// The type name of the static's storage.
+    ///
+    /// This is the type of the static, unless it's wrapped by the crate (e.g. the statics of
+    /// [`Namespace::generic_lazy`] or [`Namespace::counter`]).
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// The size of the static in bytes.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The alignment of the static in bytes.
+    #[inline]
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// The address of the static.
+    #[inline]
+    pub fn addr(&self) -> *const () {
+        self.addr
+    }
+
+    /// The static as `Debug` trait object, if its storage implements `Debug` and is `Sync`
+    /// (`dump` feature).
+    ///
+    /// See [`Namespace::dump`].
+    ///
+    /// # Safety
+    ///
+    /// The static must not be written while the returned reference is used, other than through
+    /// shared references (e.g. of atomics or locks). In particular, writes through the pointers
+    /// returned by [`Namespace::generic_static_ptr`] have to be synchronized with the use of the
+    /// reference, like with any other shared reference to the static.
+    #[cfg(feature = "dump")]
+    #[inline]
+    pub unsafe fn value(&self) -> Option<&'static dyn fmt::Debug> {
+        // SAFETY: The address is the one of the described storage, which is always initialized
+        // (`Debug` isn't captured for uninitialized storage).
+        self.debug.map(|debug| unsafe { debug(self.addr) })
+    }
+
+    /// Resets the static to its initial value (statics without one are left unchanged).
+    ///
+    /// # Safety
+    ///
+    /// See [`Namespace::reset_all`].
+    #[cfg(all(feature = "reset", not(any(feature = "loom", miri))))]
+    #[inline]
+    pub(crate) unsafe fn reset(&self) {
+        if let Some(reset) = self.reset {
+            reset(self.addr);
+        }
+    }
+}
+
+impl fmt::Debug for StaticDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticDescriptor")
+            .field("namespace", &self.namespace.name())
+            .field("type_name", &self.type_name)
+            .field("size", &self.size)
+            .field("align", &self.align)
+            .field("addr", &self.addr)
+            .finish()
+    }
+}
+
+/// Describes the storage of `T` in namespace `N`, without its address.
+pub(crate) fn describe<N: Namespace, T: Storage>() -> StaticDescriptor {
+    StaticDescriptor {
+        namespace: N::info(),
+        type_name: any::type_name::<T>(),
+        size: mem::size_of::<T>(),
+        align: mem::align_of::<T>(),
+        addr: ptr::null(),
+        #[cfg(feature = "dump")]
+        debug: <T as crate::dump::MaybeDebug>::DEBUG,
+        #[cfg(all(feature = "reset", not(any(feature = "loom", miri))))]
+        reset: crate::reset::resetter::<T>(),
+    }
+}
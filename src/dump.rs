@@ -0,0 +1,29 @@
+//! Captures the `Debug` implementation of the statics (`dump` feature), see
+//! [`Namespace::dump`](crate::Namespace::dump).
+//!
+//! This lives in its own module, as the specialization syntax is rejected even in items that are
+//! configured out.
+
+use std::fmt;
+
+/// Captures the `Debug` implementation of a storage type, if any.
+pub(crate) trait MaybeDebug {
+    const DEBUG: Option<unsafe fn(*const ()) -> &'static dyn fmt::Debug>;
+}
+
+impl<T> MaybeDebug for T {
+    default const DEBUG: Option<unsafe fn(*const ()) -> &'static dyn fmt::Debug> = None;
+}
+
+impl<T: fmt::Debug + Sync + 'static> MaybeDebug for T {
+    const DEBUG: Option<unsafe fn(*const ()) -> &'static dyn fmt::Debug> = Some(debug::<T>);
+}
+
+/// Returns the static of `T` at `addr` as `Debug` trait object.
+///
+/// # Safety
+///
+/// `addr` must point to an initialized `T` that lives forever.
+unsafe fn debug<T: fmt::Debug + Sync + 'static>(addr: *const ()) -> &'static dyn fmt::Debug {
+    &*addr.cast::<T>()
+}
@@ -0,0 +1,81 @@
+//! Process-global canonicalization across dynamically loaded images (`dylib-dedup` feature).
+//!
+//! Every image linking this crate exports [`generic_statics_canonicalize_v1`]. On first use, the
+//! definition that is visible process-wide (usually the one of the executable) is looked up and
+//! used by all images, falling back to the image's own registry if there is none.
+
+use std::{
+    any::TypeId,
+    ffi::{c_char, c_void},
+    mem,
+    ptr::NonNull,
+    sync::OnceLock,
+};
+
+use crate::registry;
+
+/// Signature of [`generic_statics_canonicalize_v1`].
+type CanonicalizeFn =
+    unsafe extern "C" fn(ns: *const TypeId, ty: *const TypeId, addr: NonNull<()>) -> NonNull<()>;
+
+/// The name of the exported registry entry point, its version must be bumped on every change of
+/// [`CanonicalizeFn`].
+const SYMBOL: &[u8] = b"generic_statics_canonicalize_v1\0";
+
+/// Returns the canonical address for `(ns, ty)` of this image's registry, registering `addr` if
+/// there is none yet.
+///
+/// # Safety
+///
+/// `ns` and `ty` must be valid pointers.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub unsafe extern "C" fn generic_statics_canonicalize_v1(
+    ns: *const TypeId,
+    ty: *const TypeId,
+    addr: NonNull<()>,
+) -> NonNull<()> {
+    unsafe { registry::get_or_insert_local(*ns, *ty, || addr) }
+}
+
+/// Returns the process-wide canonical address for `(ns, ty)`, registering `addr` if there is
+/// none yet.
+pub(crate) fn canonicalize(ns: TypeId, ty: TypeId, addr: NonNull<()>) -> NonNull<()> {
+    static GLOBAL: OnceLock<CanonicalizeFn> = OnceLock::new();
+
+    let canonicalize = GLOBAL.get_or_init(|| lookup().unwrap_or(generic_statics_canonicalize_v1));
+    unsafe { canonicalize(&ns, &ty, addr) }
+}
+
+#[cfg(unix)]
+fn lookup() -> Option<CanonicalizeFn> {
+    extern "C" {
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "none"))]
+    let default = std::ptr::null_mut::<c_void>();
+    // `RTLD_DEFAULT` is `(void *)-2` on macOS and FreeBSD.
+    #[cfg(not(any(target_os = "linux", target_os = "none")))]
+    let default = std::ptr::null_mut::<u8>().wrapping_sub(2).cast::<c_void>();
+
+    let f = unsafe { dlsym(default, SYMBOL.as_ptr().cast()) };
+    (!f.is_null()).then(|| unsafe { mem::transmute::<*mut c_void, CanonicalizeFn>(f) })
+}
+
+#[cfg(windows)]
+fn lookup() -> Option<CanonicalizeFn> {
+    extern "system" {
+        fn GetModuleHandleW(name: *const u16) -> *mut c_void;
+        fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *mut c_void;
+    }
+
+    // Only the executable is searched, as there is no global symbol namespace on Windows.
+    let module = unsafe { GetModuleHandleW(std::ptr::null()) };
+    if module.is_null() {
+        return None;
+    }
+
+    let f = unsafe { GetProcAddress(module, SYMBOL.as_ptr().cast()) };
+    (!f.is_null()).then(|| unsafe { mem::transmute::<*mut c_void, CanonicalizeFn>(f) })
+}
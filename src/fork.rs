@@ -0,0 +1,116 @@
+//! Per-namespace `fork` hooks, see [`Namespace::on_fork_child`](crate::Namespace::on_fork_child).
+
+use std::{
+    ffi::c_int,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, Ordering},
+};
+
+use crate::{Namespace, Zeroable};
+
+/// A registered hook, hooks are kept in a leaked, append-only list so they can be run without
+/// taking locks (which might be held by other threads while forking).
+struct Hook {
+    hook: fn(),
+    next: *mut Hook,
+}
+
+/// The hooks of a namespace.
+pub(crate) struct ForkHooks {
+    registered: AtomicBool,
+    prepare: AtomicPtr<Hook>,
+    parent: AtomicPtr<Hook>,
+    child: AtomicPtr<Hook>,
+}
+
+unsafe impl Zeroable for ForkHooks {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            registered: AtomicBool::new(false),
+            prepare: AtomicPtr::new(ptr::null_mut()),
+            parent: AtomicPtr::new(ptr::null_mut()),
+            child: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+}
+
+/// When a hook is run.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum Stage {
+    Prepare,
+    Parent,
+    Child,
+}
+
+extern "C" {
+    fn pthread_atfork(
+        prepare: Option<extern "C" fn()>,
+        parent: Option<extern "C" fn()>,
+        child: Option<extern "C" fn()>,
+    ) -> c_int;
+}
+
+/// Registers `hook` to be run at `stage` of every `fork` for namespace `N`.
+pub(crate) fn register<N: Namespace>(stage: Stage, hook: fn()) {
+    let hooks = N::generic_static::<ForkHooks>();
+
+    let list = match stage {
+        Stage::Prepare => &hooks.prepare,
+        Stage::Parent => &hooks.parent,
+        Stage::Child => &hooks.child,
+    };
+    let node = Box::leak(Box::new(Hook {
+        hook,
+        next: ptr::null_mut(),
+    }));
+    let mut head = list.load(Ordering::Acquire);
+    loop {
+        node.next = head;
+        match list.compare_exchange_weak(head, node, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => break,
+            Err(current) => head = current,
+        }
+    }
+
+    if !hooks.registered.swap(true, Ordering::AcqRel) {
+        let result =
+            unsafe { pthread_atfork(Some(prepare::<N>), Some(parent::<N>), Some(child::<N>)) };
+        assert_eq!(result, 0, "failed to register fork handlers");
+    }
+}
+
+extern "C" fn prepare<N: Namespace>() {
+    // Like `pthread_atfork`, prepare hooks run in reverse order of registration.
+    let mut hook = N::generic_static::<ForkHooks>()
+        .prepare
+        .load(Ordering::Acquire);
+    while let Some(current) = unsafe { hook.as_ref() } {
+        (current.hook)();
+        hook = current.next;
+    }
+}
+
+extern "C" fn parent<N: Namespace>() {
+    run_in_order(
+        N::generic_static::<ForkHooks>()
+            .parent
+            .load(Ordering::Acquire),
+    );
+}
+
+extern "C" fn child<N: Namespace>() {
+    run_in_order(
+        N::generic_static::<ForkHooks>()
+            .child
+            .load(Ordering::Acquire),
+    );
+}
+
+/// Runs the hooks of the (newest first) list starting at `hook` in order of registration.
+fn run_in_order(hook: *mut Hook) {
+    if let Some(hook) = unsafe { hook.as_ref() } {
+        run_in_order(hook.next);
+        (hook.hook)();
+    }
+}
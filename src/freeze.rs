@@ -0,0 +1,217 @@
+//! Storage that can be made read-only (`freeze` feature), see
+//! [`Namespace::freeze`](crate::Namespace::freeze).
+//!
+//! The `.bss`/`.data` storage emitted by [`crate::asm`] shares its pages with unrelated data, so
+//! the storage of [freezable](crate::Namespace::FREEZABLE) namespaces is allocated from dedicated
+//! memory mappings instead, which are protected as a whole by [`freeze`].
+
+use std::{
+    any::TypeId,
+    collections::BTreeMap,
+    marker::PhantomData,
+    mem,
+    ptr::NonNull,
+    sync::{
+        atomic::{AtomicPtr, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+use crate::{init::Storage, Namespace, Zeroable};
+
+/// The granularity of the mappings, a multiple of the page size of every supported target.
+const CHUNK: usize = 1 << 16;
+
+/// Per-copy cache of the address of the storage of `T` in namespace `N`.
+struct Slot<N, T>(AtomicPtr<T>, PhantomData<fn() -> N>);
+
+unsafe impl<N, T> Zeroable for Slot<N, T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(AtomicPtr::new(std::ptr::null_mut()), PhantomData)
+    }
+}
+
+/// The mappings of a namespace.
+#[derive(Default)]
+struct Arena {
+    /// The mappings, the last one is used for new storage unless the arena is frozen.
+    chunks: Vec<(NonNull<u8>, usize)>,
+    /// The offset of the free space in the last mapping.
+    offset: usize,
+    /// The storage of every type.
+    statics: BTreeMap<TypeId, NonNull<u8>>,
+    frozen: bool,
+}
+
+// SAFETY: The mappings are only used as opaque memory by the arena.
+unsafe impl Send for Arena {}
+
+/// Process-wide map of namespaces to their arena.
+static ARENAS: Mutex<BTreeMap<TypeId, Arena>> = Mutex::new(BTreeMap::new());
+
+/// Returns the address of `T`'s storage in the freezable namespace `N`.
+pub(crate) fn resolve<N: Namespace, T: Storage>() -> NonNull<T> {
+    let cache = unsafe { &crate::reserve_bookkeeping::<N, Slot<N, T>>().as_ref().0 };
+
+    if let Some(addr) = NonNull::new(cache.load(Ordering::Acquire)) {
+        return addr;
+    }
+
+    let mut arenas = ARENAS.lock().unwrap_or_else(PoisonError::into_inner);
+    let arena = arenas.entry(TypeId::of::<N>()).or_default();
+    let addr = match arena.statics.get(&TypeId::of::<T>()) {
+        Some(addr) => addr.cast(),
+        None => {
+            let addr = arena.alloc::<T>();
+            arena.statics.insert(TypeId::of::<T>(), addr.cast());
+            addr
+        }
+    };
+    drop(arenas);
+
+    cache.store(addr.as_ptr(), Ordering::Release);
+    addr
+}
+
+/// Makes the storage of namespace `N` read-only.
+pub(crate) fn freeze<N: Namespace>() {
+    let mut arenas = ARENAS.lock().unwrap_or_else(PoisonError::into_inner);
+    let arena = arenas.entry(TypeId::of::<N>()).or_default();
+    if arena.frozen {
+        return;
+    }
+
+    for &(addr, len) in &arena.chunks {
+        unsafe { sys::protect(addr, len) };
+    }
+    arena.frozen = true;
+}
+
+impl Arena {
+    /// Allocates the storage for `T`, initialized with [`Storage::init`].
+    ///
+    /// Once the arena is frozen, every storage gets its own mapping, which is made read-only
+    /// right after the initialization.
+    fn alloc<T: Storage>(&mut self) -> NonNull<T> {
+        let size = mem::size_of::<T>().max(1);
+        let align = mem::align_of::<T>();
+
+        let free = match self.chunks.last() {
+            Some(&(chunk, len)) if !self.frozen => {
+                let offset = self.offset + align_offset(chunk, self.offset, align);
+                (offset + size <= len).then_some((chunk, len, offset))
+            }
+            _ => None,
+        };
+        let (chunk, len, offset) = free.unwrap_or_else(|| {
+            let len = (size + align).next_multiple_of(CHUNK);
+            let chunk = unsafe { sys::map(len) };
+            self.chunks.push((chunk, len));
+            (chunk, len, align_offset(chunk, 0, align))
+        });
+        self.offset = offset + size;
+
+        let addr = unsafe { chunk.as_ptr().add(offset).cast::<T>() };
+        unsafe { addr.write(T::init()) };
+
+        if self.frozen {
+            unsafe { sys::protect(chunk, len) };
+        }
+
+        unsafe { NonNull::new_unchecked(addr) }
+    }
+}
+
+/// Returns the offset from `offset` into `chunk` to the next address aligned to `align`.
+#[inline]
+fn align_offset(chunk: NonNull<u8>, offset: usize, align: usize) -> usize {
+    chunk.as_ptr().wrapping_add(offset).align_offset(align)
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::{ffi::c_int, ptr::NonNull};
+
+    const PROT_READ: c_int = 1;
+    const PROT_WRITE: c_int = 2;
+    const MAP_PRIVATE: c_int = 2;
+    #[cfg(target_os = "linux")]
+    const MAP_ANONYMOUS: c_int = 0x20;
+    #[cfg(not(target_os = "linux"))]
+    const MAP_ANONYMOUS: c_int = 0x1000;
+
+    extern "C" {
+        fn mmap(
+            addr: *mut u8,
+            len: usize,
+            prot: c_int,
+            flags: c_int,
+            fd: c_int,
+            offset: i64,
+        ) -> *mut u8;
+        fn mprotect(addr: *mut u8, len: usize, prot: c_int) -> c_int;
+    }
+
+    /// Maps `len` bytes of zeroed, writable memory.
+    pub(super) unsafe fn map(len: usize) -> NonNull<u8> {
+        let addr = unsafe {
+            mmap(
+                std::ptr::null_mut(),
+                len,
+                PROT_READ | PROT_WRITE,
+                MAP_PRIVATE | MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        // `MAP_FAILED` is `-1`.
+        assert!(
+            !addr.wrapping_add(1).is_null(),
+            "failed to map generic static storage"
+        );
+        NonNull::new(addr).expect("failed to map generic static storage")
+    }
+
+    /// Makes the mapping at `addr` read-only.
+    pub(super) unsafe fn protect(addr: NonNull<u8>, len: usize) {
+        let result = unsafe { mprotect(addr.as_ptr(), len, PROT_READ) };
+        assert_eq!(result, 0, "failed to freeze generic static storage");
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::ptr::NonNull;
+
+    const MEM_COMMIT: u32 = 0x1000;
+    const MEM_RESERVE: u32 = 0x2000;
+    const PAGE_READONLY: u32 = 0x02;
+    const PAGE_READWRITE: u32 = 0x04;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn VirtualAlloc(addr: *mut u8, len: usize, ty: u32, protect: u32) -> *mut u8;
+        fn VirtualProtect(addr: *mut u8, len: usize, protect: u32, old: *mut u32) -> i32;
+    }
+
+    /// Maps `len` bytes of zeroed, writable memory.
+    pub(super) unsafe fn map(len: usize) -> NonNull<u8> {
+        let addr = unsafe {
+            VirtualAlloc(
+                std::ptr::null_mut(),
+                len,
+                MEM_COMMIT | MEM_RESERVE,
+                PAGE_READWRITE,
+            )
+        };
+        NonNull::new(addr).expect("failed to map generic static storage")
+    }
+
+    /// Makes the mapping at `addr` read-only.
+    pub(super) unsafe fn protect(addr: NonNull<u8>, len: usize) {
+        let mut old = 0;
+        let result = unsafe { VirtualProtect(addr.as_ptr(), len, PAGE_READONLY, &mut old) };
+        assert_ne!(result, 0, "failed to freeze generic static storage");
+    }
+}
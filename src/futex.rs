@@ -0,0 +1,212 @@
+//! Waiting on atomics, used by the locks of this crate.
+//!
+//! This uses the futex-like primitive of the OS (`futex` on Linux, `_umtx_op` on FreeBSD,
+//! `__ulock_*` on Apple platforms and `WaitOnAddress` on Windows). Elsewhere (and with `loom`),
+//! waiting degrades to yielding (or spinning).
+
+use crate::sync::atomic::AtomicU32;
+
+/// Blocks the current thread while `atomic` has the value `expected`.
+///
+/// This may return spuriously, callers have to recheck their condition.
+#[inline]
+pub(crate) fn wait(atomic: &AtomicU32, expected: u32) {
+    sys::wait(atomic, expected);
+}
+
+/// Wakes one thread waiting on `atomic`.
+#[inline]
+pub(crate) fn wake_one(atomic: &AtomicU32) {
+    sys::wake(atomic, false);
+}
+
+/// Wakes all threads waiting on `atomic`.
+#[inline]
+pub(crate) fn wake_all(atomic: &AtomicU32) {
+    sys::wake(atomic, true);
+}
+
+#[cfg(all(target_os = "linux", not(feature = "loom")))]
+mod sys {
+    use std::{
+        ffi::{c_int, c_long},
+        ptr,
+        sync::atomic::AtomicU32,
+    };
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_FUTEX: c_long = 202;
+    #[cfg(target_arch = "aarch64")]
+    const SYS_FUTEX: c_long = 98;
+
+    const FUTEX_WAIT_PRIVATE: c_int = 128;
+    const FUTEX_WAKE_PRIVATE: c_int = 129;
+
+    extern "C" {
+        fn syscall(number: c_long, ...) -> c_long;
+    }
+
+    pub(super) fn wait(atomic: &AtomicU32, expected: u32) {
+        unsafe {
+            syscall(
+                SYS_FUTEX,
+                atomic.as_ptr(),
+                FUTEX_WAIT_PRIVATE,
+                expected,
+                ptr::null::<()>(),
+            )
+        };
+    }
+
+    pub(super) fn wake(atomic: &AtomicU32, all: bool) {
+        let count: c_int = if all { c_int::MAX } else { 1 };
+        unsafe { syscall(SYS_FUTEX, atomic.as_ptr(), FUTEX_WAKE_PRIVATE, count) };
+    }
+}
+
+#[cfg(all(target_os = "freebsd", not(feature = "loom")))]
+mod sys {
+    use std::{
+        ffi::{c_int, c_long, c_void},
+        ptr,
+        sync::atomic::AtomicU32,
+    };
+
+    const UMTX_OP_WAIT_UINT_PRIVATE: c_int = 15;
+    const UMTX_OP_WAKE_PRIVATE: c_int = 16;
+
+    extern "C" {
+        fn _umtx_op(
+            obj: *mut c_void,
+            op: c_int,
+            val: c_long,
+            a: *mut c_void,
+            b: *mut c_void,
+        ) -> c_int;
+    }
+
+    pub(super) fn wait(atomic: &AtomicU32, expected: u32) {
+        unsafe {
+            _umtx_op(
+                atomic.as_ptr().cast(),
+                UMTX_OP_WAIT_UINT_PRIVATE,
+                expected as c_long,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+    }
+
+    pub(super) fn wake(atomic: &AtomicU32, all: bool) {
+        let count = if all { c_int::MAX } else { 1 };
+        unsafe {
+            _umtx_op(
+                atomic.as_ptr().cast(),
+                UMTX_OP_WAKE_PRIVATE,
+                count as c_long,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+    }
+}
+
+#[cfg(all(
+    any(target_os = "macos", target_os = "ios", target_os = "tvos"),
+    not(feature = "loom")
+))]
+mod sys {
+    use std::{
+        ffi::{c_int, c_void},
+        sync::atomic::AtomicU32,
+    };
+
+    const UL_COMPARE_AND_WAIT: u32 = 1;
+    const ULF_WAKE_ALL: u32 = 0x100;
+    const ULF_NO_ERRNO: u32 = 0x0100_0000;
+
+    extern "C" {
+        fn __ulock_wait(operation: u32, addr: *mut c_void, value: u64, timeout: u32) -> c_int;
+        fn __ulock_wake(operation: u32, addr: *mut c_void, wake_value: u64) -> c_int;
+    }
+
+    pub(super) fn wait(atomic: &AtomicU32, expected: u32) {
+        unsafe {
+            __ulock_wait(
+                UL_COMPARE_AND_WAIT | ULF_NO_ERRNO,
+                atomic.as_ptr().cast(),
+                expected.into(),
+                0,
+            )
+        };
+    }
+
+    pub(super) fn wake(atomic: &AtomicU32, all: bool) {
+        let operation = UL_COMPARE_AND_WAIT | ULF_NO_ERRNO | if all { ULF_WAKE_ALL } else { 0 };
+        unsafe { __ulock_wake(operation, atomic.as_ptr().cast(), 0) };
+    }
+}
+
+#[cfg(all(target_os = "windows", not(feature = "loom")))]
+mod sys {
+    use std::{ffi::c_void, sync::atomic::AtomicU32};
+
+    const INFINITE: u32 = u32::MAX;
+
+    #[link(name = "synchronization")]
+    extern "system" {
+        fn WaitOnAddress(
+            addr: *const c_void,
+            compare: *const c_void,
+            size: usize,
+            timeout: u32,
+        ) -> i32;
+        fn WakeByAddressSingle(addr: *const c_void);
+        fn WakeByAddressAll(addr: *const c_void);
+    }
+
+    pub(super) fn wait(atomic: &AtomicU32, expected: u32) {
+        unsafe {
+            WaitOnAddress(
+                atomic.as_ptr().cast(),
+                (&expected as *const u32).cast(),
+                4,
+                INFINITE,
+            )
+        };
+    }
+
+    pub(super) fn wake(atomic: &AtomicU32, all: bool) {
+        if all {
+            unsafe { WakeByAddressAll(atomic.as_ptr().cast()) };
+        } else {
+            unsafe { WakeByAddressSingle(atomic.as_ptr().cast()) };
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "loom",
+    not(any(
+        target_os = "linux",
+        target_os = "freebsd",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "tvos",
+        target_os = "windows",
+    ))
+))]
+mod sys {
+    use crate::sync::atomic::{AtomicU32, Ordering};
+
+    pub(super) fn wait(atomic: &AtomicU32, expected: u32) {
+        if atomic.load(Ordering::Relaxed) == expected {
+            #[cfg(feature = "loom")]
+            loom::thread::yield_now();
+            #[cfg(not(feature = "loom"))]
+            std::hint::spin_loop();
+        }
+    }
+
+    pub(super) fn wake(_atomic: &AtomicU32, _all: bool) {}
+}
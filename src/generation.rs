@@ -0,0 +1,155 @@
+//! Per-tag generation counters, see [`Namespace::generation`](crate::Namespace::generation).
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    sync::atomic::{AtomicU64, Ordering},
+    Zeroable,
+};
+
+/// A zero-initializable generation counter, which is bumped whenever the data it guards changes so
+/// derived values can be invalidated (see [`Stamped`]).
+///
+/// This is returned by [`Namespace::generation`](crate::Namespace::generation).
+pub struct Generation(AtomicU64);
+
+unsafe impl Zeroable for Generation {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(AtomicU64::zeroed())
+    }
+}
+
+impl Generation {
+    /// Creates a counter at generation zero.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    /// Returns the current generation.
+    ///
+    /// Changes made before the generation was bumped are visible after this returns the bumped
+    /// generation.
+    #[inline]
+    pub fn current(&self) -> u64 {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Advances to the next generation, returning it.
+    #[inline]
+    pub fn bump(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::AcqRel) + 1
+    }
+}
+
+impl Default for Generation {
+    #[inline]
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl fmt::Debug for Generation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Generation").field(&self.current()).finish()
+    }
+}
+
+/// Storage of the [`Generation`] for `Tag`, so tags don't have to be [`Zeroable`] themselves.
+#[repr(transparent)]
+pub(crate) struct GenerationFor<Tag>(pub(crate) Generation, PhantomData<fn() -> Tag>);
+
+unsafe impl<Tag> Zeroable for GenerationFor<Tag> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(Generation::zeroed(), PhantomData)
+    }
+}
+
+/// A value derived from data guarded by a [`Generation`], stamped with the generation it was
+/// computed at.
+///
+/// ```rust
+/// use generic_statics::{define_namespace, Namespace, Stamped};
+///
+/// define_namespace!(Caches);
+///
+/// struct Routes;
+///
+/// let mut table = Stamped::new();
+/// let routes = Caches::generation::<Routes>();
+/// assert_eq!(*table.get_or_update(routes, || vec!["/"]), ["/"]);
+/// assert_eq!(*table.get_or_update(routes, || unreachable!()), ["/"]);
+///
+/// routes.bump();
+/// assert!(table.get(routes).is_none());
+/// assert_eq!(*table.get_or_update(routes, || vec!["/", "/about"]), ["/", "/about"]);
+/// ```
+#[derive(Clone)]
+pub struct Stamped<T> {
+    value: Option<T>,
+    generation: u64,
+}
+
+impl<T> Stamped<T> {
+    /// Creates an empty `Stamped`, which is stale for every generation.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            value: None,
+            generation: 0,
+        }
+    }
+
+    /// Returns the value if it was computed at the current generation of `generation`.
+    #[inline]
+    pub fn get(&self, generation: &Generation) -> Option<&T> {
+        self.value
+            .as_ref()
+            .filter(|_| self.generation == generation.current())
+    }
+
+    /// Returns the value, recomputing it with `f` if it wasn't computed at the current generation
+    /// of `generation`.
+    ///
+    /// The value is stamped with the generation read before calling `f`, so it's recomputed again
+    /// if the generation is bumped while `f` runs.
+    pub fn get_or_update(&mut self, generation: &Generation, f: impl FnOnce() -> T) -> &T {
+        let current = generation.current();
+        if self.value.is_none() || self.generation != current {
+            self.value = Some(f());
+            self.generation = current;
+        }
+        self.value.as_ref().unwrap()
+    }
+
+    /// Returns the generation the value was computed at, if any.
+    #[inline]
+    pub fn generation(&self) -> Option<u64> {
+        self.value.as_ref().map(|_| self.generation)
+    }
+
+    /// Drops the value, so the next access recomputes it.
+    #[inline]
+    pub fn invalidate(&mut self) {
+        self.value = None;
+    }
+}
+
+impl<T> Default for Stamped<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Stamped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stamped")
+            .field("value", &self.value)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
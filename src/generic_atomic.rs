@@ -0,0 +1,224 @@
+//! A typed atomic cell for small `Copy` types, see [`Namespace::atomic`](crate::Namespace::atomic).
+
+use std::{
+    cell::UnsafeCell,
+    fmt, mem,
+    num::{
+        NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU16, NonZeroU32,
+        NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+    sync::atomic::{AtomicU16, AtomicU32, AtomicU64, AtomicU8, Ordering},
+};
+
+use crate::Zeroable;
+
+/// Types whose values consist of initialized bytes only (no padding) and don't contain pointers,
+/// so they can be stored in a [`GenericAtomic`].
+///
+/// # Safety
+///
+/// Every byte of every value of the type must be initialized and the type must not carry
+/// provenance (i.e. contain references or pointers), as values are converted to and from
+/// integers of the same size.
+pub unsafe trait NoUninit: Copy + Zeroable {}
+
+macro_rules! impl_no_uninit {
+    ($($t:ty),+ $(,)?) => {
+        $(unsafe impl NoUninit for $t {})+
+    };
+}
+
+impl_no_uninit!(
+    u8,
+    u16,
+    u32,
+    u64,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    isize,
+    f32,
+    f64,
+    bool,
+    char,
+    ()
+);
+impl_no_uninit!(
+    Option<NonZeroU8>,
+    Option<NonZeroU16>,
+    Option<NonZeroU32>,
+    Option<NonZeroU64>,
+    Option<NonZeroUsize>,
+    Option<NonZeroI8>,
+    Option<NonZeroI16>,
+    Option<NonZeroI32>,
+    Option<NonZeroI64>,
+    Option<NonZeroIsize>,
+);
+
+unsafe impl<T: NoUninit, const N: usize> NoUninit for [T; N] {}
+
+/// An atomic cell for `T`, whose operations are implemented with the atomic integer type of the
+/// size of `T`.
+///
+/// ```rust
+/// use std::sync::atomic::Ordering;
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// #[derive(Copy, Clone, Debug, PartialEq)]
+/// #[repr(C)]
+/// struct Version {
+///     major: u16,
+///     minor: u16,
+/// }
+///
+/// unsafe impl generic_statics::Zeroable for Version {}
+/// unsafe impl generic_statics::NoUninit for Version {}
+///
+/// let version = Test::atomic::<Version>();
+/// version.store(Version { major: 1, minor: 2 }, Ordering::Release);
+/// assert_eq!(version.load(Ordering::Acquire).minor, 2);
+/// ```
+///
+/// `T` must be 1, 2, 4 or 8 bytes in size, which fails to compile otherwise. Values are compared
+/// by their bit patterns (e.g. by [`GenericAtomic::compare_exchange`]), not by `PartialEq`.
+#[repr(C, align(8))]
+pub struct GenericAtomic<T> {
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: The value is only accessed atomically.
+unsafe impl<T: Send> Sync for GenericAtomic<T> {}
+
+unsafe impl<T: Zeroable> Zeroable for GenericAtomic<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new_unchecked(T::zeroed())
+    }
+}
+
+/// Runs `$body` with `$atomic` referring to the value of `$self` as the atomic integer of the
+/// size of `T`.
+macro_rules! with_atomic {
+    ($self:ident, |$atomic:ident| $body:expr) => {{
+        let ptr = $self.value.get();
+        match mem::size_of::<T>() {
+            1 => {
+                let $atomic = unsafe { AtomicU8::from_ptr(ptr.cast()) };
+                $body
+            }
+            2 => {
+                let $atomic = unsafe { AtomicU16::from_ptr(ptr.cast()) };
+                $body
+            }
+            4 => {
+                let $atomic = unsafe { AtomicU32::from_ptr(ptr.cast()) };
+                $body
+            }
+            8 => {
+                let $atomic = unsafe { AtomicU64::from_ptr(ptr.cast()) };
+                $body
+            }
+            _ => unreachable!(),
+        }
+    }};
+}
+
+impl<T> GenericAtomic<T> {
+    #[inline]
+    const fn new_unchecked(value: T) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+        }
+    }
+}
+
+impl<T: NoUninit> GenericAtomic<T> {
+    /// Creates a new atomic cell.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        const { check::<T>() };
+        Self::new_unchecked(value)
+    }
+
+    /// Loads the value.
+    #[inline]
+    pub fn load(&self, order: Ordering) -> T {
+        const { check::<T>() };
+        with_atomic!(self, |atomic| from_bits(atomic.load(order)))
+    }
+
+    /// Stores `value`.
+    #[inline]
+    pub fn store(&self, value: T, order: Ordering) {
+        const { check::<T>() };
+        with_atomic!(self, |atomic| atomic.store(to_bits(value), order));
+    }
+
+    /// Stores `value`, returning the previous value.
+    #[inline]
+    pub fn swap(&self, value: T, order: Ordering) -> T {
+        const { check::<T>() };
+        with_atomic!(self, |atomic| from_bits(atomic.swap(to_bits(value), order)))
+    }
+
+    /// Stores `new` if the current value has the same bit pattern as `current`.
+    ///
+    /// Returns the previous value, which is `Ok` if it was replaced.
+    #[inline]
+    pub fn compare_exchange(
+        &self,
+        current: T,
+        new: T,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<T, T> {
+        const { check::<T>() };
+        with_atomic!(self, |atomic| {
+            let result = atomic.compare_exchange(to_bits(current), to_bits(new), success, failure);
+            result.map(from_bits).map_err(from_bits)
+        })
+    }
+
+    /// Returns a mutable reference to the value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Consumes the cell, returning the value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: NoUninit + fmt::Debug> fmt::Debug for GenericAtomic<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+    }
+}
+
+/// Fails compilation if there is no atomic integer of the size of `T`.
+const fn check<T>() {
+    assert!(
+        matches!(mem::size_of::<T>(), 1 | 2 | 4 | 8),
+        "the size of a `GenericAtomic` value must be 1, 2, 4 or 8 bytes"
+    );
+}
+
+/// Converts `value` to the integer of the same size.
+#[inline]
+fn to_bits<T: NoUninit, I: Copy>(value: T) -> I {
+    unsafe { mem::transmute_copy(&value) }
+}
+
+/// Converts `bits` (which were converted from a `T`, or are zero) back to a `T`.
+#[inline]
+fn from_bits<T: NoUninit, I: Copy>(bits: I) -> T {
+    unsafe { mem::transmute_copy(&bits) }
+}
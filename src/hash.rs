@@ -0,0 +1,37 @@
+//! Compile-time hashing used to derive storage symbol names.
+
+/// The initial state of [`hash`] (the FNV-1a offset basis).
+pub(crate) const SEED: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Continues the FNV-1a hash `state` with `bytes`.
+pub(crate) const fn hash(mut state: u64, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+    while i < bytes.len() {
+        state ^= bytes[i] as u64;
+        state = state.wrapping_mul(0x0100_0000_01b3);
+        i += 1;
+    }
+    state
+}
+
+/// Derives the salt of a namespace defined in the crate `name` of version `version`.
+///
+/// This is used by [`define_namespace`](crate::define_namespace) and never returns `0` (which
+/// marks unsalted namespaces).
+#[doc(hidden)]
+pub const fn __salt(name: Option<&str>, version: Option<&str>) -> u64 {
+    let mut state = SEED;
+    if let Some(name) = name {
+        state = hash(state, name.as_bytes());
+    }
+    state = hash(state, b"@");
+    if let Some(version) = version {
+        state = hash(state, version.as_bytes());
+    }
+
+    if state == 0 {
+        1
+    } else {
+        state
+    }
+}
@@ -0,0 +1,80 @@
+//! Parent relationships between namespaces, see [`Namespace::PARENT`](crate::Namespace::PARENT).
+
+use std::{any::TypeId, fmt, iter};
+
+/// Describes a [`Namespace`](crate::Namespace) and its position in the namespace hierarchy,
+/// returned by [`Namespace::info`](crate::Namespace::info).
+///
+/// ```rust
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(pub App);
+/// define_namespace!(pub Cache under App);
+///
+/// let cache = Cache::info();
+/// assert_eq!(cache.parent(), Some(App::info()));
+/// assert_eq!(cache.ancestors().count(), 1);
+/// assert!(Cache::is_within::<App>());
+/// ```
+#[derive(Copy, Clone)]
+pub struct NamespaceInfo {
+    name: &'static str,
+    type_id: TypeId,
+    parent: Option<fn() -> NamespaceInfo>,
+}
+
+impl NamespaceInfo {
+    #[inline]
+    pub(crate) fn new(
+        name: &'static str,
+        type_id: TypeId,
+        parent: Option<fn() -> NamespaceInfo>,
+    ) -> Self {
+        Self {
+            name,
+            type_id,
+            parent,
+        }
+    }
+
+    /// The type name of the namespace.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The [`TypeId`] of the namespace.
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// The parent namespace, if this namespace was defined `under` another one.
+    #[inline]
+    pub fn parent(&self) -> Option<NamespaceInfo> {
+        self.parent.map(|parent| parent())
+    }
+
+    /// Iterates over the parent, grandparent, etc. of the namespace.
+    pub fn ancestors(&self) -> impl Iterator<Item = NamespaceInfo> {
+        iter::successors(self.parent(), NamespaceInfo::parent)
+    }
+}
+
+impl PartialEq for NamespaceInfo {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+    }
+}
+
+impl Eq for NamespaceInfo {}
+
+impl fmt::Debug for NamespaceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NamespaceInfo")
+            .field("name", &self.name)
+            .field("parent", &self.parent().map(|parent| parent.name))
+            .finish()
+    }
+}
@@ -0,0 +1,107 @@
+//! Function hook slots, see [`Namespace::hook`](crate::Namespace::hook).
+
+use std::{fmt, marker::PhantomData, mem, ptr};
+
+use crate::{
+    sync::atomic::{AtomicPtr, Ordering},
+    Zeroable,
+};
+
+/// A zero-initializable slot holding an optional `fn(Args) -> Ret`, e.g. for fault injection in
+/// tests or for reporting hooks.
+///
+/// This is returned by [`Namespace::hook`](crate::Namespace::hook). Multiple arguments are passed
+/// as a tuple.
+pub struct GenericHook<Args, Ret> {
+    f: AtomicPtr<()>,
+    _signature: PhantomData<fn(Args) -> Ret>,
+}
+
+unsafe impl<Args, Ret> Zeroable for GenericHook<Args, Ret> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            f: AtomicPtr::zeroed(),
+            _signature: PhantomData,
+        }
+    }
+}
+
+impl<Args, Ret> GenericHook<Args, Ret> {
+    /// Creates an empty hook.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            f: AtomicPtr::new(ptr::null_mut()),
+            _signature: PhantomData,
+        }
+    }
+
+    /// Sets the hook to `f`, returning the previous function, if any.
+    #[inline]
+    pub fn set(&self, f: fn(Args) -> Ret) -> Option<fn(Args) -> Ret> {
+        unsafe { Self::from_ptr(self.f.swap(f as *mut (), Ordering::AcqRel)) }
+    }
+
+    /// Removes and returns the function, if any.
+    #[inline]
+    pub fn take(&self) -> Option<fn(Args) -> Ret> {
+        unsafe { Self::from_ptr(self.f.swap(ptr::null_mut(), Ordering::AcqRel)) }
+    }
+
+    /// Returns the function, if any.
+    #[inline]
+    pub fn get(&self) -> Option<fn(Args) -> Ret> {
+        unsafe { Self::from_ptr(self.f.load(Ordering::Acquire)) }
+    }
+
+    /// Returns whether a function is set.
+    #[inline]
+    pub fn is_set(&self) -> bool {
+        !self.f.load(Ordering::Relaxed).is_null()
+    }
+
+    /// Calls the function with `args` if one is set.
+    #[inline]
+    pub fn call_if_set(&self, args: Args) -> Option<Ret> {
+        self.get().map(|f| f(args))
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be null or a `fn(Args) -> Ret`.
+    #[inline]
+    unsafe fn from_ptr(ptr: *mut ()) -> Option<fn(Args) -> Ret> {
+        (!ptr.is_null()).then(|| mem::transmute::<*mut (), fn(Args) -> Ret>(ptr))
+    }
+}
+
+impl<Args, Ret> Default for GenericHook<Args, Ret> {
+    #[inline]
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl<Args, Ret> fmt::Debug for GenericHook<Args, Ret> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenericHook")
+            .field("is_set", &self.is_set())
+            .finish()
+    }
+}
+
+/// Storage of the [`GenericHook`] for `Tag`, so tags don't have to be [`Zeroable`] themselves.
+#[repr(transparent)]
+pub(crate) struct HookFor<Args, Ret, Tag>(
+    pub(crate) GenericHook<Args, Ret>,
+    PhantomData<fn() -> Tag>,
+);
+
+unsafe impl<Args, Ret, Tag> Zeroable for HookFor<Args, Ret, Tag> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(GenericHook::zeroed(), PhantomData)
+    }
+}
@@ -0,0 +1,52 @@
+//! Huge page advice, see [`Namespace::advise_huge_pages`](crate::Namespace::advise_huge_pages).
+
+use std::{io, ptr::NonNull};
+
+/// Advises the kernel to back the pages of the `len` bytes at `addr` with huge pages.
+pub(crate) fn advise(addr: NonNull<u8>, len: usize) -> io::Result<()> {
+    if len == 0 {
+        return Ok(());
+    }
+
+    unsafe { sys::advise(addr, len) }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod sys {
+    use std::{
+        ffi::{c_int, c_long, c_void},
+        io,
+        ptr::NonNull,
+    };
+
+    const MADV_HUGEPAGE: c_int = 14;
+    const SC_PAGESIZE: c_int = 30;
+
+    extern "C" {
+        fn madvise(addr: *mut c_void, len: usize, advice: c_int) -> c_int;
+        fn sysconf(name: c_int) -> c_long;
+    }
+
+    pub(super) unsafe fn advise(addr: NonNull<u8>, len: usize) -> io::Result<()> {
+        // `madvise` requires a page-aligned address.
+        let page = unsafe { sysconf(SC_PAGESIZE) } as usize;
+        let offset = (page - addr.as_ptr().align_offset(page)) % page;
+        let start = addr.as_ptr().wrapping_sub(offset);
+
+        match unsafe { madvise(start.cast(), len + offset, MADV_HUGEPAGE) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+}
+
+// Other targets either use huge pages transparently (FreeBSD, macOS) or only for dedicated
+// allocations (Windows).
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod sys {
+    use std::{io, ptr::NonNull};
+
+    pub(super) unsafe fn advise(_addr: NonNull<u8>, _len: usize) -> io::Result<()> {
+        Ok(())
+    }
+}
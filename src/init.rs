@@ -0,0 +1,125 @@
+//! Initial values of the storage.
+
+use std::{
+    fmt,
+    mem::{self, ManuallyDrop, MaybeUninit},
+};
+
+use crate::Zeroable;
+
+/// Types with a compile-time initial value, see [`Namespace::generic_static_init`].
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicU32, Ordering};
+/// use generic_statics::{define_namespace, ConstInit, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// struct Config {
+///     max_retries: AtomicU32,
+/// }
+///
+/// impl ConstInit for Config {
+///     const INIT: Self = Config {
+///         max_retries: AtomicU32::new(16),
+///     };
+/// }
+///
+/// let config = Test::generic_static_init::<Config>();
+/// assert_eq!(config.max_retries.load(Ordering::Relaxed), 16);
+/// ```
+///
+/// The bytes of [`ConstInit::INIT`] are emitted into the storage at compile time, so `INIT` must
+/// not contain padding bytes or pointers (which fails to compile) and is limited to 512 bytes.
+///
+/// [`Namespace::generic_static_init`]: crate::Namespace::generic_static_init
+pub trait ConstInit: Sized {
+    /// The initial value of the static.
+    const INIT: Self;
+}
+
+/// Storage of a [`ConstInit`] type, so it doesn't share the storage of a [`Zeroable`] type.
+#[repr(transparent)]
+pub(crate) struct Init<T>(pub(crate) T);
+
+impl<T: fmt::Debug> fmt::Debug for Init<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Storage that isn't initialized at startup, see
+/// [`Namespace::generic_static_uninit`](crate::Namespace::generic_static_uninit).
+#[repr(transparent)]
+pub(crate) struct Noinit<T>(pub(crate) MaybeUninit<T>);
+
+/// The maximum size of a [`ConstInit`] type, in words.
+#[cfg_attr(any(feature = "loom", miri), allow(dead_code))]
+pub(crate) const MAX_WORDS: usize = 64;
+
+/// Types the storage can be reserved for.
+pub trait Storage: 'static + Sized {
+    /// Whether the initial value is all zeroes.
+    #[cfg_attr(any(feature = "loom", miri), allow(dead_code))]
+    const ZEROED: bool;
+
+    /// The bytes of the initial value as little-endian words, only used if not [`Self::ZEROED`].
+    #[cfg_attr(any(feature = "loom", miri), allow(dead_code))]
+    const WORDS: [u64; MAX_WORDS] = [0; MAX_WORDS];
+
+    /// Whether the storage is left uninitialized at startup (takes precedence over
+    /// [`Self::ZEROED`]).
+    #[cfg_attr(any(feature = "loom", miri), allow(dead_code))]
+    const NOINIT: bool = false;
+
+    /// Creates the initial value at runtime (used by the heap-backed backends).
+    #[cfg_attr(not(any(feature = "loom", miri)), allow(dead_code))]
+    fn init() -> Self;
+}
+
+impl<T: 'static + Zeroable> Storage for T {
+    const ZEROED: bool = true;
+
+    #[inline]
+    fn init() -> Self {
+        T::zeroed()
+    }
+}
+
+impl<T: 'static + ConstInit> Storage for Init<T> {
+    const ZEROED: bool = false;
+    const WORDS: [u64; MAX_WORDS] = words(&ManuallyDrop::new(T::INIT));
+
+    #[inline]
+    fn init() -> Self {
+        Init(T::INIT)
+    }
+}
+
+impl<T: 'static> Storage for Noinit<T> {
+    const ZEROED: bool = true;
+    const NOINIT: bool = true;
+
+    #[inline]
+    fn init() -> Self {
+        Noinit(MaybeUninit::uninit())
+    }
+}
+
+/// Returns the bytes of `value` as little-endian words.
+#[cfg_attr(any(feature = "loom", miri), allow(dead_code))]
+const fn words<T>(value: &T) -> [u64; MAX_WORDS] {
+    assert!(
+        mem::size_of::<T>() <= MAX_WORDS * 8,
+        "the initial value of this generic static exceeds the maximum of 512 bytes"
+    );
+
+    let bytes = (value as *const T).cast::<u8>();
+    let mut words = [0; MAX_WORDS];
+    let mut i = 0;
+    while i < mem::size_of::<T>() {
+        words[i / 8] |= (unsafe { *bytes.add(i) } as u64) << (i % 8 * 8);
+        i += 1;
+    }
+    words
+}
@@ -0,0 +1,236 @@
+//! Link-time descriptors of the reserved storage (`introspection` feature).
+//!
+//! Every storage definition emitted by [`crate::asm`] also emits a [`StaticEntry`] into a dedicated
+//! section, which refers to the storage and to a function describing it. [`statics`] walks these
+//! entries at runtime.
+//!
+//! Only the storage of the linked image walking the entries is described. Zero-sized statics and
+//! the statics of freezable namespaces don't reserve storage at link time and aren't described.
+
+use std::ptr;
+
+use crate::StaticDescriptor;
+
+/// A described storage definition.
+///
+/// These are the elements of [`GENERIC_STATICS`] with the `linkme` feature.
+#[repr(C)]
+pub struct StaticEntry {
+    /// The function describing the storage, `None` for sentinels.
+    describe: Option<fn() -> StaticDescriptor>,
+    /// The address of the storage.
+    addr: *const (),
+}
+
+// SAFETY: Entries are never mutated.
+unsafe impl Sync for StaticEntry {}
+
+impl StaticEntry {
+    #[allow(dead_code)]
+    const SENTINEL: Self = Self {
+        describe: None,
+        addr: ptr::null(),
+    };
+
+    /// Returns the descriptor of the storage, `None` for the (internal) sentinels.
+    #[inline]
+    pub fn descriptor(&self) -> Option<StaticDescriptor> {
+        let describe = self.describe?;
+        Some(describe().at(self.addr))
+    }
+}
+
+/// The descriptors of the generic statics of this linked image as a `linkme` distributed slice
+/// (`linkme` feature).
+///
+/// ```rust
+/// use generic_statics::{define_namespace, Namespace, GENERIC_STATICS};
+///
+/// define_namespace!(Test);
+///
+/// let addr = Test::generic_static::<[u64; 2]>() as *const _ as *const ();
+/// assert!(GENERIC_STATICS
+///     .iter()
+///     .filter_map(|entry| entry.descriptor())
+///     .any(|s| s.addr() == addr));
+/// ```
+///
+/// Unlike [`statics`], this may contain multiple entries for the same storage (one per object
+/// file instantiating it, on targets other than ELF).
+#[cfg(feature = "linkme")]
+#[linkme::distributed_slice]
+pub static GENERIC_STATICS: [StaticEntry];
+
+#[cfg(all(
+    any(target_os = "none", target_os = "linux", target_os = "freebsd"),
+    not(feature = "linkme")
+))]
+mod section {
+    use super::StaticEntry;
+
+    // The entries are only read by Rust code.
+    #[allow(improper_ctypes)]
+    extern "C" {
+        #[link_name = "__start_generic_statics_descriptors"]
+        pub(super) static START: StaticEntry;
+        #[link_name = "__stop_generic_statics_descriptors"]
+        pub(super) static STOP: StaticEntry;
+    }
+}
+
+#[cfg(all(
+    any(target_os = "macos", target_os = "ios", target_os = "tvos"),
+    not(feature = "linkme")
+))]
+mod section {
+    use super::StaticEntry;
+
+    // Makes sure the section (and therefore its bounds) exists even without any storage.
+    #[used]
+    #[link_section = "__DATA,__gs_descs,regular,no_dead_strip"]
+    static ANCHOR: StaticEntry = StaticEntry::SENTINEL;
+
+    // The entries are only read by Rust code.
+    #[allow(improper_ctypes)]
+    extern "C" {
+        #[link_name = "\x01section$start$__DATA$__gs_descs"]
+        pub(super) static START: StaticEntry;
+        #[link_name = "\x01section$end$__DATA$__gs_descs"]
+        pub(super) static STOP: StaticEntry;
+    }
+}
+
+#[cfg(all(target_os = "windows", not(feature = "linkme")))]
+mod section {
+    use super::StaticEntry;
+
+    // The linker sorts the `.gsdesc$*` sections by their suffix, so the entries (`$m`) end up
+    // between these bounds.
+    #[used]
+    #[link_section = ".gsdesc$a"]
+    pub(super) static START: StaticEntry = StaticEntry::SENTINEL;
+    #[used]
+    #[link_section = ".gsdesc$z"]
+    pub(super) static STOP: StaticEntry = StaticEntry::SENTINEL;
+}
+
+/// Returns all entries of this linked image.
+#[cfg(feature = "linkme")]
+fn entries() -> &'static [StaticEntry] {
+    &GENERIC_STATICS
+}
+
+/// Returns all entries of this linked image.
+#[cfg(not(feature = "linkme"))]
+fn entries() -> &'static [StaticEntry] {
+    // Makes sure the section (and therefore its bounds) exists even without any storage. This
+    // has to live in the same object file as the references to the bounds.
+    #[cfg(any(target_os = "none", target_os = "linux", target_os = "freebsd"))]
+    unsafe {
+        std::arch::asm!(
+            ".pushsection generic_statics_descriptors,\"awR\",@progbits",
+            ".p2align 3",
+            ".quad 0",
+            ".quad 0",
+            ".popsection",
+            options(nomem, nostack, preserves_flags),
+        );
+    }
+
+    let start = ptr::addr_of!(section::START);
+    let stop = ptr::addr_of!(section::STOP);
+
+    unsafe { std::slice::from_raw_parts(start, stop.offset_from(start) as usize) }
+}
+
+/// Returns the descriptors of all generic statics of this linked image, ordered by address.
+///
+/// ```rust
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// let addr = Test::generic_static::<[u64; 2]>() as *const _ as *const ();
+/// let statics = generic_statics::statics();
+/// assert!(statics.iter().any(|s| s.addr() == addr && s.type_name() == "[u64; 2]"));
+/// ```
+///
+/// Statics are described if their accessor is instantiated in the binary, even if they were
+/// never accessed.
+pub fn statics() -> Vec<StaticDescriptor> {
+    let mut statics = entries()
+        .iter()
+        .filter_map(StaticEntry::descriptor)
+        .collect::<Vec<_>>();
+
+    // Entries are emitted once per object file (and deduplicated by the linker on ELF and COFF).
+    statics.sort_unstable_by_key(|descriptor| descriptor.addr());
+    statics.dedup_by_key(|descriptor| descriptor.addr());
+    statics
+}
+
+/// Aggregate statistics of the generic statics of this linked image, see [`stats`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    count: usize,
+    size: usize,
+    padding: usize,
+}
+
+impl Stats {
+    /// The number of statics.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The number of bytes reserved for the statics (zero-sized statics reserve one byte).
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The number of bytes between adjacent statics that were lost to their alignment.
+    ///
+    /// Only gaps smaller than the alignment of the following static are counted, larger ones are
+    /// attributed to other data.
+    #[inline]
+    pub fn padding(&self) -> usize {
+        self.padding
+    }
+}
+
+/// Returns statistics about the generic statics of this linked image, see [`statics`].
+///
+/// ```rust
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// let _ = Test::generic_static::<[u64; 128]>();
+/// let stats = generic_statics::stats();
+/// assert!(stats.count() >= 1);
+/// assert!(stats.size() >= 1024);
+/// ```
+pub fn stats() -> Stats {
+    let mut stats = Stats::default();
+    let mut end = None;
+    for descriptor in statics() {
+        // The nightly toolchains this crate requires have `<*const T>::addr`.
+        #[allow(clippy::incompatible_msrv)]
+        let addr = descriptor.addr().addr();
+        let size = descriptor.size().max(1);
+
+        if let Some(end) = end {
+            let gap = addr.wrapping_sub(end);
+            if gap < descriptor.align() {
+                stats.padding += gap;
+            }
+        }
+
+        stats.count += 1;
+        stats.size += size;
+        end = Some(addr + size);
+    }
+    stats
+}
@@ -0,0 +1,45 @@
+//! Statics keyed by a marker type, an integer or a name, see
+//! [`Namespace::generic_static_keyed`](crate::Namespace::generic_static_keyed),
+//! [`Namespace::generic_static_id`](crate::Namespace::generic_static_id) and
+//! [`generic_static_named`](crate::generic_static_named).
+
+use std::marker::PhantomData;
+
+use crate::{hash, Zeroable};
+
+/// Storage of the static `T` for `Tag`, so the same `T` can have multiple statics.
+#[repr(transparent)]
+pub(crate) struct Keyed<T, Tag>(pub(crate) T, PhantomData<fn() -> Tag>);
+
+unsafe impl<T: Zeroable, Tag> Zeroable for Keyed<T, Tag> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(T::zeroed(), PhantomData)
+    }
+}
+
+/// Storage of the `N` replicas of `T`, see
+/// [`Namespace::generic_static_indexed`](crate::Namespace::generic_static_indexed).
+#[repr(transparent)]
+pub(crate) struct Replicas<T, const N: usize>(pub(crate) [T; N]);
+
+unsafe impl<T: Zeroable, const N: usize> Zeroable for Replicas<T, N> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(std::array::from_fn(|_| T::zeroed()))
+    }
+}
+
+/// The tag of the static with the integer key `ID`.
+pub(crate) struct Id<const ID: u64>;
+
+/// The tag of the static with the name hashing to `HASH`, see
+/// [`generic_static_named`](crate::generic_static_named).
+#[doc(hidden)]
+pub struct __Named<const HASH: u64>;
+
+/// Hashes the name of a static for [`generic_static_named`](crate::generic_static_named).
+#[doc(hidden)]
+pub const fn __name_hash(name: &str) -> u64 {
+    hash::hash(hash::SEED, name.as_bytes())
+}
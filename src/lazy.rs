@@ -0,0 +1,101 @@
+//! Lazily initialized storage, see [`Namespace::generic_lazy`](crate::Namespace::generic_lazy).
+
+use std::{cell::UnsafeCell, mem, mem::MaybeUninit};
+
+use crate::{
+    sync::atomic::{AtomicU8, Ordering},
+    Zeroable,
+};
+
+const UNINIT: u8 = 0;
+const RUNNING: u8 = 1;
+const DONE: u8 = 2;
+
+/// A value that is initialized on first access.
+///
+/// Unlike `std::sync::OnceLock`, this is zero-initializable so it can be used as a generic static.
+pub(crate) struct Lazy<T> {
+    state: AtomicU8,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: The value is only written once (by the thread moving the state to `RUNNING`) and only
+// shared after the state has been moved to `DONE`.
+unsafe impl<T: Send + Sync> Sync for Lazy<T> {}
+
+unsafe impl<T> Zeroable for Lazy<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            state: AtomicU8::zeroed(),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+}
+
+impl<T> Lazy<T> {
+    /// Returns the value, initializing it with `f` if this is the first access.
+    ///
+    /// Concurrent callers wait for the initialization to complete. If `f` panics, the next
+    /// caller retries the initialization.
+    #[inline]
+    pub(crate) fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        if self.state.load(Ordering::Acquire) != DONE {
+            self.initialize(f);
+        }
+
+        unsafe { (*self.value.get()).assume_init_ref() }
+    }
+
+    /// Takes the value out, leaving this uninitialized.
+    #[cfg(feature = "teardown")]
+    pub(crate) fn take(&self) -> Option<T> {
+        self.state
+            .compare_exchange(DONE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+            .ok()?;
+
+        let value = unsafe { (*self.value.get()).assume_init_read() };
+        self.state.store(UNINIT, Ordering::Release);
+        Some(value)
+    }
+
+    #[cold]
+    fn initialize(&self, f: impl FnOnce() -> T) {
+        loop {
+            match self
+                .state
+                .compare_exchange(UNINIT, RUNNING, Ordering::Acquire, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    let reset = Reset(&self.state);
+                    let value = f();
+                    unsafe { (*self.value.get()).write(value) };
+                    mem::forget(reset);
+
+                    self.state.store(DONE, Ordering::Release);
+                    return;
+                }
+                Err(DONE) => return,
+                Err(_) => yield_now(),
+            }
+        }
+    }
+}
+
+/// Resets the state if the initialization panicked.
+struct Reset<'a>(&'a AtomicU8);
+
+impl Drop for Reset<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.store(UNINIT, Ordering::Release);
+    }
+}
+
+#[inline]
+fn yield_now() {
+    #[cfg(feature = "loom")]
+    loom::thread::yield_now();
+    #[cfg(not(feature = "loom"))]
+    std::thread::yield_now();
+}
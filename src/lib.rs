@@ -1,4 +1,20 @@
 #![feature(asm_const)]
+#![feature(cfg_sanitize)]
+#![cfg_attr(not(any(feature = "loom", miri)), feature(const_type_name))]
+#![feature(strict_provenance_lints)]
+#![feature(sync_unsafe_cell)]
+#![cfg_attr(
+    all(
+        feature = "dump",
+        any(
+            not(any(feature = "loom", miri)),
+            all(feature = "touched-statics", not(feature = "loom"))
+        )
+    ),
+    feature(specialization),
+    allow(incomplete_features)
+)]
+#![deny(fuzzy_provenance_casts, lossy_provenance_casts)]
 
 //! A "workaround" for missing generic statics in Rust.
 //!
@@ -39,13 +55,97 @@
 //!
 //! The used approach relies on inline assembly to instantiate/reserve static data for each
 //! monomorphized variant of the function.
-//! Unfortunately inlining will return a different version of the data and thus will not return
-//! stable addresses.
-//! However, [`Namespace::generic_static`] is marked `#[inline(never)]` which should provide stable
-//! addresses in most situations
-//! (Note that `#[inline(never)]` is just a hint to the compiler and doesn't guarantee anything).
+//! The storage is emitted as a hidden linkonce (weak/COMDAT) symbol named after the namespace and
+//! type, so that copies of the function created by inlining or by multiple codegen units resolve
+//! to the same storage.
+//! However, this relies on the linker deduplicating these symbols and doesn't extend beyond a
+//! single linked image (e.g. a `cdylib` and the executable loading it have separate storage).
+//! Namespaces can opt into storage shared by all crates instead, whose symbols are named after the
+//! type names (with the limits described in [`Identity::Process`]), or into storage that another
+//! object file can provide (e.g. at a fixed address), see [`Namespace::WEAK`].
 //!
-//! Only "zeroable" types are allowed for now due to inline asm restrictions.
+//! If you need a hard guarantee, [`Namespace::generic_static_strict`] canonicalizes every
+//! resolved address through a per-namespace registry, at the cost of an additional lookup.
+//!
+//! To catch broken address stability during testing, the `check-duplicates` feature records
+//! every resolved address in debug builds and panics if a `(namespace, type)` pair ever resolves
+//! to two different addresses.
+//!
+//! The `verify-at-startup` feature registers every emitted storage and checks the registry
+//! before `main` runs: the process aborts if a `(namespace, type)` pair of a
+//! [`Identity::Process`] namespace has more than one storage location in the executable (or in
+//! any other linked image).
+//!
+//! The `canaries` feature follows every emitted storage with a canary, which [`check_canaries`]
+//! checks for out-of-bounds writes past the end of a static.
+//!
+//! Only "zeroable" types are allowed for now due to inline asm restrictions. With the `bytemuck`
+//! feature, types implementing `bytemuck::Zeroable` can be used by wrapping them in
+//! [`ByteZeroed`]. Types with a compile-time initial value (see [`ConstInit`]) can be used with
+//! [`Namespace::generic_static_init`] instead.
+//!
+//! The storage of a single static is limited to 1 GiB (so it stays reachable by PC-relative
+//! addressing) and to the maximum alignment of the target's object format: 64 KiB on ELF targets,
+//! 32 KiB on Apple targets and 4 KiB (the section alignment of the image) on Windows. Larger types
+//! fail to compile:
+//!
+//! ```rust,compile_fail
+//! use generic_statics::{define_namespace, Namespace};
+//!
+//! define_namespace!(Test);
+//!
+//! let _ = Test::generic_static::<[u8; 1 << 31]>();
+//! ```
+//!
+//! With the `loom` feature, statics are backed by a deterministic heap allocation per
+//! [`loom`](https://docs.rs/loom) execution and [`sync`] re-exports loom's atomics, so data
+//! structures built on top of generic statics can be model-checked.
+//!
+//! Under Miri, statics are backed by leaked heap allocations instead of inline assembly. Pointers
+//! are handled strictly via [`NonNull`](std::ptr::NonNull) and never round-trip through
+//! integers, so the crate is clean under `-Zmiri-strict-provenance`.
+//!
+//! With the `dylib-dedup` feature, the first access to every static is routed through a
+//! process-global registry, so dynamically loaded libraries and the executable agree on a single
+//! address per `(namespace, type)`. The registry is looked up through the exported
+//! `generic_statics_canonicalize_v1` symbol, so the executable has to export its symbols
+//! dynamically (e.g. with `-Zexport-executable-symbols` or `-C link-arg=-rdynamic`), otherwise
+//! every image falls back to its own registry.
+//!
+//! With the `introspection` feature, every storage also emits a descriptor (namespace, type
+//! name, size, alignment and address) into a dedicated section, which can be enumerated at
+//! runtime with [`statics`] and `Namespace::iter_statics`, and aggregated with [`stats`].
+//! With the `dump` feature, the descriptors also capture the `Debug` implementation of the
+//! statics (using `#![feature(specialization)]`), so `Namespace::dump` prints their values.
+//! With the `snapshot` feature, `Namespace::snapshot` copies the described storage of a
+//! namespace, so tests can restore its state later.
+//! With the `reset` feature, `Namespace::reset_all` resets the described storage of a namespace
+//! to its initial value.
+//! With the `linkme` feature, the descriptors are the elements of the
+//! [`linkme`](https://docs.rs/linkme) distributed slice `GENERIC_STATICS` instead.
+//!
+//! With the `touched-statics` feature, the first access to every static is recorded in a global
+//! list, so [`touched_statics`] enumerates the statics that were actually used during a run, and
+//! `Namespace::set_first_access_hook` is notified about them.
+//!
+//! With the `tracing` or `log` features (which enable `touched-statics`), the first access to
+//! every static is also logged as a debug event (with the target `generic_statics`).
+//! The `defmt` feature does the same with [`defmt`](https://docs.rs/defmt) (whose `DEFMT_LOG`
+//! filter has to enable debug events of this crate), which requires a `defmt` global logger.
+//!
+//! With the `shared-resolver` feature, the accessors call a single non-generic resolver with a
+//! constant descriptor of the static instead of inlining the resolution, which reduces the code
+//! size of binaries with many instantiations and bookkeeping features (e.g. `dylib-dedup`) at the
+//! cost of a call and an indirect call per access.
+//!
+//! With the `storage-range` feature, the zeroed storage of every namespace is emitted into a
+//! section of its own, whose address range is returned by `Namespace::storage_range`.
+//!
+//! With the `metrics` feature, `Namespace::metric` provides per-tag counters, gauges and
+//! histograms, which exporters collect with `gather`.
+//!
+//! Binaries built with `-Zsanitizer=address` or `-Zsanitizer=memory` are supported: the storage
+//! is explicitly unpoisoned on every resolution.
 //!
 //! This crate only supports these targets for now:
 //!
@@ -55,18 +155,281 @@
 //! - Windows `x86_64`
 //!
 
+mod aligned;
+#[cfg(not(any(feature = "loom", miri)))]
+mod asm;
+#[cfg(feature = "async-lazy")]
+mod async_lazy;
+mod batch;
+mod cache_aligned;
+mod cached;
+#[cfg(all(feature = "canaries", not(any(feature = "loom", miri))))]
+mod canaries;
+mod claim;
+mod component;
+mod counter;
+#[cfg(feature = "critical-section")]
+mod cs_mutex;
+#[cfg(any(
+    all(feature = "introspection", not(any(feature = "loom", miri))),
+    all(feature = "touched-statics", not(feature = "loom"))
+))]
+mod descriptor;
+#[cfg(all(
+    feature = "dump",
+    any(
+        not(any(feature = "loom", miri)),
+        all(feature = "touched-statics", not(feature = "loom"))
+    )
+))]
+mod dump;
+#[cfg(feature = "dylib-dedup")]
+mod dylib;
+#[cfg(unix)]
+mod fork;
+#[cfg(all(
+    feature = "freeze",
+    any(unix, windows),
+    not(any(feature = "loom", miri))
+))]
+mod freeze;
+mod futex;
+mod generation;
+mod generic_atomic;
+mod hash;
+mod hierarchy;
+mod hook;
+#[cfg(any(unix, windows))]
+mod huge_pages;
+mod init;
+#[cfg(all(feature = "introspection", not(any(feature = "loom", miri))))]
+mod introspect;
+mod keyed;
+mod lazy;
+mod list;
+#[cfg(not(target_os = "none"))]
+mod local_key;
+#[cfg(feature = "loom")]
+mod loom;
+#[cfg(all(feature = "metrics", not(feature = "loom")))]
+mod metrics;
+#[cfg(all(miri, not(feature = "loom")))]
+mod miri;
+#[cfg(any(unix, windows))]
+mod mlock;
+mod mutex;
+mod once;
+mod once_lock;
+#[cfg(all(
+    target_os = "none",
+    any(target_arch = "x86_64", target_arch = "aarch64"),
+    not(any(feature = "loom", miri))
+))]
+pub mod percpu;
+mod plugin;
+mod pool;
+mod queue;
+mod rcu;
+mod registry;
+#[cfg(all(feature = "reset", not(any(feature = "loom", miri))))]
+mod reset;
+#[cfg(feature = "shared-resolver")]
+mod resolver;
+mod ring;
+mod rwlock;
+mod sanitizer;
+mod section;
+mod seqlock;
+mod shard;
+#[cfg(all(feature = "snapshot", not(any(feature = "loom", miri))))]
+mod snapshot;
+mod static_ref;
+pub mod sync;
+#[cfg(feature = "teardown")]
+mod teardown;
+#[cfg(not(target_os = "none"))]
+mod tls;
+#[cfg(all(feature = "touched-statics", not(feature = "loom")))]
+mod touched;
+mod type_index;
+mod type_map;
+#[cfg(all(feature = "verify-at-startup", not(any(feature = "loom", miri))))]
+mod verify;
+#[cfg(feature = "zeroize")]
+mod wipe;
 mod zeroable;
 
-use std::{any::TypeId, mem, ptr};
+use aligned::Aligned;
+use init::{Init, Noinit, Storage};
+use lazy::Lazy;
+use std::{
+    any::{self, TypeId},
+    cell::{SyncUnsafeCell, UnsafeCell},
+    mem::{self, MaybeUninit},
+    pin::Pin,
+    ptr::NonNull,
+};
 
+pub use aligned::{Align, Alignment, MAX_PAGE_SIZE};
+#[cfg(not(any(feature = "loom", miri)))]
+#[doc(hidden)]
+pub use asm::{
+    budget as __budget, crate_static as __crate_static, generic_static as __generic_static,
+    namespace_key as __namespace_key, storage_key as __storage_key, storage_size as __storage_size,
+    weak as __weak,
+};
+pub use batch::StaticTuple;
+pub use cache_aligned::CacheAligned;
+#[cfg(all(feature = "canaries", not(any(feature = "loom", miri))))]
+pub use canaries::{check_canaries, CorruptedCanary};
+pub use component::ComponentMeta;
+#[cfg(feature = "critical-section")]
+pub use cs_mutex::GenericCsMutex;
+#[cfg(any(
+    all(feature = "introspection", not(any(feature = "loom", miri))),
+    all(feature = "touched-statics", not(feature = "loom"))
+))]
+pub use descriptor::StaticDescriptor;
+pub use generation::{Generation, Stamped};
+pub use generic_atomic::{GenericAtomic, NoUninit};
+/// Declares a named accessor of a generic static (`macros` feature).
+///
+/// The attribute takes the namespace and is applied to a function declaration without a body,
+/// whose return type is the `&'static` reference to the static. Every accessor (and every
+/// instantiation of its generic parameters) has its own static:
+///
+/// ```rust
+/// # #[cfg(feature = "macros")]
+/// # {
+/// use generic_statics::{define_namespace, generic_static_accessor, Zeroable};
+///
+/// define_namespace!(Buffers);
+///
+/// /// The scratch buffer of `T`.
+/// #[generic_static_accessor(Buffers)]
+/// pub fn buffer<T: Zeroable>() -> &'static [T; 64];
+///
+/// assert_eq!(buffer::<u32>()[0], 0);
+/// # }
+/// ```
+///
+/// This is a function rather than a `static BUFFER<T>: [T; 64]` item, as Rust doesn't parse
+/// statics with generic parameters (even as the input of an attribute). The accessor requires
+/// the type of the static to be `'static`, [`Zeroable`] and `Sync`. See [`generic_static!`] for
+/// statics declared inside of functions.
+#[cfg(feature = "macros")]
+pub use generic_statics_macros::generic_static_accessor;
+/// Defines a namespace for a module (`macros` feature).
+///
+/// The attribute adds a namespace (named `Statics` unless another name is passed) and a
+/// `statics::<T>()` helper returning the generic static of `T` in that namespace to the module:
+///
+/// ```rust
+/// # #[cfg(feature = "macros")]
+/// #[generic_statics::namespace]
+/// mod registry {
+///     use std::sync::atomic::{AtomicUsize, Ordering};
+///
+///     pub fn register<T: 'static>() -> usize {
+///         statics::<AtomicUsize>().fetch_add(1, Ordering::Relaxed)
+///     }
+/// }
+/// ```
+///
+/// The module has to be inline. As the namespace is a type defined in the module, its statics
+/// are separate from those of any other module.
+#[cfg(feature = "macros")]
+pub use generic_statics_macros::namespace;
+#[doc(hidden)]
+pub use hash::__salt;
+pub use hierarchy::NamespaceInfo;
+pub use hook::GenericHook;
+pub use init::ConstInit;
+#[doc(hidden)]
+pub use init::Storage as __Storage;
+#[cfg(all(feature = "introspection", not(any(feature = "loom", miri))))]
+pub use introspect::{statics, stats, Stats};
+#[cfg(all(feature = "linkme", not(any(feature = "loom", miri))))]
+pub use introspect::{StaticEntry, GENERIC_STATICS};
+#[doc(hidden)]
+pub use keyed::{__Named, __name_hash};
+pub use list::{GenericListHead, ListIter, ListNode};
+#[cfg(not(target_os = "none"))]
+pub use local_key::{AccessError, GenericLocalKey};
+#[cfg(all(feature = "metrics", not(feature = "loom")))]
+pub use metrics::{
+    gather, CounterMetric, GaugeMetric, HistogramMetric, Metric, MetricSample, MetricValue,
+    HISTOGRAM_BUCKETS,
+};
+pub use mutex::{GenericMutex, GenericMutexGuard};
+pub use once::GenericOnce;
+pub use once_lock::GenericOnceLock;
+pub use plugin::Implementation;
+pub use pool::{GenericPool, PoolBox};
+pub use queue::GenericQueue;
+pub use rcu::{GenericRcu, RcuReadGuard};
+pub use ring::{GenericRing, RingConsumer, RingProducer};
+pub use rwlock::{GenericRwLock, GenericRwLockReadGuard, GenericRwLockWriteGuard};
+#[doc(hidden)]
+pub use section::check_section as __check_section;
+pub use section::SectionName;
+pub use seqlock::GenericSeqLock;
+pub use shard::shard_index;
+#[cfg(all(feature = "snapshot", not(any(feature = "loom", miri))))]
+pub use snapshot::Snapshot;
+pub use static_ref::GenericStaticRef;
+#[cfg(all(feature = "touched-statics", not(feature = "loom")))]
+pub use touched::touched_statics;
+pub use type_map::TypeMap;
+#[cfg(feature = "bytemuck")]
+pub use zeroable::ByteZeroed;
 pub use zeroable::Zeroable;
 
-const fn cmp_max(a: usize, b: usize) -> usize {
-    if a > b {
-        a
-    } else {
-        b
-    }
+define_namespace!(
+    /// The default namespace, see [`global`].
+    pub Global
+);
+
+/// Returns the static of `T` in the default [`Global`] namespace.
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// generic_statics::global::<AtomicUsize>().fetch_add(1, Ordering::Relaxed);
+/// ```
+///
+/// This is meant for applications (binaries, examples and tests) where defining a namespace is
+/// ceremony. The default namespace is shared by every crate of the process, libraries should
+/// define their own namespace so their statics don't collide with those of other crates.
+#[inline]
+#[must_use]
+pub fn global<T: 'static + Zeroable + Sync>() -> &'static T {
+    Global::generic_static::<T>()
+}
+
+/// How the storage of a [`Namespace`] is shared between crates.
+///
+/// This is configured with the `#[unsafe(identity(process))]` and `#[identity(crate)]`
+/// attributes of [`define_namespace`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Identity {
+    /// There is one storage per `(namespace, type)` which is shared by all crates.
+    ///
+    /// The storage is keyed by the type name of `(namespace, type)`, the salt of the namespace
+    /// (see [`Namespace::SALT`]) and the size and alignment of the type, which is the same in
+    /// every crate. Type names aren't unique though, so distinct types with equal names and
+    /// layouts share their storage, e.g. the types of semver-incompatible versions of a crate
+    /// (the salt only covers the crate defining the namespace) and types defined in blocks or
+    /// closures. Namespaces with this identity must therefore only be used with types whose names
+    /// are unique in the linked image.
+    Process,
+    /// Every crate instantiating a generic static gets its own storage per `(namespace, type)`
+    /// (the default).
+    ///
+    /// The storage is keyed by the `TypeId` of `(namespace, type)`, so distinct types never share
+    /// their storage. Crates still share their storage if generic instantiations are shared
+    /// between crates (e.g. with `-Zshare-generics`, which is enabled for debug builds).
+    Crate,
 }
 
 /// A namespace for generic statics.
@@ -74,211 +437,4082 @@ const fn cmp_max(a: usize, b: usize) -> usize {
 /// # Safety
 ///
 /// Implementing this trait is not unsafe per-se but you should use the [`define_namespace`]
-/// instead.
+/// instead. Namespaces with [`Identity::Process`] must only be used with types whose names are
+/// unique (see [`Identity::Process`]).
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a generic statics namespace",
+    label = "`{Self}` doesn't implement `Namespace`",
+    note = "define namespaces with `generic_statics::define_namespace!({Self})`"
+)]
 pub unsafe trait Namespace: 'static + Send + Sync + Copy + Clone {
-    /// The returned reference points to the static namespaced global variable for each
-    /// generic `T` (but are lifetime erased). The static's value is zero-initialized.
+    /// How the storage of this namespace is shared between crates.
+    const IDENTITY: Identity = Identity::Crate;
+
+    /// A salt mixed into the storage symbols of this namespace, `0` if unsalted.
     ///
-    /// For caveats and limitations, refer to [top-module](crate#caveats-and-limitations).
-    #[inline(never)]
-    #[must_use]
-    fn generic_static<T: 'static + Zeroable>() -> &'static T {
-        #[allow(unused_assignments)]
-        let mut addr: *const () = ptr::null();
+    /// [`define_namespace`] derives the salt from the name and version of the defining crate, so
+    /// equally named namespaces of different crates (or crate versions) never share storage. The
+    /// salt doesn't cover the types of the statics though (see [`Identity::Process`]).
+    const SALT: u64 = 0;
 
-        // HACK: We have to "use" the generic `T` in some way to force the compiler to emit every
-        // instatiation of this function, otherwise rustc might be smart and merge instantiations.
-        let type_id = TypeId::of::<(Self, T)> as *const ();
+    /// The parent of this namespace, set with `under` in [`define_namespace`].
+    ///
+    /// This is only informational (see [`Namespace::info`]), the storage of a namespace is
+    /// separate from the storage of its parent.
+    const PARENT: Option<fn() -> NamespaceInfo> = None;
 
-        #[cfg(all(
-            target_arch = "aarch64",
-            any(target_os = "macos", target_os = "ios", target_os = "tvos")
-        ))]
-        unsafe {
-            std::arch::asm!(
-                "/* {type_id} */",
-                "adrp {x}, 1f@PAGE",
-                "add {x}, {x}, 1f@PAGEOFF",
-                ".pushsection __DATA,__data",
-                ".p2align {align}, 0",
-                "1: .zero {size}",
-                ".popsection",
-                size = const { cmp_max(mem::size_of::<T>(), 1) },
-                align = const { mem::align_of::<T>().ilog2() },
-                type_id = in(reg) type_id,
-                x = out(reg) addr,
-                options(nostack)
-            );
-        }
+    /// The link section the storage of this namespace is placed in, set with `in` in
+    /// [`define_namespace`], `None` for the default sections.
+    ///
+    /// This is only informational, setting it in a manual implementation doesn't move the
+    /// storage.
+    const SECTION: Option<&'static str> = None;
 
-        #[cfg(all(
-            target_arch = "aarch64",
-            any(target_os = "none", target_os = "linux", target_os = "freebsd")
-        ))]
-        unsafe {
-            std::arch::asm!(
-                "/* {type_id} */",
-                "adrp {x}, 1f",
-                "add {x}, {x}, :lo12:1f",
-                ".pushsection .bss.generic_statics,\"aw\",@nobits",
-                ".p2align {align}, 0",
-                "1: .zero {size}",
-                ".popsection",
-                size = const { cmp_max(mem::size_of::<T>(), 1) },
-                align = const { mem::align_of::<T>().ilog2() },
-                type_id = in(reg) type_id,
-                x = out(reg) addr,
-                options(nostack)
-            );
-        }
+    /// Whether the storage of this namespace can be made read-only with [`Namespace::freeze`]
+    /// (`freeze` feature), set with the `#[freezable]` attribute of [`define_namespace`].
+    ///
+    /// The storage of freezable namespaces isn't reserved at compile time but allocated from
+    /// dedicated memory mappings on first access, so it doesn't share pages with other data.
+    #[cfg(all(
+        feature = "freeze",
+        any(unix, windows),
+        not(any(feature = "loom", miri))
+    ))]
+    const FREEZABLE: bool = false;
 
-        #[cfg(all(
-            target_arch = "x86_64",
-            any(target_os = "macos", target_os = "ios", target_os = "tvos")
-        ))]
-        unsafe {
-            std::arch::asm!(
-                "/* {type_id} */",
-                "lea {x}, [rip + 1f]",
-                ".pushsection __DATA,__data",
-                ".p2align {align}, 0",
-                "1: .zero {size}",
-                ".popsection",
-                size = const { cmp_max(mem::size_of::<T>(), 1) },
-                align = const { mem::align_of::<T>().ilog2() },
-                type_id = in(reg) type_id,
-                x = out(reg) addr,
-                options(nostack)
-            );
-        }
+    /// Whether the storage of this namespace can be provided by a strong definition in another
+    /// object file, set with the `#[weak]` attribute of [`define_namespace`].
+    ///
+    /// ```rust
+    /// use std::sync::atomic::AtomicU64;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(
+    ///     #[unsafe(identity(process))]
+    ///     #[weak]
+    ///     pub Handoff
+    /// );
+    ///
+    /// // Defined by the bootloader, e.g. as
+    /// // `uint64_t boot_count __asm__("__generic_statics.<key>.8.3") = 0;`.
+    /// let symbol = Handoff::storage_symbol::<AtomicU64>().unwrap();
+    /// assert!(symbol.ends_with(".8.3"));
+    /// ```
+    ///
+    /// The storage of these namespaces is emitted as weak definition of the symbol returned by
+    /// [`Namespace::storage_symbol`], which is only used if no other object file linked into the
+    /// same image (e.g. a C object file placing the storage at a fixed address, or a Rust crate
+    /// exporting it with [`export_generic_static`]) defines the symbol. The definition has to be
+    /// at least as large and aligned as the type, and start out zeroed (or initialized, for
+    /// statics with an initial value). Weak namespaces must have [`Identity::Process`], and their
+    /// statics have no canary (`canaries` feature).
+    ///
+    /// The storage of all namespaces is emitted as weak definition on ELF and Mach-O, this only
+    /// makes a difference on Windows, whose storage is otherwise deduplicated as COMDAT, which
+    /// conflicts with other definitions.
+    const WEAK: bool = false;
 
-        #[cfg(all(
-            target_arch = "x86_64",
-            any(target_os = "none", target_os = "linux", target_os = "freebsd")
-        ))]
-        unsafe {
-            std::arch::asm!(
-                "/* {type_id} */",
-                "lea {x}, [rip + 1f]",
-                ".pushsection .bss.generic_statics,\"aw\",@nobits",
-                ".p2align {align}, 0",
-                "1: .zero {size}",
-                ".popsection",
-                size = const { cmp_max(mem::size_of::<T>(), 1) },
-                align = const { mem::align_of::<T>().ilog2() },
-                type_id = in(reg) type_id,
-                x = out(reg) addr,
-                options(nostack)
-            );
+    /// The maximum number of bytes the statics of this namespace may take up in a linked image,
+    /// set with the `#[budget(..)]` attribute of [`define_namespace`], `None` for no limit.
+    ///
+    /// ```rust
+    /// use std::sync::atomic::AtomicU32;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(#[budget(4096)] pub Sensors);
+    ///
+    /// let readings = Sensors::generic_static::<[AtomicU32; 16]>();
+    /// ```
+    ///
+    /// On ELF targets, the storage of every static of these namespaces is accounted in a
+    /// non-allocated section (`generic_statics_budget_<key>`), which takes up as many bytes as the
+    /// statics (at least 4 per static). If the statics that end up in the linked image exceed the
+    /// budget, the link fails with a relocation out of range error referring to that section
+    /// (e.g. "relocation R_X86_64_32 out of range" or "relocation truncated to fit"). The
+    /// section isn't loaded at runtime and can be stripped from the image, e.g. by `objcopy`.
+    ///
+    /// The budget covers the size of the statics, not the alignment padding between them. Statics
+    /// dropped by the linker's garbage collection aren't accounted (GNU ld keeps them with the
+    /// bookkeeping features unless linked with `-z start-stop-gc`). Statics placed into another
+    /// section with [`Namespace::generic_static_in`] aren't accounted either. The bookkeeping of
+    /// the crate's features (e.g. `dylib-dedup` and `check-duplicates`) is kept in private
+    /// namespaces and isn't accounted.
+    /// Budgets aren't enforced on Mach-O and COFF targets and with the `loom` and `miri`
+    /// backends.
+    const BUDGET: Option<usize> = None;
+
+    /// Reserves the storage of `T` in this namespace, which [`define_namespace`] overrides for
+    /// namespaces with a custom section (see [`Namespace::SECTION`]).
+    #[doc(hidden)]
+    #[inline(always)]
+    fn __reserve<T: init::Storage>() -> NonNull<T> {
+        #[cfg(not(any(feature = "loom", miri)))]
+        return asm::reserve::<Self, T>();
+        #[cfg(any(feature = "loom", miri))]
+        unreachable!("the storage is reserved by the loom and miri backends")
+    }
+
+    /// Returns the descriptors of the generic statics of this namespace (`introspection`
+    /// feature), see [`statics`].
+    ///
+    /// ```rust
+    /// use std::sync::atomic::AtomicU64;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let _ = Test::generic_static::<AtomicU64>();
+    /// for descriptor in Test::iter_statics() {
+    ///     println!("{}: {} bytes", descriptor.type_name(), descriptor.size());
+    /// }
+    /// ```
+    ///
+    /// This only includes the statics of this namespace. Those of its children (see
+    /// [`Namespace::PARENT`]) can be found with `NamespaceInfo::ancestors`.
+    #[cfg(all(feature = "introspection", not(any(feature = "loom", miri))))]
+    fn iter_statics() -> impl Iterator<Item = StaticDescriptor> {
+        let namespace = TypeId::of::<Self>();
+        statics()
+            .into_iter()
+            .filter(move |descriptor| descriptor.namespace().type_id() == namespace)
+    }
+
+    /// Writes the type name and value of every generic static of this namespace to `out` (`dump`
+    /// feature), see [`Namespace::iter_statics`].
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// Test::generic_static::<AtomicU32>().store(42, Ordering::Relaxed);
+    ///
+    /// let mut out = Vec::new();
+    /// unsafe { Test::dump(&mut out) }.unwrap();
+    /// assert!(String::from_utf8(out).unwrap().contains(": 42"));
+    /// ```
+    ///
+    /// Statics whose storage doesn't implement `Debug` (or isn't `Sync`) are printed with their
+    /// size. This is meant for diagnosing state leaking between tests, the output format isn't
+    /// stable.
+    ///
+    /// # Safety
+    ///
+    /// See [`StaticDescriptor::value`].
+    #[cfg(all(feature = "dump", not(any(feature = "loom", miri))))]
+    unsafe fn dump(out: &mut impl std::io::Write) -> std::io::Result<()> {
+        for descriptor in Self::iter_statics() {
+            match unsafe { descriptor.value() } {
+                Some(value) => writeln!(out, "{}: {:?}", descriptor.type_name(), value)?,
+                None => writeln!(
+                    out,
+                    "{}: <{} bytes>",
+                    descriptor.type_name(),
+                    descriptor.size()
+                )?,
+            }
         }
+        Ok(())
+    }
 
-        #[cfg(all(target_arch = "x86_64", target_os = "windows"))]
-        unsafe {
-            std::arch::asm!(
-                "/* {type_id} */",
-                "lea {x}, [rip + 1f]",
-                ".pushsection .bss.generic_statics,\"bw\"",
-                ".p2align {align}, 0",
-                "1: .zero {size}",
-                ".popsection",
-                size = const { cmp_max(mem::size_of::<T>(), 1) },
-                align = const { mem::align_of::<T>().ilog2() },
-                type_id = in(reg) type_id,
-                x = out(reg) addr,
-                options(nostack)
-            );
+    /// Copies the storage of all generic statics of this namespace (`snapshot` feature), see
+    /// [`Namespace::iter_statics`].
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let counter = Test::generic_static::<AtomicU32>();
+    /// counter.store(1, Ordering::Relaxed);
+    /// let snapshot = unsafe { Test::snapshot() };
+    ///
+    /// counter.store(2, Ordering::Relaxed);
+    /// unsafe { snapshot.restore() };
+    /// assert_eq!(counter.load(Ordering::Relaxed), 1);
+    /// ```
+    ///
+    /// This allows tests to roll back the global state of a scenario. The storage is copied
+    /// byte-wise, see [`Snapshot::restore`] for restoring it. The statics of freezable namespaces
+    /// aren't included.
+    ///
+    /// # Safety
+    ///
+    /// No other thread may modify the statics of this namespace while the snapshot is taken.
+    #[cfg(all(feature = "snapshot", not(any(feature = "loom", miri))))]
+    unsafe fn snapshot() -> Snapshot {
+        Snapshot::take(Self::iter_statics())
+    }
+
+    /// Resets all generic statics of this namespace to their initial value (`reset` feature), see
+    /// [`Namespace::iter_statics`].
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let counter = Test::generic_static::<AtomicU32>();
+    /// counter.store(1, Ordering::Relaxed);
+    /// unsafe { Test::reset_all() };
+    /// assert_eq!(counter.load(Ordering::Relaxed), 0);
+    /// ```
+    ///
+    /// This isolates tests sharing global state, see also [`Namespace::with_fresh_statics`].
+    /// Zeroed statics are zeroed again, those with an initial value (see
+    /// [`Namespace::generic_static_init`]) are reset to it and uninitialized ones are left
+    /// unchanged. The statics of freezable namespaces aren't reset.
+    ///
+    /// # Safety
+    ///
+    /// No other thread may access the statics of this namespace during the reset, and no
+    /// references into their values (other than to the statics themselves) may be used afterwards.
+    /// Values owning resources (e.g. the value of a
+    /// [`Namespace::generic_lazy`] static) are leaked.
+    #[cfg(all(feature = "reset", not(any(feature = "loom", miri))))]
+    unsafe fn reset_all() {
+        for descriptor in Self::iter_statics() {
+            descriptor.reset();
         }
+    }
 
-        #[cfg(not(any(
-            target_os = "none",
-            target_os = "linux",
-            target_os = "freebsd",
-            target_os = "macos",
-            target_os = "ios",
-            target_os = "tvos",
-            target_os = "windows",
-        )))]
-        std::compile_error!("static-generics is not supported on this platform");
+    /// Runs `f` with freshly reset statics of this namespace (`reset` feature), resetting them
+    /// again afterwards (even if `f` panics), see [`Namespace::reset_all`].
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let counter = Test::generic_static::<AtomicU32>();
+    /// counter.store(1, Ordering::Relaxed);
+    /// unsafe {
+    ///     Test::with_fresh_statics(|| {
+    ///         assert_eq!(counter.fetch_add(1, Ordering::Relaxed), 0);
+    ///     })
+    /// };
+    /// assert_eq!(counter.load(Ordering::Relaxed), 0);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// See [`Namespace::reset_all`].
+    #[cfg(all(feature = "reset", not(any(feature = "loom", miri))))]
+    unsafe fn with_fresh_statics<R>(f: impl FnOnce() -> R) -> R {
+        Self::reset_all();
+        let _reset = reset::ResetOnDrop::<Self>(std::marker::PhantomData);
+        f()
+    }
 
-        assert!(!addr.is_null(), "unsupported platform");
+    /// Returns the address range of the zeroed storage of this namespace in this linked image
+    /// (`storage-range` feature).
+    ///
+    /// ```rust
+    /// use std::sync::atomic::AtomicU64;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let counter = Test::generic_static::<AtomicU64>() as *const _ as *const u8;
+    /// assert!(Test::storage_range().contains(&counter));
+    /// ```
+    ///
+    /// The range can be used to account for the memory of a namespace, to protect it with
+    /// `mprotect` or to include it in crash dumps. It's empty if no static of this namespace is
+    /// instantiated.
+    ///
+    /// Storage with an initial value (see [`Namespace::generic_static_init`]) or without
+    /// initialization (see [`Namespace::generic_static_uninit`]) isn't part of the range, neither
+    /// is the storage of freezable namespaces. The storage of namespaces with equal type names and
+    /// salts may share a range.
+    #[cfg(all(feature = "storage-range", not(any(feature = "loom", miri))))]
+    #[inline]
+    fn storage_range() -> std::ops::Range<*const u8> {
+        asm::storage_range::<Self>()
+    }
 
-        unsafe { &*addr.cast::<T>() }
+    /// Sets the hook invoked on the first access to every static of this namespace
+    /// (`touched-statics` feature).
+    ///
+    /// ```rust
+    /// use std::sync::atomic::AtomicU64;
+    /// use generic_statics::{define_namespace, Namespace, StaticDescriptor};
+    ///
+    /// define_namespace!(Metrics);
+    ///
+    /// fn register(descriptor: &'static StaticDescriptor) {
+    ///     println!("registering {}", descriptor.type_name());
+    /// }
+    ///
+    /// Metrics::set_first_access_hook(register);
+    /// let _ = Metrics::generic_static::<AtomicU64>();
+    /// ```
+    ///
+    /// The hook is invoked exactly once per static, by the thread accessing it first, after it has
+    /// been added to [`touched_statics`]. Statics accessed before the hook was set don't invoke
+    /// it. Setting a new hook replaces the previous one.
+    #[cfg(all(feature = "touched-statics", not(feature = "loom")))]
+    #[inline]
+    fn set_first_access_hook(hook: fn(&'static StaticDescriptor)) {
+        touched::set_hook::<Self>(hook);
     }
-}
 
-#[macro_export]
-macro_rules! define_namespace {
-    ($vis:vis $name:ident) => {
-        #[derive(Debug, Copy, Clone)]
-        $vis struct $name;
+    /// Returns the description of this namespace, including its parent.
+    #[inline]
+    #[must_use]
+    fn info() -> NamespaceInfo {
+        NamespaceInfo::new(any::type_name::<Self>(), TypeId::of::<Self>(), Self::PARENT)
+    }
 
-        unsafe impl $crate::Namespace for $name {}
-    };
-}
+    /// Returns whether this namespace is `P` or a (transitive) child of `P`.
+    #[inline]
+    #[must_use]
+    fn is_within<P: Namespace>() -> bool {
+        let parent = TypeId::of::<P>();
+        TypeId::of::<Self>() == parent || Self::info().ancestors().any(|a| a.type_id() == parent)
+    }
 
-#[cfg(test)]
-mod tests {
-    use std::{
-        assert_ne,
-        marker::PhantomData,
-        sync::atomic::{AtomicIsize, AtomicPtr, AtomicUsize, Ordering},
-    };
+    /// Returns the name of the symbol labeling the storage of `T` in this namespace, `None` for
+    /// [`Identity::Crate`] namespaces (whose symbols contain mangled names).
+    ///
+    /// This is the name in the object file (i.e. without the `_` prefix of C symbols on Mach-O),
+    /// which is only stable for the same compiler, crate versions and target, see
+    /// [`Namespace::WEAK`].
+    #[cfg(not(any(feature = "loom", miri)))]
+    #[must_use]
+    fn storage_symbol<T: 'static>() -> Option<String> {
+        match Self::IDENTITY {
+            Identity::Process => Some(format!(
+                "__generic_statics.{}.{}.{}",
+                asm::storage_key::<Self, T>(),
+                asm::storage_size::<T>(),
+                mem::align_of::<T>().ilog2()
+            )),
+            Identity::Crate => None,
+        }
+    }
 
-    use super::Namespace;
+    /// The returned reference points to the static namespaced global variable for each
+    /// generic `T` (but are lifetime erased). The static's value is zero-initialized.
+    ///
+    /// Since the reference can be shared across threads, `T` must be `Sync`. Use
+    /// [`Namespace::generic_static_not_sync`] for single-threaded use of `!Sync` types.
+    ///
+    /// Zero-sized types resolve to a well-aligned dangling reference without reserving any
+    /// storage.
+    ///
+    /// For caveats and limitations, refer to [top-module](crate#caveats-and-limitations).
+    #[inline]
+    #[must_use]
+    fn generic_static<T: 'static + Zeroable + Sync>() -> &'static T {
+        unsafe { Self::generic_static_not_sync::<T>() }
+    }
 
-    define_namespace!(pub Test);
+    /// Like [`Namespace::generic_static`] but returns a [`GenericStaticRef`] handle, which
+    /// compares, hashes and prints by the identity of the static.
+    #[inline]
+    #[must_use]
+    fn generic_static_ref<T: 'static + Zeroable + Sync>() -> GenericStaticRef<Self, T> {
+        GenericStaticRef::new()
+    }
 
-    #[test]
-    fn stable_addr() {
-        let a = Test::generic_static::<*const ()>() as *const _;
-        let b = Test::generic_static::<*const ()>() as *const _;
-        assert_eq!(a, b);
+    /// Like [`Namespace::generic_static`] but pinned, for address-sensitive types (e.g. intrusive
+    /// list heads or wait queues) used with pin-based APIs.
+    ///
+    /// ```rust
+    /// use std::{marker::PhantomPinned, pin::Pin};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let head: Pin<&'static PhantomPinned> = Test::generic_static_pin::<PhantomPinned>();
+    /// ```
+    ///
+    /// The storage of a static never moves, so this is the same static as the one returned by
+    /// [`Namespace::generic_static`].
+    #[inline]
+    #[must_use]
+    fn generic_static_pin<T: 'static + Zeroable + Sync>() -> Pin<&'static T> {
+        Pin::static_ref(Self::generic_static::<T>())
+    }
 
-        let d = Test::generic_static::<(AtomicUsize, AtomicUsize, AtomicUsize)>() as *const _;
-        let e = Test::generic_static::<(AtomicUsize, AtomicUsize, AtomicUsize)>() as *const _;
-        assert_eq!(d, e);
+    /// Like [`Namespace::generic_static`] but with a separate static for every marker type `Tag`,
+    /// which is only used for identity.
+    ///
+    /// ```rust
+    /// use std::sync::atomic::AtomicUsize;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// struct Reads;
+    /// struct Writes;
+    ///
+    /// let reads = Test::generic_static_keyed::<AtomicUsize, Reads>();
+    /// let writes = Test::generic_static_keyed::<AtomicUsize, Writes>();
+    /// assert_ne!(reads as *const _, writes as *const _);
+    /// ```
+    ///
+    /// `Tag` doesn't have to be [`Zeroable`] or `Sync`.
+    #[inline]
+    #[must_use]
+    fn generic_static_keyed<T: 'static + Zeroable + Sync, Tag: 'static>() -> &'static T {
+        &Self::generic_static::<keyed::Keyed<T, Tag>>().0
+    }
 
-        assert_ne!(a as *const (), d as *const _ as *const ());
+    /// Like [`Namespace::generic_static_keyed`] but keyed by the integer `ID`, e.g. for numbered
+    /// slots stamped out by macros.
+    ///
+    /// ```rust
+    /// use std::sync::atomic::AtomicU64;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let slot0 = Test::generic_static_id::<AtomicU64, 0>();
+    /// let slot1 = Test::generic_static_id::<AtomicU64, 1>();
+    /// assert_ne!(slot0 as *const _, slot1 as *const _);
+    /// ```
+    #[inline]
+    #[must_use]
+    fn generic_static_id<T: 'static + Zeroable + Sync, const ID: u64>() -> &'static T {
+        Self::generic_static_keyed::<T, keyed::Id<ID>>()
     }
 
-    #[test]
-    fn unique_address() {
-        let a = Test::generic_static::<AtomicUsize>() as *const _ as *const ();
-        let b = Test::generic_static::<AtomicIsize>() as *const _ as *const ();
-        let c = Test::generic_static::<usize>() as *const _ as *const ();
-        let d = Test::generic_static::<AtomicPtr<()>>() as *const _ as *const ();
+    /// Returns the `index`-th of `N` independent statics of `T`, e.g. one per plugin slot.
+    ///
+    /// ```rust
+    /// use std::sync::atomic::AtomicU64;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let slot = |i| Test::generic_static_indexed::<AtomicU64, 4>(i) as *const _;
+    /// assert_ne!(slot(0), slot(1));
+    /// assert_eq!(slot(2), slot(2));
+    /// ```
+    ///
+    /// The replicas are reserved together, separately from [`Namespace::generic_static`] and
+    /// for every `N`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds (not less than `N`).
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    fn generic_static_indexed<T: 'static + Zeroable + Sync, const N: usize>(
+        index: usize,
+    ) -> &'static T {
+        &Self::generic_static::<keyed::Replicas<T, N>>().0[index]
+    }
 
-        assert_ne!(a, b);
-        assert_ne!(a, c);
-        assert_ne!(a, d);
-        assert_ne!(b, c);
-        assert_ne!(b, d);
-        assert_ne!(c, d);
+    /// Like [`Namespace::generic_static`] but without requiring `T: Sync`.
+    ///
+    /// # Safety
+    ///
+    /// The returned reference points to storage that is shared by every thread resolving `T` in
+    /// this namespace. The caller must ensure that it is only ever accessed from a single thread
+    /// (or otherwise synchronized), just like a `static` of a `!Sync` type would require.
+    #[inline]
+    #[must_use]
+    unsafe fn generic_static_not_sync<T: 'static + Zeroable>() -> &'static T {
+        if const { mem::size_of::<T>() == 0 } {
+            return unsafe { NonNull::dangling().as_ref() };
+        }
+
+        unsafe { resolve::<Self, T>().as_ref() }
     }
 
-    #[test]
-    fn unique_address_dyn() {
-        trait Foo<A: 'static> {}
+    /// Like [`Namespace::generic_static`] but initialized with [`ConstInit::INIT`] instead of
+    /// zeroes.
+    ///
+    /// The initial value is emitted at compile time, so there is no runtime initialization. Note
+    /// that this is a different static than the one returned by [`Namespace::generic_static`]
+    /// for the same `T`.
+    #[inline]
+    #[must_use]
+    fn generic_static_init<T: 'static + ConstInit + Sync>() -> &'static T {
+        if const { mem::size_of::<T>() == 0 } {
+            return unsafe { NonNull::dangling().as_ref() };
+        }
+
+        unsafe { resolve::<Self, Init<T>>().cast::<T>().as_ref() }
+    }
 
-        let a = Test::generic_static::<PhantomData<dyn Foo<usize>>>() as *const _ as *const ();
-        let b = Test::generic_static::<PhantomData<dyn Foo<isize>>>() as *const _ as *const ();
-        let c = Test::generic_static::<PhantomData<dyn Foo<()>>>() as *const _ as *const ();
+    /// Like [`Namespace::generic_static`] but the storage isn't initialized at startup.
+    ///
+    /// This is meant for data that has to survive soft resets on embedded targets (e.g. panic
+    /// logs or boot counters). On ELF targets, the storage is emitted into a `.noinit.*` section,
+    /// which the linker script has to place into a region that isn't cleared by the startup code,
+    /// for example:
+    ///
+    /// ```text
+    /// .noinit (NOLOAD) : {
+    ///     KEEP(*(.noinit .noinit.*))
+    /// } > RAM
+    /// ```
+    ///
+    /// Without such a rule (and on other object formats), the storage is zeroed like any other
+    /// `.bss` data. This is a different static than the one returned by
+    /// [`Namespace::generic_static`] for the same `T`.
+    #[inline]
+    #[must_use]
+    fn generic_static_uninit<T: 'static + Sync>() -> &'static MaybeUninit<T> {
+        if const { mem::size_of::<T>() == 0 } {
+            return unsafe { NonNull::dangling().as_ref() };
+        }
 
-        assert_ne!(a, b);
-        assert_ne!(a, c);
-        assert_ne!(b, c);
+        unsafe {
+            resolve::<Self, Noinit<T>>()
+                .cast::<MaybeUninit<T>>()
+                .as_ref()
+        }
     }
 
-    #[test]
-    fn mutation() {
-        let a = Test::generic_static::<AtomicUsize>();
-        assert_eq!(a.load(Ordering::Relaxed), 0);
-        a.store(42, Ordering::Relaxed);
+    /// Like [`Namespace::generic_static`] but the storage is placed into the link section named
+    /// by `S` (see [`define_section`]).
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # {
+    /// use std::sync::atomic::AtomicU32;
+    /// use generic_statics::{define_namespace, define_section, Namespace};
+    ///
+    /// define_namespace!(Test);
+    /// define_section!(BackupRam in ".backup_ram");
+    ///
+    /// let boots = Test::generic_static_in::<AtomicU32, BackupRam>();
+    /// # }
+    /// ```
+    ///
+    /// This places individual statics without a namespace of their own (see the `in` syntax of
+    /// [`define_namespace`]). The static is different from the one returned by
+    /// [`Namespace::generic_static`] for the same `T`, and from those in other sections: It
+    /// belongs to a child namespace of this namespace (see [`Namespace::PARENT`]) per section.
+    #[inline]
+    #[must_use]
+    fn generic_static_in<T: 'static + Zeroable + Sync, S: SectionName>() -> &'static T {
+        section::InSection::<Self, S>::generic_static::<T>()
+    }
 
-        let b = Test::generic_static::<AtomicUsize>();
-        assert_eq!(b.load(Ordering::Relaxed), 42);
+    /// Like [`Namespace::generic_static`] but with a copy per CPU, for kernels on `x86_64` and
+    /// `aarch64` (see [`percpu`]).
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    /// use generic_statics::{define_namespace, percpu, Namespace};
+    ///
+    /// define_namespace!(Sched);
+    ///
+    /// struct RunQueueLen(AtomicU64);
+    /// unsafe impl generic_statics::Zeroable for RunQueueLen {}
+    ///
+    /// // On every CPU, with `area` allocated for `percpu::area_size()` bytes.
+    /// # let area = std::ptr::null_mut();
+    /// unsafe {
+    ///     percpu::init_area(area);
+    ///     percpu::set_base(area);
+    /// }
+    ///
+    /// unsafe { Sched::generic_static_percpu::<RunQueueLen>() }
+    ///     .0
+    ///     .fetch_add(1, Ordering::Relaxed);
+    /// ```
+    ///
+    /// The storage of `T` in the template of the per-CPU areas is the static returned by
+    /// [`Namespace::generic_static_in`] for a section private to this crate. The returned
+    /// reference stays valid if the thread is migrated to another CPU (it then refers to the
+    /// static of the previous CPU), so `T` must be `Sync`.
+    ///
+    /// # Safety
+    ///
+    /// The per-CPU area of the current CPU must have been set with [`percpu::set_base`].
+    #[cfg(all(
+        target_os = "none",
+        any(target_arch = "x86_64", target_arch = "aarch64"),
+        not(any(feature = "loom", miri))
+    ))]
+    #[inline]
+    #[must_use]
+    unsafe fn generic_static_percpu<T: 'static + Zeroable + Sync>() -> &'static T {
+        const {
+            assert!(
+                mem::align_of::<T>() <= percpu::AREA_ALIGN,
+                "per-CPU statics can't be aligned to more than `percpu::AREA_ALIGN`"
+            )
+        };
+        if const { mem::size_of::<T>() == 0 } {
+            return NonNull::dangling().as_ref();
+        }
 
-        let a2 = Test::generic_static::<AtomicIsize>();
-        assert_eq!(a2.load(Ordering::Relaxed), 0);
+        let template = section::InSection::<Self, percpu::Template>::generic_static::<T>();
+        &*percpu::this_cpu(template)
+    }
+
+    /// Like [`Namespace::generic_static`] but with a copy per thread, which is reserved in the
+    /// TLS segment (like a `#[thread_local]` static) and zero-initialized in every thread.
+    ///
+    /// ```rust
+    /// use std::cell::Cell;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// struct Depth(Cell<u32>);
+    /// unsafe impl generic_statics::Zeroable for Depth {}
+    ///
+    /// let depth = Test::generic_static_thread_local::<Depth>();
+    /// let depth = unsafe { depth.as_ref() };
+    /// depth.0.set(depth.0.get() + 1);
+    /// ```
+    ///
+    /// This returns a pointer, as the storage is only valid until the current thread exits.
+    /// Values aren't dropped when the thread exits.
+    #[cfg(not(target_os = "none"))]
+    #[inline]
+    #[must_use]
+    fn generic_static_thread_local<T: 'static + Zeroable>() -> NonNull<T> {
+        if const { mem::size_of::<T>() == 0 } {
+            return NonNull::dangling();
+        }
+
+        tls::reserve::<Self, T>()
+    }
+
+    /// Returns a key to the thread-local value of `T`, with the closure API of
+    /// [`std::thread::LocalKey`] (see [`GenericLocalKey`]).
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let scratch = Test::generic_local_key::<RefCell<Vec<u8>>>(|| RefCell::new(Vec::new()));
+    /// scratch.with_borrow_mut(|scratch| scratch.extend_from_slice(b"abc"));
+    /// assert_eq!(scratch.with_borrow(Vec::len), 3);
+    /// ```
+    ///
+    /// The value is created by calling `init` on the first access in every thread and dropped
+    /// when the thread exits. Only the `init` of the first access in a thread is called, later
+    /// accesses use the existing value even if their key was created with a different `init`. The
+    /// value is stored in a generic thread-local static (see
+    /// [`Namespace::generic_static_thread_local`]).
+    #[cfg(not(target_os = "none"))]
+    #[inline]
+    #[must_use]
+    fn generic_local_key<T: 'static>(init: fn() -> T) -> GenericLocalKey<T> {
+        GenericLocalKey::new::<Self>(init)
+    }
+
+    /// Like [`Namespace::generic_static`] but aligned and padded to the size of a cache line (see
+    /// [`CacheAligned`]), so hot statics (e.g. the counters of different types) don't suffer from
+    /// false sharing.
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// Test::generic_static_padded::<AtomicU64>().fetch_add(1, Ordering::Relaxed);
+    /// ```
+    ///
+    /// This is the static returned by `generic_static::<CacheAligned<T>>`, which is different
+    /// from the one returned by [`Namespace::generic_static`] for `T`.
+    #[inline]
+    #[must_use]
+    fn generic_static_padded<T: 'static + Zeroable + Sync>() -> &'static T {
+        &Self::generic_static::<CacheAligned<T>>().0
+    }
+
+    /// Returns `SHARDS` cache-line aligned copies of the static for `T`, so hot statics (e.g. the
+    /// counters of a type) can be striped across shards to avoid contention and summed on read.
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    /// use generic_statics::{define_namespace, shard_index, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let hits = Test::generic_static_sharded::<AtomicU64, 8>();
+    /// hits[shard_index(8)].fetch_add(1, Ordering::Relaxed);
+    /// let total: u64 = hits.iter().map(|hits| hits.load(Ordering::Relaxed)).sum();
+    /// assert_eq!(total, 1);
+    /// ```
+    ///
+    /// This is the static returned by `generic_static::<[CacheAligned<T>; SHARDS]>`, see
+    /// [`shard_index`] for picking the shard of the current thread (or
+    /// [`Namespace::generic_static_shard`]).
+    #[inline]
+    #[must_use]
+    fn generic_static_sharded<T: 'static + Zeroable + Sync, const SHARDS: usize>(
+    ) -> &'static [CacheAligned<T>; SHARDS] {
+        Self::generic_static::<[CacheAligned<T>; SHARDS]>()
+    }
+
+    /// Returns the shard of the static returned by [`Namespace::generic_static_sharded`] picked
+    /// for the current thread by [`shard_index`].
+    #[inline]
+    #[must_use]
+    fn generic_static_shard<T: 'static + Zeroable + Sync, const SHARDS: usize>() -> &'static T {
+        &Self::generic_static_sharded::<T, SHARDS>()[shard_index(SHARDS)].0
+    }
+
+    /// Like [`Namespace::generic_static`] but aligned to at least `ALIGN` bytes, which must be a
+    /// power of two (see [`Alignment`]).
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let scratch = Test::generic_static_aligned::<[f32; 16], 64>();
+    /// assert_eq!(scratch.as_ptr() as usize % 64, 0);
+    /// ```
+    ///
+    /// Other alignments fail to compile:
+    ///
+    /// ```rust,compile_fail
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let _ = Test::generic_static_aligned::<[f32; 16], 48>();
+    /// ```
+    ///
+    /// The static is different from the one returned by [`Namespace::generic_static`] for `T`
+    /// and from those with other alignments. Its storage is rounded up to a multiple of the
+    /// alignment.
+    #[inline]
+    #[must_use]
+    fn generic_static_aligned<T: 'static + Zeroable + Sync, const ALIGN: usize>() -> &'static T
+    where
+        Align<ALIGN>: Alignment,
+    {
+        &Self::generic_static::<Aligned<T, ALIGN>>().value
+    }
+
+    /// Like [`Namespace::generic_static`] but aligned to the page size (see [`MAX_PAGE_SIZE`]),
+    /// e.g. for DMA buffers or memory that is protected with `mprotect`.
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, Namespace, MAX_PAGE_SIZE};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let descriptors = Test::generic_static_page_aligned::<[u64; 32]>();
+    /// assert_eq!(descriptors.as_ptr() as usize % MAX_PAGE_SIZE, 0);
+    /// ```
+    ///
+    /// This is the static returned by `generic_static_aligned::<T, MAX_PAGE_SIZE>`. Its storage
+    /// is rounded up to a multiple of the page size, so it occupies its pages exclusively.
+    #[inline]
+    #[must_use]
+    fn generic_static_page_aligned<T: 'static + Zeroable + Sync>() -> &'static T {
+        Self::generic_static_aligned::<T, MAX_PAGE_SIZE>()
+    }
+
+    /// Returns the lazily initialized static for `T`.
+    ///
+    /// The value is created by calling `init` on the first access, concurrent first accesses
+    /// wait for the initialization to complete. This is like a `LazyLock` per type:
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let names = Test::generic_lazy::<Vec<&str>>(|| vec!["a", "b"]);
+    /// assert_eq!(names.len(), 2);
+    /// ```
+    ///
+    /// Only the `init` of the first access is called, later calls return the existing value even
+    /// if they pass a different `init`. If `init` panics, the next access retries the
+    /// initialization. Accessing the same static from within `init` deadlocks.
+    #[inline]
+    #[must_use]
+    fn generic_lazy<T: 'static + Send + Sync>(init: fn() -> T) -> &'static T {
+        Self::generic_static::<Lazy<T>>().get_or_init(init)
+    }
+
+    /// Like [`Namespace::generic_lazy`] but the value is dropped when the process exits.
+    ///
+    /// On first initialization, a drop function for the static is registered with `atexit`, so
+    /// values holding resources (e.g. temporary files or connections) are cleaned up. This is a
+    /// different static than the one returned by [`Namespace::generic_lazy`] for the same `T`.
+    ///
+    /// # Safety
+    ///
+    /// The returned reference dangles once the value has been dropped. The caller must ensure
+    /// that the static isn't accessed after the exit handlers started to run, e.g. by other
+    /// (detached) threads or by later-registered exit handlers. Accessing the static again from
+    /// an exit handler reinitializes it.
+    #[cfg(feature = "teardown")]
+    #[inline]
+    #[must_use]
+    unsafe fn generic_lazy_teardown<T: 'static + Send + Sync>(init: fn() -> T) -> &'static T {
+        teardown::get_or_init::<Self, T>(init)
+    }
+
+    /// Registers `hook` to run in the child process after a `fork`.
+    ///
+    /// This is meant for resetting statics that must not be inherited by the child, e.g. cached
+    /// pids, file descriptors or locks that were held by other threads of the parent:
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// Test::on_fork_child(|| {
+    ///     Test::generic_static::<AtomicU32>().store(0, Ordering::Relaxed);
+    /// });
+    /// ```
+    ///
+    /// The hooks of a namespace are installed with `pthread_atfork` on first registration and
+    /// run in order of registration. Like any code running after a `fork` of a multi-threaded
+    /// process, hooks should restrict themselves to async-signal-safe operations. Hooks can't be
+    /// unregistered.
+    #[cfg(unix)]
+    #[inline]
+    fn on_fork_child(hook: fn()) {
+        fork::register::<Self>(fork::Stage::Child, hook);
+    }
+
+    /// Registers `hook` to run in the parent process before a `fork`, see
+    /// [`Namespace::on_fork_child`].
+    ///
+    /// Prepare hooks run in reverse order of registration, e.g. to acquire locks that are then
+    /// released by the hooks registered with [`Namespace::on_fork_parent`] and
+    /// [`Namespace::on_fork_child`].
+    #[cfg(unix)]
+    #[inline]
+    fn on_fork_prepare(hook: fn()) {
+        fork::register::<Self>(fork::Stage::Prepare, hook);
+    }
+
+    /// Registers `hook` to run in the parent process after a `fork`, see
+    /// [`Namespace::on_fork_child`].
+    #[cfg(unix)]
+    #[inline]
+    fn on_fork_parent(hook: fn()) {
+        fork::register::<Self>(fork::Stage::Parent, hook);
+    }
+
+    /// Makes the storage of this [freezable](Namespace::FREEZABLE) namespace read-only
+    /// (`freeze` feature).
+    ///
+    /// This is meant for hardening global configuration against late writes once the startup is
+    /// complete:
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(#[freezable] Config);
+    ///
+    /// Config::generic_static::<AtomicU32>().store(8, Ordering::Relaxed);
+    /// Config::freeze();
+    /// assert_eq!(Config::generic_static::<AtomicU32>().load(Ordering::Relaxed), 8);
+    /// ```
+    ///
+    /// Any write to the storage afterwards (including interior mutability, e.g. of atomics,
+    /// locks or lazily initialized statics) crashes the process with an access violation.
+    /// Statics first accessed after freezing are read-only from the start. Freezing a namespace
+    /// twice has no effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the namespace isn't freezable.
+    #[cfg(all(
+        feature = "freeze",
+        any(unix, windows),
+        not(any(feature = "loom", miri))
+    ))]
+    fn freeze() {
+        assert!(Self::FREEZABLE, "namespace is not freezable");
+        freeze::freeze::<Self>();
+    }
+
+    /// Returns the [`ArcSwapOption`](arc_swap::ArcSwapOption) for `T` (`arc-swap` feature),
+    /// which is `None` until a value is stored.
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// Test::arc_swap::<String>().store(Some(Arc::new("config".to_owned())));
+    /// assert_eq!(Test::arc_swap::<String>().load().as_deref().unwrap(), "config");
+    /// ```
+    ///
+    /// This is a plain generic static, keyed by `T` (see [`Zeroable`] for the supported types of
+    /// the `arc-swap` crate).
+    #[cfg(feature = "arc-swap")]
+    #[inline]
+    #[must_use]
+    fn arc_swap<T: 'static + Send + Sync>() -> &'static arc_swap::ArcSwapOption<T> {
+        Self::generic_static::<arc_swap::ArcSwapOption<T>>()
+    }
+
+    /// Like [`Namespace::generic_lazy`] but initialized by awaiting the future returned by `init`
+    /// (`async-lazy` feature).
+    ///
+    /// This has the semantics of `tokio::sync::OnceCell::get_or_init` but doesn't depend on a
+    /// specific executor:
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// struct Pool {
+    ///     size: usize,
+    /// }
+    ///
+    /// async fn connect() -> Pool {
+    ///     Pool { size: 4 }
+    /// }
+    ///
+    /// async fn pool() -> &'static Pool {
+    ///     Test::generic_async_lazy::<Pool, _, _>(connect).await
+    /// }
+    /// ```
+    ///
+    /// Concurrent callers wait for the initialization to complete. If the initializing future
+    /// panics or is dropped before it completes, a waiting (or the next) caller retries the
+    /// initialization. This is a different static than the one returned by
+    /// [`Namespace::generic_lazy`] for the same `T`.
+    #[cfg(feature = "async-lazy")]
+    #[inline]
+    fn generic_async_lazy<T, F, Fut>(
+        init: F,
+    ) -> impl std::future::Future<Output = &'static T> + Send
+    where
+        T: 'static + Send + Sync,
+        F: FnOnce() -> Fut + Send,
+        Fut: std::future::Future<Output = T> + Send,
+    {
+        Self::generic_static::<async_lazy::AsyncLazy<T>>().get_or_init(init)
+    }
+
+    /// Securely zeroes the static for `T` returned by [`Namespace::generic_static`] (`zeroize`
+    /// feature).
+    ///
+    /// This is meant for statics holding key material, the writes are guaranteed to not be
+    /// optimized away:
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let key = Test::generic_static::<[AtomicU64; 4]>();
+    /// key.iter().for_each(|word| word.store(0xabab_abab, Ordering::Relaxed));
+    ///
+    /// unsafe { Test::wipe::<[AtomicU64; 4]>() };
+    /// assert!(key.iter().all(|word| word.load(Ordering::Relaxed) == 0));
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The static must not be accessed concurrently and no references to it may be alive, unless
+    /// `T` permits writes through shared references (e.g. atomics).
+    #[cfg(feature = "zeroize")]
+    #[inline]
+    unsafe fn wipe<T: 'static + Zeroable>() {
+        unsafe { wipe::wipe::<Self, T>() }
+    }
+
+    /// Registers the static for `T` to be wiped with [`Namespace::wipe`] when the process exits
+    /// (`zeroize` feature).
+    ///
+    /// The static is registered with `atexit` on the first call, later calls have no effect.
+    ///
+    /// # Safety
+    ///
+    /// The requirements of [`Namespace::wipe`] must hold when the exit handlers run, i.e. the
+    /// caller must ensure that the static isn't accessed concurrently (e.g. by other, detached
+    /// threads) at that point.
+    #[cfg(feature = "zeroize")]
+    #[inline]
+    unsafe fn zeroize_on_exit<T: 'static + Zeroable>() {
+        wipe::zeroize_on_exit::<Self, T>();
+    }
+
+    /// Locks the pages backing the static for `T` returned by [`Namespace::generic_static`] into
+    /// memory (with `mlock` or `VirtualLock`), so it isn't swapped to disk.
+    ///
+    /// This is meant for statics holding secrets, e.g. together with [`Namespace::wipe`]:
+    ///
+    /// ```rust,no_run
+    /// use std::sync::atomic::AtomicU64;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// if let Err(err) = Test::mlock::<[AtomicU64; 4]>() {
+    ///     eprintln!("failed to lock the key into memory: {err}");
+    /// }
+    /// ```
+    ///
+    /// Locking is done at page granularity, so other data sharing the pages is locked as well.
+    /// The pages stay locked until the process exits.
+    ///
+    /// # Errors
+    ///
+    /// Returns the OS error if the pages can't be locked, e.g. because the process exceeded its
+    /// limit of locked memory.
+    #[cfg(any(unix, windows))]
+    #[inline]
+    fn mlock<T: 'static + Zeroable + Sync>() -> std::io::Result<()> {
+        let value = Self::generic_static::<T>();
+        mlock::lock(NonNull::from(value).cast(), mem::size_of::<T>())
+    }
+
+    /// Advises the kernel to back the static for `T` returned by [`Namespace::generic_static`]
+    /// with huge pages (`madvise(MADV_HUGEPAGE)` on Linux), e.g. for large lookup tables whose
+    /// accesses are bound by TLB misses.
+    ///
+    /// ```rust,no_run
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// if let Err(err) = Test::advise_huge_pages::<[u64; 1 << 20]>() {
+    ///     eprintln!("failed to advise huge pages: {err}");
+    /// }
+    /// ```
+    ///
+    /// Only the parts of the static covering whole (e.g. 2 MiB) huge pages can be backed by huge
+    /// pages, and only if transparent huge pages are enabled for `madvise`. This is a no-op on
+    /// other targets, which either use huge pages transparently or only for dedicated
+    /// allocations.
+    ///
+    /// # Errors
+    ///
+    /// Returns the OS error if the advice fails, e.g. because the kernel doesn't support
+    /// transparent huge pages.
+    #[cfg(any(unix, windows))]
+    #[inline]
+    fn advise_huge_pages<T: 'static + Zeroable + Sync>() -> std::io::Result<()> {
+        let value = Self::generic_static::<T>();
+        huge_pages::advise(NonNull::from(value).cast(), mem::size_of::<T>())
+    }
+
+    /// Returns a mutable reference to the zero-initialized static for `T` on the first call,
+    /// `None` afterwards.
+    ///
+    /// This is meant for buffers or state that need a unique `&'static mut` (e.g. DMA buffers or
+    /// executors on embedded targets):
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let buf = Test::claim::<[u8; 64]>().unwrap();
+    /// buf[0] = 1;
+    /// assert!(Test::claim::<[u8; 64]>().is_none());
+    /// ```
+    ///
+    /// This is a different static than the one returned by [`Namespace::generic_static`] for
+    /// the same `T`.
+    #[inline]
+    #[must_use]
+    fn claim<T: 'static + Zeroable + Send>() -> Option<&'static mut T> {
+        Self::generic_static::<claim::Claim<T>>().claim()
+    }
+
+    /// Returns the [`GenericAtomic`] for `T`, a zero-initialized atomic cell for small `Copy`
+    /// types.
+    #[inline]
+    #[must_use]
+    fn atomic<T: 'static + NoUninit + Send>() -> &'static GenericAtomic<T> {
+        Self::generic_static::<GenericAtomic<T>>()
+    }
+
+    /// Returns the counter for the marker type `Tag`, starting at zero.
+    ///
+    /// ```rust
+    /// use std::sync::atomic::Ordering;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Ids);
+    ///
+    /// struct Request;
+    ///
+    /// let id = Ids::counter::<Request>().fetch_add(1, Ordering::Relaxed);
+    /// assert_eq!(id, 0);
+    /// ```
+    ///
+    /// This is a plain generic static, `Tag` doesn't have to be [`Zeroable`] though.
+    #[inline]
+    #[must_use]
+    fn counter<Tag: 'static>() -> &'static std::sync::atomic::AtomicU64 {
+        &Self::generic_static::<counter::Counter<Tag>>().0
+    }
+
+    /// Returns the generation counter for the marker type `Tag`, starting at zero.
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Caches);
+    ///
+    /// struct Config;
+    ///
+    /// let seen = Caches::generation::<Config>().current();
+    /// // After reloading the config:
+    /// Caches::generation::<Config>().bump();
+    /// assert_ne!(Caches::generation::<Config>().current(), seen);
+    /// ```
+    ///
+    /// See [`Stamped`] for recomputing derived values when the generation changes. This is a plain
+    /// generic static, `Tag` doesn't have to be [`Zeroable`] though.
+    #[inline]
+    #[must_use]
+    fn generation<Tag: 'static>() -> &'static Generation {
+        &Self::generic_static::<generation::GenerationFor<Tag>>().0
+    }
+
+    /// Returns the dense index of `T` in this namespace, assigning the next free index on first
+    /// use.
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Components);
+    ///
+    /// struct Position;
+    /// struct Velocity;
+    ///
+    /// let position = Components::type_index::<Position>();
+    /// let velocity = Components::type_index::<Velocity>();
+    /// assert_ne!(position, velocity);
+    /// assert_eq!(Components::type_index::<Position>(), position);
+    /// assert_eq!(Components::type_count(), 2);
+    /// ```
+    ///
+    /// The indices are `0..Self::type_count()` in the order the types are first indexed, so they
+    /// can index a vector instead of hashing the `TypeId` (like [`TypeMap`] does). The order
+    /// depends on the execution, so the indices must not be persisted. After the first use of a
+    /// type, this is a load of its generic static.
+    #[inline]
+    #[must_use]
+    fn type_index<T: 'static>() -> usize {
+        type_index::index::<Self, T>()
+    }
+
+    /// Returns the number of types indexed by [`type_index`](Namespace::type_index) so far.
+    #[inline]
+    #[must_use]
+    fn type_count() -> usize {
+        type_index::count::<Self>()
+    }
+
+    /// Returns the [`ComponentMeta`] of `T` in this namespace (its layout, drop function, etc.).
+    ///
+    /// This doesn't register `T`, see [`Namespace::register_component`].
+    #[inline]
+    #[must_use]
+    fn component<T: 'static>() -> &'static ComponentMeta {
+        component::meta::<Self, T>()
+    }
+
+    /// Registers `T` as a component of this namespace, returning whether it wasn't registered
+    /// before.
+    ///
+    /// Registered components are iterated by [`Namespace::components`]. Registration links the
+    /// [`ComponentMeta`] of `T` into an intrusive list, so it doesn't allocate.
+    #[inline]
+    fn register_component<T: 'static>() -> bool {
+        component::register::<Self, T>()
+    }
+
+    /// Iterates over the components registered with this namespace, most recently registered
+    /// first.
+    #[inline]
+    fn components() -> impl Iterator<Item = &'static ComponentMeta> {
+        component::registered::<Self>()
+    }
+
+    /// Registers `Impl` as an implementation of the trait object type `Dyn`, returning whether it
+    /// wasn't registered before.
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Plugins);
+    ///
+    /// trait Greeter: Sync {
+    ///     fn greet(&self) -> &'static str;
+    /// }
+    ///
+    /// struct English;
+    /// impl Greeter for English {
+    ///     fn greet(&self) -> &'static str {
+    ///         "hello"
+    ///     }
+    /// }
+    ///
+    /// struct German;
+    /// impl Greeter for German {
+    ///     fn greet(&self) -> &'static str {
+    ///         "hallo"
+    ///     }
+    /// }
+    ///
+    /// Plugins::register_impl::<dyn Greeter, English>(|| &English);
+    /// Plugins::register_impl::<dyn Greeter, German>(|| &German);
+    ///
+    /// let mut greetings: Vec<_> = Plugins::implementations_of::<dyn Greeter>()
+    ///     .map(|greeter| greeter.get().greet())
+    ///     .collect();
+    /// greetings.sort();
+    /// assert_eq!(greetings, ["hallo", "hello"]);
+    /// ```
+    ///
+    /// `instance` returns the instance of the implementation (e.g. a constant or a generic static
+    /// of `Impl`), coerced to `Dyn`. An implementation is only registered once per `Dyn`, later
+    /// registrations with other functions are ignored. Registration links the implementation into
+    /// an intrusive list of generic statics, so it doesn't allocate and doesn't have to run before
+    /// `main`.
+    #[inline]
+    fn register_impl<Dyn: ?Sized + 'static, Impl: 'static>(instance: fn() -> &'static Dyn) -> bool {
+        plugin::register::<Self, Dyn, Impl>(instance)
+    }
+
+    /// Iterates over the implementations of the trait object type `Dyn` registered with
+    /// [`Namespace::register_impl`], most recently registered first.
+    #[inline]
+    fn implementations_of<Dyn: ?Sized + 'static>(
+    ) -> impl Iterator<Item = &'static Implementation<Dyn>> {
+        plugin::implementations::<Self, Dyn>()
+    }
+
+    /// Returns the metric `M` for the marker type `Tag`, starting at zero (`metrics` feature).
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, CounterMetric, GaugeMetric, Namespace};
+    ///
+    /// define_namespace!(Metrics);
+    ///
+    /// struct Requests;
+    /// struct Connections;
+    ///
+    /// Metrics::metric::<CounterMetric, Requests>().inc();
+    /// Metrics::metric::<GaugeMetric, Connections>().add(2);
+    /// assert_eq!(Metrics::metric::<CounterMetric, Requests>().get(), 1);
+    /// ```
+    ///
+    /// The metric is registered on its first access, so exporters can collect it with
+    /// [`gather`]. Metrics of different types (e.g. a counter and a histogram) for the same `Tag`
+    /// are distinct.
+    #[cfg(all(feature = "metrics", not(feature = "loom")))]
+    #[inline]
+    #[must_use]
+    fn metric<M: Metric, Tag: 'static>() -> &'static M {
+        metrics::metric::<Self, M, Tag>()
+    }
+
+    /// Returns the [`GenericOnce`] for the marker type `Tag`, to run a one-shot side effect.
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Setup);
+    ///
+    /// struct Callbacks;
+    ///
+    /// let mut calls = 0;
+    /// Setup::once::<Callbacks>().call_once(|| calls += 1);
+    /// Setup::once::<Callbacks>().call_once(|| calls += 1);
+    /// assert_eq!(calls, 1);
+    /// ```
+    ///
+    /// This is a plain generic static, `Tag` doesn't have to be [`Zeroable`] though.
+    #[inline]
+    #[must_use]
+    fn once<Tag: 'static>() -> &'static GenericOnce {
+        &Self::generic_static::<once::OnceFor<Tag>>().0
+    }
+
+    /// Returns the [`GenericHook`] with the signature `fn(Args) -> Ret` for the marker type `Tag`.
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Faults);
+    ///
+    /// struct BeforeWrite;
+    ///
+    /// fn write(data: &[u8]) -> io::Result<usize> {
+    ///     if let Some(Err(err)) = Faults::hook::<usize, io::Result<()>, BeforeWrite>()
+    ///         .call_if_set(data.len())
+    ///     {
+    ///         return Err(err);
+    ///     }
+    ///     Ok(data.len())
+    /// }
+    ///
+    /// assert_eq!(write(b"abc").unwrap(), 3);
+    /// Faults::hook::<usize, io::Result<()>, BeforeWrite>()
+    ///     .set(|_| Err(io::ErrorKind::WriteZero.into()));
+    /// assert!(write(b"abc").is_err());
+    /// ```
+    ///
+    /// Multiple arguments are passed as a tuple. This is a plain generic static, keyed by the
+    /// signature and `Tag`.
+    #[inline]
+    #[must_use]
+    fn hook<Args: 'static, Ret: 'static, Tag: 'static>() -> &'static GenericHook<Args, Ret> {
+        &Self::generic_static::<hook::HookFor<Args, Ret, Tag>>().0
+    }
+
+    /// Returns the static instance of `T`, created with [`Default`] on first access.
+    ///
+    /// ```rust
+    /// use std::{collections::HashMap, sync::Mutex};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// Test::singleton::<Mutex<HashMap<u32, String>>>()
+    ///     .lock()
+    ///     .unwrap()
+    ///     .insert(1, "one".into());
+    /// ```
+    ///
+    /// This is a shorthand for [`Namespace::generic_lazy`] with `T::default`, so both share the
+    /// same static.
+    #[inline]
+    #[must_use]
+    fn singleton<T: 'static + Default + Send + Sync>() -> &'static T {
+        Self::generic_lazy::<T>(T::default)
+    }
+
+    /// Returns the [`GenericOnceLock`] for `T`, which can store any `T: 'static` (not only
+    /// [`Zeroable`] types) by allocating it on first initialization.
+    #[inline]
+    #[must_use]
+    fn generic_once<T: 'static + Send + Sync>() -> &'static GenericOnceLock<T> {
+        Self::generic_static::<GenericOnceLock<T>>()
+    }
+
+    /// Moves `value` into the static for `T` if it hasn't been initialized yet.
+    ///
+    /// This is meant for values that are constructed once at startup (e.g. from command line
+    /// arguments) and read everywhere afterwards:
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// struct Args {
+    ///     verbose: bool,
+    /// }
+    ///
+    /// let args = Test::init_with(Args { verbose: true }).ok().unwrap();
+    /// assert!(args.verbose);
+    /// assert!(Test::init_with(Args { verbose: false }).is_err());
+    /// ```
+    ///
+    /// Returns `value` back if the static has already been initialized, the existing value can
+    /// be read with [`GenericOnceLock::get`]. This uses the same static as
+    /// [`Namespace::generic_once`].
+    #[inline]
+    fn init_with<T: 'static + Send + Sync>(value: T) -> Result<&'static T, T> {
+        let cell = Self::generic_once::<T>();
+        cell.set(value)?;
+        Ok(cell.get().expect("initialized"))
+    }
+
+    /// Like [`Namespace::generic_static_not_sync`] but without requiring `T: Zeroable`.
+    ///
+    /// The storage is returned as possibly uninitialized (it's zeroed, but zero might not be a
+    /// valid `T`) and is wrapped in an [`UnsafeCell`], so the caller can initialize it in place.
+    ///
+    /// ```rust
+    /// use std::num::NonZeroU32;
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let slot = unsafe { Test::generic_static_unchecked::<NonZeroU32>() };
+    /// let value = unsafe {
+    ///     (*slot.get()).write(NonZeroU32::new(42).unwrap());
+    ///     (*slot.get()).assume_init()
+    /// };
+    /// assert_eq!(value.get(), 42);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as for [`Namespace::generic_static_not_sync`] apply. Additionally,
+    /// the caller is responsible for synchronizing the initialization and for only assuming the
+    /// storage to be initialized once it has been.
+    #[inline]
+    #[must_use]
+    unsafe fn generic_static_unchecked<T: 'static>() -> &'static UnsafeCell<MaybeUninit<T>> {
+        unsafe { Self::generic_static_not_sync::<UnsafeCell<MaybeUninit<T>>>() }
+    }
+
+    /// Returns the zero-initialized static for `T` wrapped in a [`SyncUnsafeCell`], for interior
+    /// mutability of types that don't provide it themselves.
+    ///
+    /// ```rust
+    /// #![feature(sync_unsafe_cell)]
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let buf = Test::generic_static_cell::<[u8; 4]>();
+    /// unsafe { (*buf.get())[0] = 1 };
+    /// assert_eq!(unsafe { *buf.get() }, [1, 0, 0, 0]);
+    /// ```
+    ///
+    /// The cell is shared by every thread resolving `T` in this namespace, so (as with a `static`
+    /// [`SyncUnsafeCell`]) accesses through [`SyncUnsafeCell::get`] must be synchronized by the
+    /// caller. This is a different static than the one returned by
+    /// [`Namespace::generic_static`] for the same `T`.
+    #[inline]
+    #[must_use]
+    fn generic_static_cell<T: 'static + Zeroable>() -> &'static SyncUnsafeCell<T> {
+        // SAFETY: The value is only accessible through raw pointers.
+        unsafe { Self::generic_static_not_sync::<SyncUnsafeCell<T>>() }
+    }
+
+    /// Returns the address of the static returned by [`Namespace::generic_static`] without
+    /// creating a reference to it.
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let ptr = Test::generic_static_ptr::<u64>();
+    /// unsafe { ptr.as_ptr().write_volatile(42) };
+    /// assert_eq!(unsafe { ptr.as_ptr().read_volatile() }, 42);
+    /// ```
+    ///
+    /// This is meant for code that manages the aliasing of the static itself (e.g. volatile,
+    /// MMIO-like accesses), the caller is responsible for synchronizing the accesses. The address
+    /// of a zero-sized `T` is dangling.
+    #[inline]
+    #[must_use]
+    fn generic_static_ptr<T: 'static + Zeroable>() -> NonNull<T> {
+        if const { mem::size_of::<T>() == 0 } {
+            return NonNull::dangling();
+        }
+
+        resolve::<Self, T>()
+    }
+
+    /// Like [`Namespace::generic_static`] but with guaranteed-stable addresses.
+    ///
+    /// The first address resolved for `T` in this namespace becomes the canonical one and is
+    /// returned by every subsequent call, even if inlining, LTO or multiple codegen units
+    /// produced several copies of the storage. Each copy caches the canonical address, so the
+    /// registry is only consulted once per copy.
+    ///
+    /// Note that the returned storage is only guaranteed to be shared with other
+    /// `generic_static_strict` calls, plain [`Namespace::generic_static`] calls may still observe
+    /// a different copy.
+    #[must_use]
+    fn generic_static_strict<T: 'static + Zeroable + Sync>() -> &'static T {
+        let addr = registry::canonicalize_cached::<Self, T>(Self::generic_static::<T>().into());
+
+        unsafe { addr.as_ref() }
+    }
+
+    /// Like [`Namespace::generic_static`] but the resolved address is cached, so the resolution
+    /// only runs on the first call and every subsequent call is a single acquire load (a plain load
+    /// on `x86_64`).
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Counters);
+    ///
+    /// fn hit<T: 'static>() {
+    ///     Counters::generic_static_cached::<AtomicU64>().fetch_add(1, Ordering::Relaxed);
+    /// }
+    ///
+    /// hit::<u8>();
+    /// assert_eq!(Counters::generic_static::<AtomicU64>().load(Ordering::Relaxed), 1);
+    /// ```
+    ///
+    /// This is the same static as the one returned by [`Namespace::generic_static`]. Without
+    /// features that add bookkeeping to the resolution, resolving `T` is already a single
+    /// PC-relative address computation, so this only pays off with features like `dylib-dedup`,
+    /// `freeze`, `check-duplicates` or `touched-statics`. The cold resolution is never inlined
+    /// and runs once per copy of the storage (see [`Namespace::generic_static_strict`]), so
+    /// `touched-statics` only records the first access.
+    #[inline]
+    #[must_use]
+    fn generic_static_cached<T: 'static + Zeroable + Sync>() -> &'static T {
+        if const { mem::size_of::<T>() == 0 } {
+            return unsafe { NonNull::dangling().as_ref() };
+        }
+
+        unsafe { cached::resolve::<Self, T>().as_ref() }
+    }
+
+    /// Returns the statics of all element types of the tuple `Ts`, resolved together in a single
+    /// call.
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize};
+    /// use generic_statics::{define_namespace, Namespace};
+    ///
+    /// define_namespace!(Test);
+    ///
+    /// let (a, b, c) = Test::generic_statics_of::<(AtomicU32, AtomicU64, AtomicUsize)>();
+    /// assert_eq!(a as *const _, Test::generic_static::<AtomicU32>() as *const _);
+    /// ```
+    ///
+    /// These are the same statics as the ones returned by [`Namespace::generic_static`]. The
+    /// resolution is never inlined, so hot paths using several statics only pay for one call.
+    /// Tuples of up to 8 types are supported.
+    #[inline]
+    #[must_use]
+    fn generic_statics_of<Ts: StaticTuple>() -> Ts::Refs {
+        Ts::__resolve::<Self>()
+    }
+}
+
+/// Resolves the address of the storage of `T` in namespace `N`, with the shared resolver if the
+/// `shared-resolver` feature is enabled.
+#[inline]
+fn resolve<N: Namespace, T: Storage>() -> NonNull<T> {
+    #[cfg(feature = "shared-resolver")]
+    return resolver::resolve(const { &resolver::Resolver::of::<N, T>() }).cast();
+    #[cfg(not(feature = "shared-resolver"))]
+    resolve_storage::<N, T>()
+}
+
+/// Resolves the address of the storage of `T` in namespace `N`.
+#[inline]
+fn resolve_storage<N: Namespace, T: Storage>() -> NonNull<T> {
+    #[cfg(all(
+        feature = "freeze",
+        any(unix, windows),
+        not(any(feature = "loom", miri))
+    ))]
+    if N::FREEZABLE {
+        let addr = freeze::resolve::<N, T>();
+        #[cfg(all(feature = "touched-statics", not(feature = "loom")))]
+        touched::touch::<N, T>(addr);
+        return addr;
+    }
+
+    let addr = reserve::<N, T>();
+
+    #[cfg(all(feature = "check-duplicates", debug_assertions))]
+    registry::check_unique::<N, T>(addr);
+
+    // The storage of this linked image is only used if it's the first one resolved for `(N, T)`
+    // in the whole process.
+    #[cfg(feature = "dylib-dedup")]
+    let addr = registry::canonicalize_cached::<N, T>(addr);
+
+    #[cfg(all(feature = "touched-statics", not(feature = "loom")))]
+    touched::touch::<N, T>(addr);
+
+    addr
+}
+
+/// The maximum alignment of the storage supported by the object format.
+///
+/// On COFF, this is the section alignment of the image (see `asm::reserve`).
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos"))]
+const MAX_ALIGN: usize = 1 << 15;
+#[cfg(target_os = "windows")]
+const MAX_ALIGN: usize = 1 << 12;
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "tvos",
+    target_os = "windows"
+)))]
+const MAX_ALIGN: usize = 1 << 16;
+
+/// The maximum size of the storage, which has to be reachable by PC-relative addressing (±2 GiB)
+/// together with the rest of the image.
+const MAX_SIZE: usize = 1 << 30;
+
+/// Fails compilation with a readable error if the storage for `T` can't be emitted.
+///
+/// The offending type is named by rustc's "inside `check_layout::<T>`" note.
+const fn check_layout<T>() {
+    assert!(
+        mem::align_of::<T>() <= MAX_ALIGN,
+        "the alignment of this generic static exceeds the maximum supported on this target (see \
+         the `generic-statics` caveats)"
+    );
+    assert!(
+        mem::size_of::<T>() <= MAX_SIZE,
+        "the size of this generic static exceeds the maximum of 1 GiB (see the `generic-statics` \
+         caveats)"
+    );
+}
+
+/// Reserves the storage of `T` in namespace `N` with the active backend.
+#[inline]
+fn reserve<N: Namespace, T: Storage>() -> NonNull<T> {
+    let addr = reserve_internal::<N, T>();
+
+    #[cfg(all(feature = "introspection", not(any(feature = "loom", miri))))]
+    asm::describe::<N, T>();
+
+    addr
+}
+
+/// The private namespace of the bookkeeping this crate keeps next to the statics of namespace `N`
+/// (see [`reserve_bookkeeping`]).
+///
+/// The bookkeeping isn't placed into the section of `N`, accounted in its budget or frozen with
+/// it, but has the identity and salt of `N`, so it's shared between crates like the storage.
+struct Bookkeeping<N>(std::marker::PhantomData<fn() -> N>);
+
+impl<N> Clone for Bookkeeping<N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N> Copy for Bookkeeping<N> {}
+
+unsafe impl<N: Namespace> Namespace for Bookkeeping<N> {
+    const IDENTITY: Identity = N::IDENTITY;
+    const SALT: u64 = N::SALT;
+}
+
+/// Reserves the storage of the bookkeeping `T` of a static of namespace `N`.
+#[inline]
+fn reserve_bookkeeping<N: Namespace, T: Storage>() -> NonNull<T> {
+    reserve_internal::<Bookkeeping<N>, T>()
+}
+
+/// Reserves storage like [`reserve`], without describing it (`introspection` feature).
+///
+/// This is used for the bookkeeping this crate keeps next to the storage of a static.
+#[inline]
+fn reserve_internal<N: Namespace, T: Storage>() -> NonNull<T> {
+    const { check_layout::<T>() };
+
+    #[cfg(not(any(feature = "loom", miri)))]
+    let addr = N::__reserve::<T>();
+    #[cfg(feature = "loom")]
+    let addr = loom::reserve::<N, T>();
+    #[cfg(all(miri, not(feature = "loom")))]
+    let addr = miri::reserve::<N, T>();
+
+    sanitizer::unpoison(addr.as_ptr());
+
+    addr
+}
+
+/// Stands in for the export of generic statics under loom and miri, whose statics aren't reserved
+/// at link time.
+#[cfg(any(feature = "loom", miri))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export {
+    ($N:ty, $T:ty, $name:literal) => {};
+}
+
+/// Stands in for the storage reservation of namespaces with a custom section under loom and miri,
+/// whose backends reserve the storage of all namespaces (see [`reserve_internal`]).
+#[cfg(any(feature = "loom", miri))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __reserve {
+    ($N:ty, $T:ty, $section:tt) => {
+        ::core::unreachable!()
+    };
+}
+
+/// Defines a new [`Namespace`].
+///
+/// ```rust
+/// use generic_statics::define_namespace;
+///
+/// define_namespace!(Private);
+/// define_namespace!(pub Public);
+///
+/// define_namespace!(
+///     /// Attributes (including doc comments) are applied to the namespace type.
+///     #[unsafe(identity(process))]
+///     pub(crate) PerProcess
+/// );
+/// ```
+///
+/// The following attributes configure the namespace:
+///
+/// - `#[unsafe(identity(process))]`, `#[identity(crate)]`: See [`Identity`]. Sharing the storage
+///   between crates is unsafe, as the namespace must only be used with types whose names are
+///   unique (see [`Identity::Process`]).
+/// - `#[freezable]`: See [`Namespace::FREEZABLE`] (`freeze` feature).
+/// - `#[weak]`: See [`Namespace::WEAK`].
+/// - `#[budget(bytes)]`: See [`Namespace::BUDGET`].
+/// - `#[unsalted]`: Don't salt the storage symbols with the defining crate (see
+///   [`Namespace::SALT`]), e.g. to intentionally share a namespace's storage between multiple
+///   versions of a crate.
+///
+/// Namespaces can have type and const parameters, so a library can partition its statics by its
+/// own type parameters. Every instantiation of the namespace is a distinct namespace:
+///
+/// ```rust
+/// use std::sync::atomic::AtomicUsize;
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(pub PerKey<K, const SHARD: usize>);
+///
+/// fn hits<K: 'static>() -> &'static AtomicUsize {
+///     PerKey::<K, 0>::generic_static::<AtomicUsize>()
+/// }
+///
+/// assert_ne!(hits::<u8>() as *const _, hits::<u16>() as *const _);
+/// ```
+///
+/// A namespace can be declared as an alias of another namespace with `=`, e.g. to rename a
+/// namespace without splitting its statics. The alias is the same type, so it resolves to the
+/// same storage for every `T` and has the configuration of the aliased namespace (the namespace
+/// attributes above don't apply to aliases):
+///
+/// ```rust
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(pub Renamed);
+/// define_namespace!(
+///     #[deprecated = "use `Renamed`"]
+///     pub Original = Renamed
+/// );
+///
+/// #[allow(deprecated)]
+/// let original = Original::generic_static::<u64>();
+/// assert_eq!(original as *const _, Renamed::generic_static::<u64>() as *const _);
+/// ```
+///
+/// Namespaces can be declared as children of other namespaces with `under`, which is recorded
+/// in [`Namespace::PARENT`] (see [`NamespaceInfo`]):
+///
+/// ```rust
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(pub App);
+/// define_namespace!(pub Cache under App);
+/// define_namespace!(pub PerKey<K> under Cache);
+///
+/// assert!(PerKey::<u32>::is_within::<App>());
+/// ```
+///
+/// The storage of a namespace can be placed into a custom link section with `in` (before
+/// `under`), e.g. a memory region set up by the linker script, see [`Namespace::SECTION`]:
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # {
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(pub Dma in ".dma_bss");
+///
+/// let buffer = Dma::generic_static::<[u8; 4096]>();
+/// # }
+/// ```
+///
+/// The section name is passed to the assembler as is, so it has to be valid for the object
+/// format of the target (e.g. `__DATA,__dma` on Mach-O). On Windows, the linker merges sections
+/// named `name$suffix` into the image section `name` (ordered by suffix, e.g. `.dma$b`), whose
+/// name can have at most 8 bytes, longer names fail to compile. Zeroed and uninitialized statics
+/// are emitted as zero-fill (`@nobits`) section and statics with an initial value as regular
+/// section, which the assembler doesn't allow to mix, so a custom section should only hold one
+/// kind of statics. The storage of these namespaces isn't part of `Namespace::storage_range`
+/// (`storage-range` feature). Individual statics can be placed into a section with
+/// [`Namespace::generic_static_in`].
+///
+/// Type parameters are implicitly `'static`. Their bounds must be single tokens separated by `+`
+/// (e.g. `K: Send + Sync`), import the traits to use longer paths.
+#[macro_export]
+macro_rules! define_namespace {
+    ($($tt:tt)*) => {
+        $crate::__define_namespace!(
+            []
+            []
+            [
+                const SALT: u64 = $crate::__salt(
+                    ::core::option_env!("CARGO_PKG_NAME"),
+                    ::core::option_env!("CARGO_PKG_VERSION"),
+                );
+            ]
+            $($tt)*
+        );
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_namespace {
+    (
+        [$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] #[unsafe(identity(process))] $($rest:tt)*
+    ) => {
+        $crate::__define_namespace!(
+            [$($attr)*]
+            [$($item)* const IDENTITY: $crate::Identity = $crate::Identity::Process;]
+            [$($salt)*]
+            $($rest)*
+        );
+    };
+    ([$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] #[identity(crate)] $($rest:tt)*) => {
+        $crate::__define_namespace!(
+            [$($attr)*]
+            [$($item)* const IDENTITY: $crate::Identity = $crate::Identity::Crate;]
+            [$($salt)*]
+            $($rest)*
+        );
+    };
+    ([$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] #[identity(process)] $($rest:tt)*) => {
+        ::core::compile_error!(
+            "sharing the storage of a namespace between crates requires \
+             `#[unsafe(identity(process))]`, see `generic_statics::Identity::Process`"
+        );
+    };
+    ([$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] #[freezable] $($rest:tt)*) => {
+        $crate::__define_namespace!(
+            [$($attr)*]
+            [$($item)* const FREEZABLE: bool = true;]
+            [$($salt)*]
+            $($rest)*
+        );
+    };
+    ([$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] #[weak] $($rest:tt)*) => {
+        $crate::__define_namespace!(
+            [$($attr)*]
+            [$($item)* const WEAK: bool = true;]
+            [$($salt)*]
+            $($rest)*
+        );
+    };
+    ([$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] #[budget($budget:expr)] $($rest:tt)*) => {
+        $crate::__define_namespace!(
+            [$($attr)*]
+            [$($item)*
+                const BUDGET: ::core::option::Option<usize> = ::core::option::Option::Some($budget);]
+            [$($salt)*]
+            $($rest)*
+        );
+    };
+    ([$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] #[unsalted] $($rest:tt)*) => {
+        $crate::__define_namespace!([$($attr)*] [$($item)*] [] $($rest)*);
+    };
+    ([$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] #[$meta:meta] $($rest:tt)*) => {
+        $crate::__define_namespace!([$($attr)* #[$meta]] [$($item)*] [$($salt)*] $($rest)*);
+    };
+    ([$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] $vis:vis $name:ident = $target:ty) => {
+        $($attr)*
+        $vis type $name = $target;
+    };
+    (
+        [$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*]
+        $vis:vis $name:ident < $($param:ident),* $(,)? > = $target:ty
+    ) => {
+        $($attr)*
+        $vis type $name<$($param),*> = $target;
+    };
+    (
+        [$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*]
+        $vis:vis $name:ident in $section:literal $($rest:tt)*
+    ) => {
+        $crate::__define_namespace!(
+            [$($attr)*]
+            [$($item)*
+                const SECTION: ::core::option::Option<&'static str> =
+                    ::core::option::Option::Some($section);
+                #[inline(always)]
+                fn __reserve<__T: $crate::__Storage>() -> ::core::ptr::NonNull<__T> {
+                    $crate::__reserve!(Self, __T, $section)
+                }
+            ]
+            [$($salt)*]
+            $vis $name $($rest)*
+        );
+    };
+    ([$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] $vis:vis $name:ident under $parent:ty) => {
+        $crate::__define_namespace!(
+            [$($attr)*]
+            [$($item)* const PARENT: ::core::option::Option<fn() -> $crate::NamespaceInfo> =
+                ::core::option::Option::Some(<$parent as $crate::Namespace>::info);]
+            [$($salt)*]
+            $vis $name
+        );
+    };
+    ([$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] $vis:vis $name:ident) => {
+        $($attr)*
+        #[derive(Debug, Copy, Clone)]
+        $vis struct $name;
+
+        unsafe impl $crate::Namespace for $name {
+            $($item)*
+            $($salt)*
+        }
+    };
+    ([$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] $vis:vis $name:ident < $($generics:tt)*) => {
+        $crate::__define_generic_namespace!(
+            [$($attr)*] [$($item)*] [$($salt)*] [$vis $name] [] [] [] $($generics)*
+        );
+    };
+}
+
+/// Munches the generic parameters of a namespace (see [`define_namespace`]), accumulating the
+/// parameters, the arguments and the type parameters.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_generic_namespace {
+    (
+        $head:tt $item:tt $salt:tt $name:tt [$($param:tt)*] [$($arg:tt)*] $ty:tt
+        const $c:ident : $t:ty, $($rest:tt)*
+    ) => {
+        $crate::__define_generic_namespace!(
+            $head $item $salt $name [$($param)* const $c: $t,] [$($arg)* $c,] $ty $($rest)*
+        );
+    };
+    (
+        $head:tt $item:tt $salt:tt $name:tt [$($param:tt)*] [$($arg:tt)*] $ty:tt
+        const $c:ident : $t:ty > $($tail:tt)*
+    ) => {
+        $crate::__define_generic_namespace!(
+            @finish $head $item $salt $name [$($param)* const $c: $t,] [$($arg)* $c,] $ty
+            $($tail)*
+        );
+    };
+    (
+        $head:tt $item:tt $salt:tt $name:tt [$($param:tt)*] [$($arg:tt)*] [$($ty:tt)*]
+        $p:ident $(: $first:tt $(+ $bound:tt)*)?, $($rest:tt)*
+    ) => {
+        $crate::__define_generic_namespace!(
+            $head $item $salt $name
+            [$($param)* $p: 'static $(+ $first $(+ $bound)*)?,] [$($arg)* $p,] [$($ty)* $p,]
+            $($rest)*
+        );
+    };
+    (
+        $head:tt $item:tt $salt:tt $name:tt [$($param:tt)*] [$($arg:tt)*] [$($ty:tt)*]
+        $p:ident $(: $first:tt $(+ $bound:tt)*)? > $($tail:tt)*
+    ) => {
+        $crate::__define_generic_namespace!(
+            @finish $head $item $salt $name
+            [$($param)* $p: 'static $(+ $first $(+ $bound)*)?,] [$($arg)* $p,] [$($ty)* $p,]
+            $($tail)*
+        );
+    };
+    (
+        @finish [$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] [$vis:vis $name:ident]
+        [$($param:tt)*] [$($arg:tt)*] [$($ty:tt)*] in $section:literal $($tail:tt)*
+    ) => {
+        $crate::__define_generic_namespace!(
+            @finish
+            [$($attr)*]
+            [$($item)*
+                const SECTION: ::core::option::Option<&'static str> =
+                    ::core::option::Option::Some($section);
+                #[inline(always)]
+                fn __reserve<__T: $crate::__Storage>() -> ::core::ptr::NonNull<__T> {
+                    $crate::__reserve!(Self, __T, $section)
+                }
+            ]
+            [$($salt)*]
+            [$vis $name]
+            [$($param)*] [$($arg)*] [$($ty)*]
+            $($tail)*
+        );
+    };
+    (
+        @finish [$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] [$vis:vis $name:ident]
+        [$($param:tt)*] [$($arg:tt)*] [$($ty:tt)*] under $parent:ty
+    ) => {
+        $crate::__define_generic_namespace!(
+            @finish
+            [$($attr)*]
+            [$($item)* const PARENT: ::core::option::Option<fn() -> $crate::NamespaceInfo> =
+                ::core::option::Option::Some(<$parent as $crate::Namespace>::info);]
+            [$($salt)*]
+            [$vis $name]
+            [$($param)*] [$($arg)*] [$($ty)*]
+        );
+    };
+    (
+        @finish [$($attr:tt)*] [$($item:tt)*] [$($salt:tt)*] [$vis:vis $name:ident]
+        [$($param:tt)*] [$($arg:tt)*] [$($ty:tt)*]
+    ) => {
+        $($attr)*
+        $vis struct $name<$($param)*>(::core::marker::PhantomData<fn() -> ($($ty)*)>);
+
+        impl<$($param)*> ::core::clone::Clone for $name<$($arg)*> {
+            #[inline]
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<$($param)*> ::core::marker::Copy for $name<$($arg)*> {}
+
+        impl<$($param)*> ::core::fmt::Debug for $name<$($arg)*> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(::core::stringify!($name))
+            }
+        }
+
+        unsafe impl<$($param)*> $crate::Namespace for $name<$($arg)*> {
+            $($item)*
+            $($salt)*
+        }
+    };
+}
+
+/// Forces the storage of the given types in a namespace to be emitted into this crate.
+///
+/// ```rust
+/// use generic_statics::{define_namespace, instantiate_generic_statics};
+///
+/// define_namespace!(Test);
+///
+/// struct Foo;
+///
+/// instantiate_generic_statics!(Test: [u32, Option<&'static Foo>, [u8; 16]]);
+/// ```
+///
+/// The storage of a generic static is only emitted where its accessor is monomorphized, i.e.
+/// where it's used. This instantiates the accessors of the listed types in a function that is
+/// kept by the compiler (even if it's never called), so their storage is part of the binary
+/// regardless of whether (or where) it's accessed, e.g. to make it visible to the startup
+/// verification or to other objects. The types must be [`Zeroable`].
+#[macro_export]
+macro_rules! instantiate_generic_statics {
+    ($ns:ty: [$($ty:ty),* $(,)?]) => {
+        const _: () = {
+            fn instantiate() {
+                $(
+                    let _ = unsafe {
+                        <$ns as $crate::Namespace>::generic_static_not_sync::<$ty>()
+                    };
+                )*
+            }
+
+            #[used]
+            static INSTANTIATE: fn() = instantiate;
+        };
+    };
+}
+
+/// Exports the static of a type in a namespace under a global symbol, so C (or C++) code linked
+/// into the same binary can access the same storage.
+///
+/// ```rust
+/// use std::sync::atomic::AtomicU64;
+/// use generic_statics::{define_namespace, export_generic_static, Namespace};
+///
+/// define_namespace!(
+///     #[unsafe(identity(process))]
+///     Runtime
+/// );
+///
+/// #[repr(C)]
+/// struct Stats {
+///     allocations: AtomicU64,
+///     frees: AtomicU64,
+/// }
+///
+/// unsafe impl generic_statics::Zeroable for Stats {}
+///
+/// // Declared as `extern struct stats runtime_stats;` on the C side.
+/// export_generic_static!(Runtime: Stats as "runtime_stats");
+///
+/// let stats = Runtime::generic_static::<Stats>();
+/// ```
+///
+/// The symbol is the storage of [`Namespace::generic_static`] for the type (which must be
+/// [`Zeroable`]), so the type should be `#[repr(C)]` and match the declaration of the other side.
+/// The accessor is instantiated like with [`instantiate_generic_statics`], so the static is
+/// part of the binary even if the Rust code never accesses it.
+///
+/// Only statics of [`Identity::Process`] namespaces without a custom section (see
+/// [`define_namespace`]) can be exported, others fail to compile. A static can only be exported
+/// once per binary. With the `storage-range` feature, the storage of an exported static isn't
+/// part of `Namespace::storage_range`.
+#[macro_export]
+macro_rules! export_generic_static {
+    ($ns:ty: $ty:ty as $name:literal) => {
+        const _: () = {
+            $crate::__export!($ns, $ty, $name);
+        };
+        $crate::instantiate_generic_statics!($ns: [$ty]);
+    };
+}
+
+/// Returns the static of a type in a namespace keyed by a string, like
+/// [`Namespace::generic_static_keyed`] but without defining a marker type per name.
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use generic_statics::{define_namespace, generic_static_named};
+///
+/// define_namespace!(Metrics);
+///
+/// let requests: &'static AtomicUsize = generic_static_named!(Metrics, AtomicUsize, "requests");
+/// let errors = generic_static_named!(Metrics, AtomicUsize, "errors");
+/// requests.fetch_add(1, Ordering::Relaxed);
+/// assert_eq!(errors.load(Ordering::Relaxed), 0);
+/// ```
+///
+/// The name must be a constant expression of type `&str`. It's hashed (64-bit FNV-1a) at compile
+/// time and the hash is the key, as `&'static str` const parameters aren't available on stable
+/// Rust.
+#[macro_export]
+macro_rules! generic_static_named {
+    ($ns:ty, $ty:ty, $name:expr $(,)?) => {
+        <$ns as $crate::Namespace>::generic_static_keyed::<
+            $ty,
+            $crate::__Named<{ $crate::__name_hash($name) }>,
+        >()
+    };
+}
+
+/// Declares a static inside a (generic) function, which is a reference to a generic static
+/// unique to this call site and the listed generic parameters of the function.
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use generic_statics::generic_static;
+///
+/// fn calls<T: 'static>() -> usize {
+///     generic_static!(static COUNT<T>: AtomicUsize);
+///     COUNT.fetch_add(1, Ordering::Relaxed) + 1
+/// }
+///
+/// assert_eq!(calls::<u8>(), 1);
+/// assert_eq!(calls::<u8>(), 2);
+/// assert_eq!(calls::<u16>(), 1);
+/// ```
+///
+/// The generic parameters the static is keyed by must be listed (a macro can't see the generics
+/// of the enclosing function) and be `'static`. The static is zero-initialized, so its type must
+/// be [`Zeroable`] and `Sync`.
+///
+/// The static is stored in a namespace named after the static, which is keyed by the line and
+/// column of the macro invocation.
+#[macro_export]
+macro_rules! generic_static {
+    (static $name:ident $(<$($param:ident),* $(,)?>)? : $ty:ty $(;)?) => {
+        #[allow(non_snake_case)]
+        let $name: &'static $ty = {
+            $crate::define_namespace!(
+                #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+                $name<$($($param,)*)? const LINE: u32, const COLUMN: u32>
+            );
+
+            <$name<$($($param,)*)? { ::core::line!() }, { ::core::column!() }>
+                as $crate::Namespace>::generic_static::<$ty>()
+        };
+    };
+}
+
+#[cfg(all(test, not(feature = "loom")))]
+mod tests {
+    use std::{
+        assert_ne,
+        cell::{Cell, SyncUnsafeCell},
+        marker::{PhantomData, PhantomPinned},
+        num::{NonZeroIsize, NonZeroU32, Saturating, Wrapping},
+        ptr::NonNull,
+        rc::Rc,
+        sync::{
+            atomic::{
+                AtomicIsize, AtomicPtr, AtomicU32, AtomicU64, AtomicU8, AtomicUsize, Ordering,
+            },
+            Arc,
+        },
+    };
+
+    use super::{ConstInit, GenericMutex, GenericRwLock, GenericSeqLock, Identity, Namespace};
+
+    define_namespace!(pub Test);
+
+    #[test]
+    fn generic_namespace() {
+        define_namespace!(PerKey<K, const N: usize>);
+        define_namespace!(Bounded<K: Send + Sync>);
+
+        let a = PerKey::<u8, 0>::generic_static::<AtomicU32>();
+        a.store(1, Ordering::Relaxed);
+        assert_eq!(
+            PerKey::<u16, 0>::generic_static::<AtomicU32>().load(Ordering::Relaxed),
+            0
+        );
+        assert_eq!(
+            PerKey::<u8, 1>::generic_static::<AtomicU32>().load(Ordering::Relaxed),
+            0
+        );
+        assert_eq!(
+            a as *const _,
+            PerKey::<u8, 0>::generic_static::<AtomicU32>() as *const _
+        );
+        assert_ne!(
+            Bounded::<u8>::generic_static::<AtomicU32>() as *const _,
+            Bounded::<u16>::generic_static::<AtomicU32>() as *const _
+        );
+    }
+
+    #[test]
+    fn global() {
+        use super::{global, Global};
+
+        global::<AtomicU32>().store(1, Ordering::Relaxed);
+        assert_eq!(
+            global::<AtomicU32>() as *const _,
+            Global::generic_static::<AtomicU32>() as *const _
+        );
+        assert_ne!(
+            global::<AtomicU32>() as *const _,
+            Test::generic_static::<AtomicU32>() as *const _
+        );
+    }
+
+    #[test]
+    fn alias() {
+        define_namespace!(Target);
+        define_namespace!(Alias = Target);
+        define_namespace!(PerKey<K>);
+        define_namespace!(PerKeyAlias<K> = PerKey<K>);
+
+        assert_eq!(
+            Alias::generic_static::<AtomicU32>() as *const _,
+            Target::generic_static::<AtomicU32>() as *const _
+        );
+        assert_eq!(
+            PerKeyAlias::<u8>::generic_static::<AtomicU32>() as *const _,
+            PerKey::<u8>::generic_static::<AtomicU32>() as *const _
+        );
+    }
+
+    #[test]
+    fn call_site() {
+        fn a<T: 'static>() -> &'static AtomicU32 {
+            generic_static!(static COUNT<T>: AtomicU32);
+            COUNT
+        }
+
+        fn b<T: 'static>() -> &'static AtomicU32 {
+            generic_static!(static COUNT<T>: AtomicU32);
+            COUNT
+        }
+
+        fn c() -> (&'static AtomicU32, &'static AtomicU32) {
+            generic_static!(static COUNT: AtomicU32);
+            let first = COUNT;
+            generic_static!(static COUNT: AtomicU32);
+            (first, COUNT)
+        }
+
+        a::<u8>().store(1, Ordering::Relaxed);
+        assert_eq!(a::<u8>().load(Ordering::Relaxed), 1);
+        assert_eq!(a::<u16>().load(Ordering::Relaxed), 0);
+        assert_eq!(b::<u8>().load(Ordering::Relaxed), 0);
+
+        let (first, second) = c();
+        assert_ne!(first as *const _, second as *const _);
+        assert_eq!(first as *const _, c().0 as *const _);
+    }
+
+    #[test]
+    fn hierarchy() {
+        define_namespace!(Root);
+        define_namespace!(Child under Root);
+        define_namespace!(Leaf<K> under Child);
+
+        assert_eq!(Root::info().parent(), None);
+        assert_eq!(Child::info().parent(), Some(Root::info()));
+        assert_eq!(
+            Leaf::<u8>::info().ancestors().collect::<Vec<_>>(),
+            [Child::info(), Root::info()]
+        );
+        assert!(Leaf::<u8>::is_within::<Root>());
+        assert!(Child::is_within::<Child>());
+        assert!(!Root::is_within::<Child>());
+        assert!(!Leaf::<u8>::is_within::<Leaf<u16>>());
+        assert_ne!(
+            Root::generic_static::<AtomicU32>() as *const _,
+            Child::generic_static::<AtomicU32>() as *const _
+        );
+    }
+
+    #[test]
+    fn stable_addr() {
+        let a = Test::generic_static::<usize>() as *const _;
+        let b = Test::generic_static::<usize>() as *const _;
+        assert_eq!(a, b);
+
+        let d = Test::generic_static::<(AtomicUsize, AtomicUsize, AtomicUsize)>() as *const _;
+        let e = Test::generic_static::<(AtomicUsize, AtomicUsize, AtomicUsize)>() as *const _;
+        assert_eq!(d, e);
+
+        assert_ne!(a as *const (), d as *const _ as *const ());
+    }
+
+    #[test]
+    fn unique_address() {
+        let a = Test::generic_static::<AtomicUsize>() as *const _ as *const ();
+        let b = Test::generic_static::<AtomicIsize>() as *const _ as *const ();
+        let c = Test::generic_static::<usize>() as *const _ as *const ();
+        let d = Test::generic_static::<AtomicPtr<()>>() as *const _ as *const ();
+
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a, d);
+        assert_ne!(b, c);
+        assert_ne!(b, d);
+        assert_ne!(c, d);
+    }
+
+    #[test]
+    fn unique_address_dyn() {
+        trait Foo<A: 'static> {}
+
+        let a = Test::generic_static::<(u8, PhantomData<dyn Foo<usize> + Sync>)>() as *const _
+            as *const ();
+        let b = Test::generic_static::<(u8, PhantomData<dyn Foo<isize> + Sync>)>() as *const _
+            as *const ();
+        let c = Test::generic_static::<(u8, PhantomData<dyn Foo<()> + Sync>)>() as *const _
+            as *const ();
+
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(b, c);
+    }
+
+    #[test]
+    fn equal_type_names() {
+        let a = {
+            struct S(AtomicUsize);
+            unsafe impl crate::Zeroable for S {}
+            &Test::generic_static::<S>().0 as *const _ as *const ()
+        };
+        let b = {
+            struct S(AtomicUsize);
+            unsafe impl crate::Zeroable for S {}
+            &Test::generic_static::<S>().0 as *const _ as *const ()
+        };
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn option_non_zero() {
+        let a = Test::generic_static::<Option<NonZeroU32>>();
+        assert_eq!(*a, None);
+
+        let b = Test::generic_static::<Option<NonZeroIsize>>();
+        assert_eq!(*b, None);
+    }
+
+    #[test]
+    fn option_pointers() {
+        let a = Test::generic_static::<Option<&'static u32>>();
+        assert!(a.is_none());
+
+        let b = unsafe { Test::generic_static_not_sync::<Option<NonNull<u32>>>() };
+        assert!(b.is_none());
+
+        let c = Test::generic_static::<Option<fn(usize, &'static str) -> bool>>();
+        assert!(c.is_none());
+
+        let d = Test::generic_static::<Option<unsafe extern "C" fn()>>();
+        assert!(d.is_none());
+    }
+
+    #[test]
+    fn option_owned() {
+        let a = Test::generic_static::<Option<Box<u32>>>();
+        assert!(a.is_none());
+
+        let b = Test::generic_static::<Option<Arc<u32>>>();
+        assert!(b.is_none());
+
+        let c = unsafe { Test::generic_static_not_sync::<Option<Rc<u32>>>() };
+        assert!(c.is_none());
+    }
+
+    #[test]
+    fn primitives() {
+        assert_eq!(*Test::generic_static::<f64>(), 0.0);
+        assert_eq!(*Test::generic_static::<char>(), '\0');
+        assert_eq!(*Test::generic_static::<Wrapping<u16>>(), Wrapping(0));
+        assert_eq!(*Test::generic_static::<Saturating<i16>>(), Saturating(0));
+
+        let a = unsafe { Test::generic_static_not_sync::<Cell<u64>>() };
+        assert_eq!(a.get(), 0);
+
+        let b = Test::generic_static::<SyncUnsafeCell<u32>>();
+        assert_eq!(unsafe { *b.get() }, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "crossbeam-utils")]
+    fn crossbeam_utils() {
+        use crossbeam_utils::{atomic::AtomicCell, CachePadded};
+
+        let a = Test::generic_static::<CachePadded<AtomicUsize>>();
+        assert_eq!(a.fetch_add(1, Ordering::Relaxed), 0);
+
+        let b = Test::generic_static::<AtomicCell<Option<NonZeroU32>>>();
+        assert_eq!(b.swap(NonZeroU32::new(1)), None);
+    }
+
+    #[test]
+    #[cfg(feature = "parking_lot")]
+    fn parking_lot() {
+        use parking_lot::{Mutex, Once, RwLock};
+
+        let a = Test::generic_static::<Mutex<u64>>();
+        *a.lock() += 1;
+        assert_eq!(*Test::generic_static::<Mutex<u64>>().lock(), 1);
+
+        let b = Test::generic_static::<RwLock<[u32; 4]>>();
+        b.write()[1] = 2;
+        assert_eq!(*b.read(), [0, 2, 0, 0]);
+
+        let c = Test::generic_static::<Once>();
+        let mut calls = 0;
+        c.call_once(|| calls += 1);
+        c.call_once(|| calls += 1);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    #[cfg(feature = "spin")]
+    fn spin() {
+        use spin::{Mutex, Once, RwLock};
+
+        let a = Test::generic_static::<Mutex<u64>>();
+        *a.lock() += 1;
+        assert_eq!(*Test::generic_static::<Mutex<u64>>().lock(), 1);
+
+        let b = Test::generic_static::<RwLock<u16>>();
+        *b.write() = 2;
+        assert_eq!(*b.read(), 2);
+
+        let c = Test::generic_static::<Once<String>>();
+        assert_eq!(c.call_once(|| "first".to_owned()), "first");
+        assert_eq!(c.call_once(|| "second".to_owned()), "first");
+    }
+
+    #[test]
+    #[cfg(feature = "once_cell")]
+    fn once_cell() {
+        use once_cell::race::{OnceBool, OnceBox, OnceNonZeroUsize};
+
+        let a = Test::generic_static::<OnceBox<String>>();
+        assert_eq!(a.get_or_init(|| Box::new("first".to_owned())), "first");
+        assert_eq!(a.get_or_init(|| Box::new("second".to_owned())), "first");
+
+        let b = Test::generic_static::<OnceNonZeroUsize>();
+        assert_eq!(b.get(), None);
+
+        let c = Test::generic_static::<OnceBool>();
+        assert!(c.get_or_init(|| true));
+    }
+
+    #[test]
+    #[cfg(feature = "critical-section")]
+    #[should_panic(expected = "already locked")]
+    fn cs_mutex_reentrant() {
+        use super::GenericCsMutex;
+
+        let a = Test::generic_static::<GenericCsMutex<u64>>();
+        a.lock(|_| a.lock(|_| ()));
+    }
+
+    #[test]
+    fn const_init() {
+        struct Bytes([AtomicU8; 11]);
+
+        impl ConstInit for Bytes {
+            const INIT: Self = Bytes([const { AtomicU8::new(7) }; 11]);
+        }
+
+        struct Both(AtomicU64);
+
+        crate::impl_zeroable_transparent!(Both(AtomicU64));
+
+        impl ConstInit for Both {
+            const INIT: Self = Both(AtomicU64::new(0x0102_0304_0506_0708));
+        }
+
+        define_namespace!(
+            #[identity(crate)]
+            Crate
+        );
+
+        let a = Test::generic_static_init::<Bytes>();
+        assert!(a.0.iter().all(|byte| byte.load(Ordering::Relaxed) == 7));
+        a.0[10].store(8, Ordering::Relaxed);
+        assert_eq!(
+            Test::generic_static_init::<Bytes>().0[10].load(Ordering::Relaxed),
+            8
+        );
+
+        let b = Test::generic_static_init::<Both>();
+        assert_eq!(b.0.load(Ordering::Relaxed), 0x0102_0304_0506_0708);
+        let c = Test::generic_static::<Both>();
+        assert_eq!(c.0.load(Ordering::Relaxed), 0);
+        assert_ne!(b as *const _, c as *const _);
+
+        let d = Crate::generic_static_init::<Both>();
+        assert_eq!(d.0.load(Ordering::Relaxed), 0x0102_0304_0506_0708);
+        assert_ne!(b as *const _, d as *const _);
+    }
+
+    #[test]
+    fn uninit() {
+        let a = Test::generic_static_uninit::<AtomicU32>();
+        let b = Test::generic_static_uninit::<AtomicU32>();
+        assert_eq!(a as *const _, b as *const _);
+        assert_ne!(a.as_ptr(), Test::generic_static::<AtomicU32>() as *const _);
+    }
+
+    #[test]
+    fn lazy() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let a = Test::generic_lazy::<String>(|| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            "lazy".to_owned()
+        });
+        let b = Test::generic_lazy::<String>(|| unreachable!());
+        assert_eq!(a, "lazy");
+        assert_eq!(a as *const _, b as *const _);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+    }
+
+    #[cfg(feature = "async-lazy")]
+    #[test]
+    fn async_lazy() {
+        use std::{
+            future::{pending, Future},
+            pin::pin,
+            task::{Context, Poll, Wake, Waker},
+        };
+
+        struct Count(AtomicUsize);
+
+        impl Wake for Count {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let wakes = Arc::new(Count(AtomicUsize::new(0)));
+        let waker = Waker::from(wakes.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        // The first caller never completes, the second one waits for it.
+        let mut first = Box::pin(Test::generic_async_lazy::<String, _, _>(pending));
+        assert!(first.as_mut().poll(&mut cx).is_pending());
+        let mut second = pin!(Test::generic_async_lazy::<String, _, _>(|| async {
+            "async".to_owned()
+        }));
+        assert!(second.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(wakes.0.load(Ordering::Relaxed), 0);
+
+        // Cancelling the first caller wakes the second one, which then initializes the value.
+        drop(first);
+        assert_eq!(wakes.0.load(Ordering::Relaxed), 1);
+        let Poll::Ready(a) = second.as_mut().poll(&mut cx) else {
+            panic!("not initialized");
+        };
+        assert_eq!(a, "async");
+
+        let b = pin!(Test::generic_async_lazy::<String, _, _>(|| async {
+            unreachable!()
+        }))
+        .poll(&mut cx);
+        assert_eq!(b, Poll::Ready(a));
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn wipe() {
+        let a = Test::generic_static::<(AtomicU64, AtomicU32)>();
+        a.0.store(u64::MAX, Ordering::Relaxed);
+        a.1.store(u32::MAX, Ordering::Relaxed);
+
+        unsafe {
+            Test::zeroize_on_exit::<(AtomicU64, AtomicU32)>();
+            Test::zeroize_on_exit::<(AtomicU64, AtomicU32)>();
+            Test::wipe::<(AtomicU64, AtomicU32)>();
+        }
+        assert_eq!(a.0.load(Ordering::Relaxed), 0);
+        assert_eq!(a.1.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri doesn't support `mlock`")]
+    fn mlock() {
+        // Locking may be disallowed by the environment, but must not fail for zero-sized types.
+        let _ = Test::mlock::<AtomicU64>();
+        Test::mlock::<()>().unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri doesn't support `madvise`")]
+    fn advise_huge_pages() {
+        // Transparent huge pages may be disabled, but the advice must not fail for zero-sized
+        // types.
+        let _ = Test::advise_huge_pages::<[u64; 1 << 19]>();
+        Test::advise_huge_pages::<()>().unwrap();
+        #[cfg(target_os = "linux")]
+        if std::fs::read_to_string("/sys/kernel/mm/transparent_hugepage/enabled")
+            .is_ok_and(|enabled| !enabled.contains("[never]"))
+        {
+            Test::advise_huge_pages::<[u64; 1 << 19]>().unwrap();
+        }
+    }
+
+    #[test]
+    fn claim() {
+        let a = Test::claim::<[u64; 4]>().unwrap();
+        a[0] = 1;
+        assert!(Test::claim::<[u64; 4]>().is_none());
+        assert_eq!(Test::generic_static::<[u64; 4]>()[0], 0);
+    }
+
+    #[test]
+    fn once() {
+        let a = Test::generic_once::<Vec<u32>>();
+        assert!(a.get().is_none());
+        assert_eq!(a.set(vec![1, 2]), Ok(()));
+        assert_eq!(a.set(vec![3]), Err(vec![3]));
+        assert_eq!(a.get_or_init(Vec::new), &[1, 2]);
+        assert_eq!(a as *const _, Test::generic_once::<Vec<u32>>() as *const _);
+    }
+
+    #[test]
+    fn init_with() {
+        let a = Test::init_with(String::from("a")).unwrap();
+        assert_eq!(a, "a");
+        assert_eq!(Test::init_with(String::from("b")), Err(String::from("b")));
+        assert_eq!(Test::generic_once::<String>().get(), Some(a));
+    }
+
+    #[test]
+    fn singleton() {
+        let a = Test::singleton::<Vec<u8>>();
+        assert!(a.is_empty());
+        assert_eq!(a as *const _, Test::singleton::<Vec<u8>>() as *const _);
+    }
+
+    #[test]
+    fn not_sync() {
+        let a = unsafe { Test::generic_static_not_sync::<*const ()>() };
+        let b = unsafe { Test::generic_static_not_sync::<*const ()>() };
+        assert_eq!(a as *const _, b as *const _);
+        assert!(a.is_null());
+    }
+
+    #[test]
+    fn identity() {
+        define_namespace!(
+            #[unsafe(identity(process))]
+            Process
+        );
+        define_namespace!(
+            #[identity(crate)]
+            Crate
+        );
+
+        assert_eq!(Test::IDENTITY, Identity::Crate);
+        assert_eq!(Process::IDENTITY, Identity::Process);
+        assert_eq!(Crate::IDENTITY, Identity::Crate);
+
+        let a = Crate::generic_static::<AtomicUsize>();
+        let b = Crate::generic_static::<AtomicUsize>();
+        assert_eq!(a as *const _, b as *const _);
+        assert_ne!(
+            a as *const _,
+            Process::generic_static::<AtomicUsize>() as *const _
+        );
+        assert_ne!(
+            a as *const _ as *const (),
+            Crate::generic_static::<AtomicIsize>() as *const _ as *const ()
+        );
+    }
+
+    #[test]
+    fn salt() {
+        define_namespace!(
+            #[unsalted]
+            Unsalted
+        );
+
+        assert_ne!(Test::SALT, 0);
+        assert_eq!(Unsalted::SALT, 0);
+
+        let a = Test::generic_static::<AtomicUsize>() as *const _;
+        let b = Unsalted::generic_static::<AtomicUsize>() as *const _;
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    #[cfg(all(feature = "check-duplicates", debug_assertions))]
+    #[should_panic(expected = "resolved to multiple addresses")]
+    fn check_duplicates() {
+        define_namespace!(Duplicates);
+
+        let a = Duplicates::generic_static::<AtomicUsize>();
+        let b = Duplicates::generic_static::<AtomicUsize>();
+        assert_eq!(a as *const _, b as *const _);
+
+        let duplicate = Box::leak(Box::new(AtomicUsize::new(0)));
+        crate::registry::check_unique_uncached::<Duplicates, AtomicUsize>(duplicate.into());
+    }
+
+    #[test]
+    #[cfg(all(feature = "introspection", not(any(feature = "loom", miri))))]
+    fn introspection() {
+        define_namespace!(Described);
+        define_namespace!(
+            #[identity(crate)]
+            DescribedPerCrate
+        );
+
+        let a = Described::generic_static::<AtomicU64>();
+        let b = DescribedPerCrate::generic_static::<[u16; 3]>();
+
+        let described = Described::iter_statics().collect::<Vec<_>>();
+        assert_eq!(described.len(), 1);
+        assert_eq!(described[0].addr(), a as *const _ as *const ());
+        assert_eq!(described[0].size(), 8);
+        assert_eq!(described[0].align(), 8);
+        assert!(described[0].type_name().contains("u64"));
+        assert_eq!(described[0].namespace(), Described::info());
+
+        let described = DescribedPerCrate::iter_statics().collect::<Vec<_>>();
+        assert_eq!(described.len(), 1);
+        assert_eq!(described[0].addr(), b as *const _ as *const ());
+        assert_eq!(described[0].size(), 6);
+        assert_eq!(described[0].type_name(), "[u16; 3]");
+    }
+
+    #[test]
+    #[cfg(all(feature = "dump", not(any(feature = "loom", miri))))]
+    fn dump() {
+        define_namespace!(Dumped);
+
+        struct Opaque(#[allow(dead_code)] u32);
+        unsafe impl crate::Zeroable for Opaque {}
+
+        Dumped::generic_static::<AtomicU64>().store(7, Ordering::Relaxed);
+        let _ = Dumped::generic_static::<Opaque>();
+        #[derive(Debug)]
+        struct Limit(#[allow(dead_code)] u32);
+        impl ConstInit for Limit {
+            const INIT: Self = Limit(5);
+        }
+        let _ = Dumped::generic_static_init::<Limit>();
+        let _ = unsafe { Dumped::generic_static_not_sync::<std::cell::Cell<u16>>() };
+
+        let mut out = Vec::new();
+        unsafe { Dumped::dump(&mut out) }.unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert!(lines.iter().any(|line| line.ends_with("u16>: <2 bytes>")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("u64") && line.ends_with(": 7")));
+        assert!(lines.iter().any(|line| line.ends_with("Opaque: <4 bytes>")));
+        assert!(lines.iter().any(|line| line.ends_with("Limit>: Limit(5)")));
+    }
+
+    #[test]
+    #[cfg(all(feature = "snapshot", not(any(feature = "loom", miri))))]
+    fn snapshot() {
+        define_namespace!(Snapshotted);
+
+        let counter = Snapshotted::generic_static::<AtomicU64>();
+        let values = Snapshotted::generic_static::<GenericMutex<[u16; 3]>>();
+        counter.store(1, Ordering::Relaxed);
+        *values.lock() = [1, 2, 3];
+
+        // Statics are described even before their first access.
+        let snapshot = unsafe { Snapshotted::snapshot() };
+        assert_eq!(snapshot.statics().count(), 3);
+
+        counter.store(2, Ordering::Relaxed);
+        *values.lock() = [4, 5, 6];
+        let _ = Snapshotted::generic_static::<AtomicU32>().fetch_add(1, Ordering::Relaxed);
+
+        unsafe { snapshot.restore() };
+        assert_eq!(counter.load(Ordering::Relaxed), 1);
+        assert_eq!(*values.lock(), [1, 2, 3]);
+        assert_eq!(
+            Snapshotted::generic_static::<AtomicU32>().load(Ordering::Relaxed),
+            0
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "reset", not(any(feature = "loom", miri))))]
+    fn reset_all() {
+        define_namespace!(Reset);
+
+        struct Limit(AtomicU32);
+        impl ConstInit for Limit {
+            const INIT: Self = Limit(AtomicU32::new(5));
+        }
+
+        let counter = Reset::generic_static::<AtomicU64>();
+        let limit = &Reset::generic_static_init::<Limit>().0;
+        counter.store(1, Ordering::Relaxed);
+        limit.store(6, Ordering::Relaxed);
+
+        unsafe { Reset::reset_all() };
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+        assert_eq!(limit.load(Ordering::Relaxed), 5);
+
+        counter.store(1, Ordering::Relaxed);
+        let result = std::panic::catch_unwind(|| unsafe {
+            Reset::with_fresh_statics(|| {
+                assert_eq!(counter.swap(2, Ordering::Relaxed), 0);
+                panic!();
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    #[cfg(all(feature = "introspection", not(any(feature = "loom", miri))))]
+    fn stats() {
+        define_namespace!(Counted);
+
+        let _ = Counted::generic_static::<[u8; 3]>();
+        let _ = Counted::generic_static::<()>();
+
+        let statics = super::statics();
+        let stats = super::stats();
+        assert_eq!(stats.count(), statics.len());
+        assert_eq!(
+            stats.size(),
+            statics.iter().map(|s| s.size().max(1)).sum::<usize>()
+        );
+        assert!(stats.size() >= 4);
+    }
+
+    #[test]
+    #[cfg(all(feature = "storage-range", not(any(feature = "loom", miri))))]
+    fn storage_range() {
+        define_namespace!(Ranged);
+        define_namespace!(
+            #[identity(crate)]
+            RangedPerCrate
+        );
+        define_namespace!(Empty);
+
+        let a = Ranged::generic_static::<[u64; 4]>() as *const _ as *const u8;
+        let b = Ranged::generic_static::<[u8; 3]>() as *const _ as *const u8;
+        let c = RangedPerCrate::generic_static::<u32>() as *const _ as *const u8;
+
+        let range = Ranged::storage_range();
+        assert!(range.contains(&a));
+        assert!(range.contains(&b));
+        assert!(!range.contains(&c));
+        assert!(unsafe { range.end.offset_from(range.start) } >= 35);
+        assert!(RangedPerCrate::storage_range().contains(&c));
+        assert!(Empty::storage_range().is_empty());
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", not(any(feature = "loom", miri))))]
+    fn custom_section() {
+        define_namespace!(Zeroed in "gs_custom_zeroed");
+        define_namespace!(Initialized<K> in "gs_custom_init" under Zeroed);
+
+        extern "C" {
+            static __start_gs_custom_zeroed: u8;
+            static __stop_gs_custom_zeroed: u8;
+            static __start_gs_custom_init: u8;
+            static __stop_gs_custom_init: u8;
+        }
+        let zeroed = std::ptr::addr_of!(__start_gs_custom_zeroed)
+            ..std::ptr::addr_of!(__stop_gs_custom_zeroed);
+        let initialized =
+            std::ptr::addr_of!(__start_gs_custom_init)..std::ptr::addr_of!(__stop_gs_custom_init);
+
+        struct Limit(u32);
+        impl ConstInit for Limit {
+            const INIT: Self = Limit(5);
+        }
+
+        let a = Zeroed::generic_static::<[u64; 4]>() as *const _ as *const u8;
+        let b = Initialized::<u8>::generic_static_init::<Limit>();
+        assert!(zeroed.contains(&a));
+        assert!(initialized.contains(&(b as *const _ as *const u8)));
+        assert_eq!(b.0, 5);
+
+        assert_eq!(Zeroed::SECTION, Some("gs_custom_zeroed"));
+        assert!(Initialized::<u8>::is_within::<Zeroed>());
+        assert_eq!(crate::Global::SECTION, None);
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", not(any(feature = "loom", miri))))]
+    fn generic_static_in() {
+        define_namespace!(Test);
+        crate::define_section!(Backup in "gs_backup_test");
+
+        extern "C" {
+            static __start_gs_backup_test: u8;
+            static __stop_gs_backup_test: u8;
+        }
+        let section =
+            std::ptr::addr_of!(__start_gs_backup_test)..std::ptr::addr_of!(__stop_gs_backup_test);
+
+        let a = Test::generic_static_in::<AtomicU64, Backup>();
+        let b = crate::Global::generic_static_in::<AtomicU64, Backup>();
+        assert!(section.contains(&(a as *const _ as *const u8)));
+        assert!(section.contains(&(b as *const _ as *const u8)));
+        assert_ne!(a as *const _, b as *const _);
+        assert_ne!(
+            a as *const _,
+            Test::generic_static::<AtomicU64>() as *const _
+        );
+        assert_eq!(
+            a as *const _,
+            Test::generic_static_in::<AtomicU64, Backup>() as *const _
+        );
+        assert_eq!(<Backup as crate::SectionName>::SECTION, "gs_backup_test");
+    }
+
+    #[test]
+    fn generic_static_padded() {
+        use crate::CacheAligned;
+
+        let a = (Test::generic_static_padded::<AtomicU64>() as *const AtomicU64).addr();
+        let b = (Test::generic_static_padded::<AtomicU32>() as *const AtomicU32).addr();
+        let align = CacheAligned::<AtomicU64>::ALIGN;
+        assert_eq!(a % align, 0);
+        assert_eq!(b % align, 0);
+        assert!(a.abs_diff(b) >= align);
+        assert_ne!(
+            a,
+            (Test::generic_static::<AtomicU64>() as *const AtomicU64).addr()
+        );
+        assert_eq!(std::mem::size_of::<CacheAligned<u8>>(), align);
+    }
+
+    #[test]
+    fn generic_static_aligned() {
+        let a = Test::generic_static_aligned::<[u8; 3], 64>();
+        let b = Test::generic_static_aligned::<[u8; 3], 128>();
+        let c = Test::generic_static_aligned::<u64, 1>();
+        assert_eq!(a.as_ptr().addr() % 64, 0);
+        assert_eq!(b.as_ptr().addr() % 128, 0);
+        assert_eq!((c as *const u64).addr() % std::mem::align_of::<u64>(), 0);
+        assert_ne!(a.as_ptr(), b.as_ptr());
+        assert_ne!(a.as_ptr(), Test::generic_static::<[u8; 3]>().as_ptr());
+        assert_eq!(std::mem::size_of::<crate::Aligned<[u8; 3], 64>>(), 64);
+    }
+
+    #[test]
+    fn generic_static_page_aligned() {
+        use crate::MAX_PAGE_SIZE;
+
+        let a = Test::generic_static_page_aligned::<[u8; 10]>();
+        let b = Test::generic_static_page_aligned::<[u64; 1024]>();
+        assert_eq!(a.as_ptr().addr() % MAX_PAGE_SIZE, 0);
+        assert_eq!(b.as_ptr().addr() % MAX_PAGE_SIZE, 0);
+        assert!(a.as_ptr().addr().abs_diff(b.as_ptr().addr()) >= MAX_PAGE_SIZE);
+        assert_eq!(
+            std::mem::size_of::<crate::Aligned<[u64; 1024], MAX_PAGE_SIZE>>() % MAX_PAGE_SIZE,
+            0
+        );
+    }
+
+    #[test]
+    #[cfg(not(any(feature = "loom", miri)))]
+    fn export_generic_static() {
+        define_namespace!(
+            #[unsafe(identity(process))]
+            Exports
+        );
+
+        struct Exported(AtomicU64);
+        unsafe impl crate::Zeroable for Exported {}
+
+        crate::export_generic_static!(Exports: Exported as "generic_statics_test_export");
+
+        extern "C" {
+            static generic_statics_test_export: AtomicU64;
+        }
+
+        let exported = unsafe { &generic_statics_test_export };
+        exported.store(7, Ordering::Relaxed);
+        let value = Exports::generic_static::<Exported>();
+        assert_eq!(
+            value as *const _ as *const (),
+            exported as *const _ as *const ()
+        );
+        assert_eq!(value.0.load(Ordering::Relaxed), 7);
+    }
+
+    #[test]
+    #[cfg(all(feature = "touched-statics", not(feature = "loom")))]
+    fn touched_statics() {
+        define_namespace!(Touched);
+
+        assert!(!super::touched_statics().any(|s| s.namespace() == Touched::info()));
+
+        let a = Touched::generic_static::<[u32; 2]>();
+        let _ = Touched::generic_static::<[u32; 2]>();
+        let touched = super::touched_statics()
+            .filter(|s| s.namespace() == Touched::info())
+            .collect::<Vec<_>>();
+        assert_eq!(touched.len(), 1);
+        assert_eq!(touched[0].addr(), a as *const _ as *const ());
+        assert_eq!(touched[0].type_name(), "[u32; 2]");
+    }
+
+    #[test]
+    #[cfg(all(feature = "metrics", not(feature = "loom")))]
+    fn metrics() {
+        use crate::{gather, CounterMetric, GaugeMetric, HistogramMetric, MetricValue};
+
+        define_namespace!(Measured);
+
+        struct Requests;
+        struct Latency;
+
+        Measured::metric::<CounterMetric, Requests>().add(2);
+        Measured::metric::<GaugeMetric, Requests>().sub(3);
+        let latency = Measured::metric::<HistogramMetric, Latency>();
+        for sample in [0, 1, 5, 7, 1000] {
+            latency.record(sample);
+        }
+
+        let samples = gather()
+            .into_iter()
+            .filter(|sample| sample.namespace() == Measured::info())
+            .collect::<Vec<_>>();
+        assert_eq!(samples.len(), 3);
+        assert!(samples
+            .iter()
+            .any(|s| s.name().ends_with("Requests") && s.value() == &MetricValue::Counter(2)));
+        assert!(samples
+            .iter()
+            .any(|s| s.name().ends_with("Requests") && s.value() == &MetricValue::Gauge(-3)));
+
+        let MetricValue::Histogram {
+            count,
+            sum,
+            buckets,
+        } = samples
+            .iter()
+            .find(|s| s.name().ends_with("Latency"))
+            .unwrap()
+            .value()
+        else {
+            panic!("not a histogram");
+        };
+        assert_eq!((*count, *sum), (5, 1013));
+        assert_eq!(buckets[..4], [1, 1, 0, 2]);
+        assert_eq!(buckets[10], 1);
+    }
+
+    #[test]
+    #[cfg(all(feature = "touched-statics", not(feature = "loom")))]
+    fn first_access_hook() {
+        define_namespace!(Hooked);
+
+        static FIRST_ACCESSES: AtomicUsize = AtomicUsize::new(0);
+        fn hook(descriptor: &'static crate::StaticDescriptor) {
+            assert_eq!(descriptor.namespace(), Hooked::info());
+            FIRST_ACCESSES.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let _ = Hooked::generic_static::<[u8; 1]>();
+        Hooked::set_first_access_hook(hook);
+        assert_eq!(FIRST_ACCESSES.load(Ordering::Relaxed), 0);
+
+        let _ = Hooked::generic_static::<[u8; 2]>();
+        let _ = Hooked::generic_static::<[u8; 2]>();
+        let _ = Hooked::generic_static::<[u8; 3]>();
+        assert_eq!(FIRST_ACCESSES.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    #[cfg(all(feature = "canaries", not(any(feature = "loom", miri))))]
+    fn canaries() {
+        define_namespace!(Canaries);
+
+        struct Words([u16; 3]);
+        impl ConstInit for Words {
+            const INIT: Self = Words([1, 2, 3]);
+        }
+
+        let a = Canaries::generic_static::<[AtomicU32; 3]>();
+        let b = Canaries::generic_static_init::<Words>();
+        let _ = Canaries::generic_static::<u8>();
+        assert!(crate::check_canaries().is_empty());
+
+        // Overwrites the canary after `a` (which is behind its 12 bytes).
+        let canary = unsafe { (a as *const _ as *mut u8).add(12) };
+        let value = unsafe { canary.read() };
+        unsafe { canary.write(!value) };
+        let corrupted = crate::check_canaries();
+        unsafe { canary.write(value) };
+
+        assert_eq!(corrupted.len(), 1);
+        assert_eq!(corrupted[0].addr(), a as *const _ as *const ());
+        assert_eq!(corrupted[0].offset(), 12);
+        assert!(crate::check_canaries().is_empty());
+        assert_eq!(b.0, [1, 2, 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "verify-at-startup")]
+    fn verify_at_startup() {
+        define_namespace!(
+            #[unsafe(identity(process))]
+            Verified
+        );
+
+        let a = Verified::generic_static::<AtomicU64>() as *const _ as *const u8;
+        assert!(crate::verify::entries()
+            .iter()
+            .any(|entry| entry.key != 0 && entry.addr == a));
+    }
+
+    instantiate_generic_statics!(Test: [Option<&'static u128>, [AtomicU8; 3],]);
+
+    #[test]
+    fn instantiate() {
+        assert!(Test::generic_static::<Option<&'static u128>>().is_none());
+        assert_eq!(Test::generic_static::<[AtomicU8; 3]>().len(), 3);
+    }
+
+    #[test]
+    fn zero_sized() {
+        let a = Test::generic_static::<PhantomData<usize>>() as *const _;
+        assert_eq!(a, NonNull::dangling().as_ptr());
+
+        let b = Test::generic_static::<[u64; 0]>() as *const _;
+        assert_eq!(b, NonNull::dangling().as_ptr());
+    }
+
+    #[test]
+    fn mutation() {
+        let a = Test::generic_static::<AtomicUsize>();
+        assert_eq!(a.load(Ordering::Relaxed), 0);
+        a.store(42, Ordering::Relaxed);
+
+        let b = Test::generic_static::<AtomicUsize>();
+        assert_eq!(b.load(Ordering::Relaxed), 42);
+
+        let a2 = Test::generic_static::<AtomicIsize>();
+        assert_eq!(a2.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn cell() {
+        let a = Test::generic_static_cell::<Option<Rc<u32>>>();
+        unsafe { *a.get() = Some(Rc::new(1)) };
+        assert_eq!(unsafe { (*a.get()).as_deref() }, Some(&1));
+        assert_eq!(
+            a as *const _,
+            Test::generic_static_cell::<Option<Rc<u32>>>() as *const _
+        );
+        unsafe { *a.get() = None };
+    }
+
+    #[test]
+    fn ptr() {
+        let a = Test::generic_static_ptr::<Cell<u16>>();
+        unsafe { a.as_ptr().write(Cell::new(7)) };
+        assert_eq!(
+            unsafe { Test::generic_static_not_sync::<Cell<u16>>() }.get(),
+            7
+        );
+        assert_eq!(a, Test::generic_static_ptr::<Cell<u16>>());
+    }
+
+    #[test]
+    fn pin() {
+        let a = Test::generic_static_pin::<(PhantomPinned, AtomicU32)>();
+        assert_eq!(
+            &*a as *const _,
+            Test::generic_static::<(PhantomPinned, AtomicU32)>() as *const _
+        );
+    }
+
+    #[test]
+    fn static_ref() {
+        let a = Test::generic_static_ref::<AtomicU32>();
+        let b = Test::generic_static_ref::<AtomicU32>();
+        assert_eq!(a, b);
+        assert_eq!(
+            a.get() as *const _,
+            Test::generic_static::<AtomicU32>() as *const _
+        );
+        a.store(3, Ordering::Relaxed);
+        assert_eq!(b.load(Ordering::Relaxed), 3);
+
+        let debug = format!("{a:?}");
+        assert!(debug.contains("Test") && debug.contains("u32"), "{debug}");
+    }
+
+    #[test]
+    fn atomic() {
+        let a = Test::atomic::<[u8; 2]>();
+        assert_eq!(a.load(Ordering::Relaxed), [0, 0]);
+        a.store([1, 2], Ordering::Relaxed);
+        assert_eq!(a.swap([3, 4], Ordering::Relaxed), [1, 2]);
+        assert_eq!(
+            a.compare_exchange([0, 0], [5, 6], Ordering::Relaxed, Ordering::Relaxed),
+            Err([3, 4])
+        );
+        assert_eq!(
+            a.compare_exchange([3, 4], [5, 6], Ordering::Relaxed, Ordering::Relaxed),
+            Ok([3, 4])
+        );
+        assert_eq!(a.load(Ordering::Relaxed), [5, 6]);
+
+        let b = Test::atomic::<f64>();
+        b.store(1.5, Ordering::Relaxed);
+        assert_eq!(b.load(Ordering::Relaxed), 1.5);
+        assert_eq!(format!("{b:?}"), "1.5");
+    }
+
+    #[test]
+    fn counter() {
+        struct A;
+        struct B;
+
+        assert_eq!(Test::counter::<A>().fetch_add(1, Ordering::Relaxed), 0);
+        assert_eq!(Test::counter::<A>().fetch_add(1, Ordering::Relaxed), 1);
+        assert_eq!(Test::counter::<B>().load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn once_per_tag() {
+        struct A;
+        struct B;
+
+        let calls = AtomicU32::new(0);
+        let threads = (0..4)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    Test::once::<A>().call_once(|| {
+                        std::thread::sleep(std::time::Duration::from_millis(10));
+                        Test::counter::<A>().fetch_add(1, Ordering::Relaxed);
+                    });
+                    assert!(Test::once::<A>().is_completed());
+                })
+            })
+            .collect::<Vec<_>>();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+        assert_eq!(Test::counter::<A>().load(Ordering::Relaxed), 1);
+
+        assert!(std::panic::catch_unwind(|| Test::once::<B>().call_once(|| panic!())).is_err());
+        assert!(!Test::once::<B>().is_completed());
+        Test::once::<B>().call_once(|| {
+            calls.fetch_add(1, Ordering::Relaxed);
+        });
+        Test::once::<B>().call_once(|| {
+            calls.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn keyed() {
+        struct A;
+        struct B;
+
+        let a = Test::generic_static_keyed::<AtomicU32, A>();
+        let b = Test::generic_static_keyed::<AtomicU32, B>();
+        a.store(1, Ordering::Relaxed);
+        assert_eq!(b.load(Ordering::Relaxed), 0);
+        assert_eq!(
+            a as *const _,
+            Test::generic_static_keyed::<AtomicU32, A>() as *const _
+        );
+        assert_ne!(
+            a as *const _,
+            Test::generic_static::<AtomicU32>() as *const _
+        );
+    }
+
+    #[test]
+    fn indexed() {
+        let a = Test::generic_static_indexed::<AtomicU32, 2>(0);
+        let b = Test::generic_static_indexed::<AtomicU32, 2>(1);
+        a.store(1, Ordering::Relaxed);
+        assert_eq!(b.load(Ordering::Relaxed), 0);
+        assert_eq!(
+            a as *const _,
+            Test::generic_static_indexed::<AtomicU32, 2>(0) as *const _
+        );
+        assert_ne!(
+            a as *const _,
+            Test::generic_static_indexed::<AtomicU32, 3>(0) as *const _
+        );
+        assert!(std::panic::catch_unwind(|| {
+            let _ = Test::generic_static_indexed::<AtomicU32, 2>(2);
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn named() {
+        let a = generic_static_named!(Test, AtomicU32, "a");
+        let b = generic_static_named!(Test, AtomicU32, "b");
+        a.store(1, Ordering::Relaxed);
+        assert_eq!(b.load(Ordering::Relaxed), 0);
+        assert_eq!(
+            a as *const _,
+            generic_static_named!(Test, AtomicU32, "a") as *const _
+        );
+    }
+
+    #[test]
+    fn id() {
+        let a = Test::generic_static_id::<AtomicU32, 0>();
+        let b = Test::generic_static_id::<AtomicU32, 1>();
+        a.store(1, Ordering::Relaxed);
+        assert_eq!(b.load(Ordering::Relaxed), 0);
+        assert_eq!(
+            a as *const _,
+            Test::generic_static_id::<AtomicU32, 0>() as *const _
+        );
+    }
+
+    #[test]
+    fn mutex() {
+        let mutex = Test::generic_static::<GenericMutex<(u64, u64)>>();
+        let threads = (0..4)
+            .map(|_| {
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        let mut guard = mutex.lock();
+                        guard.0 += 1;
+                        guard.1 += 1;
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+
+        let guard = mutex.lock();
+        assert_eq!(*guard, (4000, 4000));
+        assert!(mutex.try_lock().is_none());
+        drop(guard);
+        assert!(mutex.try_lock().is_some());
+    }
+
+    #[test]
+    fn rwlock() {
+        let lock = Test::generic_static::<GenericRwLock<(u64, u64)>>();
+        let threads = (0..4)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        if i == 0 {
+                            let mut guard = lock.write();
+                            guard.0 += 1;
+                            guard.1 += 1;
+                        } else {
+                            let guard = lock.read();
+                            assert_eq!(guard.0, guard.1);
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+
+        let a = lock.read();
+        let b = lock.read();
+        assert_eq!(*a, (1000, 1000));
+        assert_eq!(*a, *b);
+        assert!(lock.try_write().is_none());
+        drop((a, b));
+        let guard = lock.write();
+        assert!(lock.try_read().is_none());
+        drop(guard);
+        assert!(lock.try_write().is_some());
+    }
+
+    #[test]
+    fn seqlock() {
+        let lock = Test::generic_static::<GenericSeqLock<[u64; 3]>>();
+        let threads = (0..4)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        if i == 0 {
+                            lock.update(|[a, b, c]| {
+                                *a += 1;
+                                *b += 1;
+                                *c += 1;
+                            });
+                        } else {
+                            let [a, b, c] = lock.read();
+                            assert!(a == b && b == c);
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+
+        assert_eq!(lock.read(), [1000; 3]);
+        lock.write([1, 2, 3]);
+        assert_eq!(lock.read(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn strict() {
+        define_namespace!(Strict);
+
+        let a = Strict::generic_static_strict::<AtomicU32>();
+        let b = Strict::generic_static_strict::<AtomicU32>();
+        assert_eq!(a as *const _, b as *const _);
+        assert_eq!(
+            a as *const _,
+            Strict::generic_static::<AtomicU32>() as *const _
+        );
+
+        a.store(42, Ordering::Relaxed);
+        assert_eq!(b.load(Ordering::Relaxed), 42);
+
+        let c = Strict::generic_static_strict::<AtomicU64>();
+        assert_ne!(a as *const _ as *const (), c as *const _ as *const ());
+    }
+
+    #[test]
+    fn cached() {
+        define_namespace!(Cached);
+
+        let a = Cached::generic_static_cached::<AtomicU32>();
+        let b = Cached::generic_static_cached::<AtomicU32>();
+        assert_eq!(a as *const _, b as *const _);
+        assert_eq!(
+            a as *const _,
+            Cached::generic_static::<AtomicU32>() as *const _
+        );
+
+        a.store(42, Ordering::Relaxed);
+        assert_eq!(b.load(Ordering::Relaxed), 42);
+
+        let addr = (a as *const AtomicU32).addr();
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    (Cached::generic_static_cached::<AtomicU32>() as *const AtomicU32).addr()
+                })
+            })
+            .collect();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), addr);
+        }
+
+        let _: &'static () = Cached::generic_static_cached::<()>();
+    }
+
+    #[test]
+    fn generic_statics_of() {
+        define_namespace!(Batch);
+
+        let (a, b, c) = Batch::generic_statics_of::<(AtomicU32, AtomicU64, ())>();
+        assert_eq!(
+            a as *const _,
+            Batch::generic_static::<AtomicU32>() as *const _
+        );
+        assert_eq!(
+            b as *const _,
+            Batch::generic_static::<AtomicU64>() as *const _
+        );
+        let _: &'static () = c;
+
+        let (d,) = Batch::generic_statics_of::<(AtomicU32,)>();
+        assert_eq!(a as *const _, d as *const _);
+    }
+
+    #[cfg(feature = "shared-resolver")]
+    #[test]
+    fn shared_resolver() {
+        use super::resolver::{resolve, Resolver};
+
+        define_namespace!(Shared);
+
+        let a = Shared::generic_static::<AtomicU32>();
+        let resolver = const { &Resolver::of::<Shared, AtomicU32>() };
+        assert_eq!(
+            resolve(resolver).cast::<AtomicU32>().as_ptr().cast_const(),
+            a as *const _
+        );
+        assert_eq!(
+            Shared::generic_static_ptr::<AtomicU32>()
+                .as_ptr()
+                .cast_const(),
+            a as *const _
+        );
+        assert_ne!(
+            a as *const _ as *const (),
+            Shared::generic_static::<AtomicU64>() as *const _ as *const ()
+        );
+
+        a.store(42, Ordering::Relaxed);
+        assert_eq!(
+            Shared::generic_static_cached::<AtomicU32>().load(Ordering::Relaxed),
+            42
+        );
+    }
+
+    #[test]
+    fn type_map() {
+        define_namespace!(Maps);
+
+        let mut a = super::TypeMap::<Maps>::with_namespace();
+        let mut b = super::TypeMap::<Maps>::default();
+        assert!(a.is_empty());
+
+        assert_eq!(a.insert(1u32), None);
+        assert_eq!(a.insert(String::from("a")), None);
+        assert_eq!(b.insert(2u32), None);
+        assert_eq!(a.len(), 2);
+
+        assert_eq!(a.get::<u32>(), Some(&1));
+        assert_eq!(b.get::<u32>(), Some(&2));
+        assert_eq!(b.get::<String>(), None);
+        assert!(!b.contains::<u64>());
+
+        a.get_mut::<String>().unwrap().push('b');
+        assert_eq!(a.get_or_insert_with(String::new), "ab");
+        *b.get_or_insert_with(|| 5u64) += 1;
+        assert_eq!(b.get::<u64>(), Some(&6));
+
+        assert_eq!(a.insert(3u32), Some(1));
+        assert_eq!(a.remove::<u32>(), Some(3));
+        assert_eq!(a.remove::<u32>(), None);
+        assert_eq!(a.len(), 1);
+
+        b.clear();
+        assert!(b.is_empty());
+        assert_eq!(b.get::<u64>(), None);
+    }
+
+    #[test]
+    fn type_index() {
+        define_namespace!(A);
+        define_namespace!(B);
+
+        assert_eq!(A::type_count(), 0);
+        assert_eq!(A::type_index::<u8>(), 0);
+        assert_eq!(A::type_index::<u16>(), 1);
+        assert_eq!(A::type_index::<u8>(), 0);
+        assert_eq!(B::type_index::<u16>(), 0);
+        assert_eq!(A::type_count(), 2);
+        assert_eq!(B::type_count(), 1);
+
+        let indices = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| (A::type_index::<u32>(), A::type_index::<u64>())))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert!(indices.iter().all(|&index| index == indices[0]));
+        assert_eq!(A::type_count(), 4);
+    }
+
+    #[test]
+    fn components() {
+        define_namespace!(World);
+
+        struct Droppy(#[allow(dead_code)] String);
+
+        assert_eq!(World::components().count(), 0);
+        assert!(!World::component::<u64>().is_registered());
+        assert!(World::register_component::<u64>());
+        assert!(World::register_component::<Droppy>());
+        assert!(!World::register_component::<u64>());
+
+        let names: Vec<_> = World::components().map(|meta| meta.type_name()).collect();
+        assert_eq!(names, [std::any::type_name::<Droppy>(), "u64"]);
+
+        let meta = World::component::<Droppy>();
+        assert!(meta.is_registered());
+        assert_eq!(meta.layout(), std::alloc::Layout::new::<Droppy>());
+        assert_eq!(meta.type_id(), std::any::TypeId::of::<Droppy>());
+        assert_eq!(meta.index(), World::type_index::<Droppy>());
+        assert!(!World::component::<u64>().needs_drop());
+
+        let mut value = std::mem::ManuallyDrop::new(Droppy(String::from("dropped")));
+        unsafe { meta.drop_in_place((&mut *value as *mut Droppy).cast()) };
+    }
+
+    #[test]
+    fn list() {
+        use super::{GenericListHead, ListNode};
+
+        define_namespace!(Lists);
+
+        static A: ListNode<u32> = ListNode::new(1);
+        static B: ListNode<u32> = ListNode::new(2);
+
+        let list = Lists::generic_static::<GenericListHead<u32>>();
+        let other = GenericListHead::new();
+        assert!(list.is_empty());
+
+        let pushed = std::thread::scope(|scope| {
+            let a = scope.spawn(|| list.push(&A));
+            let b = scope.spawn(|| list.push(&B));
+            [a.join().unwrap(), b.join().unwrap()]
+        });
+        assert_eq!(pushed, [true, true]);
+        assert!(A.is_linked());
+        assert!(!other.push(&A));
+        assert!(other.is_empty());
+
+        let mut values = Vec::new();
+        for value in Lists::generic_static::<GenericListHead<u32>>() {
+            values.push(*value);
+        }
+        values.sort_unstable();
+        assert_eq!(values, [1, 2]);
+    }
+
+    #[test]
+    fn pool() {
+        use super::GenericPool;
+
+        define_namespace!(Pools);
+
+        struct Tracked(u32, &'static AtomicUsize);
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                self.1.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        let pool = Pools::generic_static::<GenericPool<Tracked, 4>>();
+        assert_eq!(GenericPool::<Tracked, 4>::CAPACITY, 4);
+
+        let boxes = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|i| scope.spawn(move || pool.alloc(Tracked(i, &DROPS)).ok().unwrap()))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        let mut indices: Vec<_> = boxes.iter().map(|value| value.index()).collect();
+        indices.sort_unstable();
+        assert_eq!(indices, [0, 1, 2, 3]);
+        assert_eq!(pool.len(), 4);
+
+        let rejected = pool.alloc(Tracked(4, &DROPS)).err().unwrap();
+        assert_eq!(rejected.0, 4);
+        drop(rejected);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+
+        let mut boxes = boxes.into_iter();
+        let mut first = boxes.next().unwrap();
+        first.0 += 10;
+        let freed = first.free();
+        assert!(freed.0 >= 10);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+        drop(freed);
+
+        drop(boxes);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 5);
+        assert!(pool.is_empty());
+
+        let local = GenericPool::<u8, 1>::new();
+        assert_eq!(*local.alloc(1).unwrap(), 1);
+    }
+
+    #[test]
+    fn queue() {
+        use super::GenericQueue;
+
+        define_namespace!(Queues);
+
+        let queue = Queues::generic_static::<GenericQueue<(usize, usize), 8>>();
+        assert!(queue.is_empty());
+
+        let mut popped = Vec::new();
+        std::thread::scope(|scope| {
+            for producer in 0..4 {
+                scope.spawn(move || {
+                    for i in 0..1000 {
+                        while queue.push((producer, i)).is_err() {
+                            std::hint::spin_loop();
+                        }
+                    }
+                });
+            }
+
+            while popped.len() < 4000 {
+                match queue.pop() {
+                    Some(value) => popped.push(value),
+                    None => std::hint::spin_loop(),
+                }
+            }
+        });
+        assert_eq!(queue.pop(), None);
+
+        // Values of every producer are popped in the order they were pushed.
+        for producer in 0..4 {
+            let values: Vec<_> = popped
+                .iter()
+                .filter(|value| value.0 == producer)
+                .map(|value| value.1)
+                .collect();
+            assert_eq!(values, (0..1000).collect::<Vec<_>>());
+        }
+
+        let local = GenericQueue::<u8, 2>::new();
+        assert_eq!(local.push(1), Ok(()));
+        assert_eq!(local.push(2), Ok(()));
+        assert_eq!(local.push(3), Err(3));
+        assert_eq!(local.len(), 2);
+        assert_eq!(local.pop(), Some(1));
+        assert_eq!(local.push(3), Ok(()));
+        assert_eq!(local.pop(), Some(2));
+        assert_eq!(local.pop(), Some(3));
+        assert_eq!(local.pop(), None);
+    }
+
+    #[test]
+    fn ring() {
+        use super::GenericRing;
+
+        define_namespace!(Rings);
+
+        let ring = Rings::generic_static::<GenericRing<u32, 4>>();
+        let mut consumer = ring.consumer().unwrap();
+        assert!(ring.consumer().is_none());
+
+        let mut popped = Vec::new();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let mut producer = ring.producer().unwrap();
+                for i in 0..1000 {
+                    while producer.push(i).is_err() {
+                        std::hint::spin_loop();
+                    }
+                }
+            });
+
+            while popped.len() < 1000 {
+                match consumer.pop() {
+                    Some(value) => popped.push(value),
+                    None => std::hint::spin_loop(),
+                }
+            }
+        });
+        assert_eq!(popped, (0..1000).collect::<Vec<_>>());
+
+        let mut producer = ring.producer().unwrap();
+        for i in 0..4 {
+            assert_eq!(producer.push(i), Ok(()));
+        }
+        assert_eq!(producer.push(4), Err(4));
+        assert_eq!(ring.len(), 4);
+        assert_eq!(consumer.by_ref().collect::<Vec<_>>(), [0, 1, 2, 3]);
+        assert!(ring.is_empty());
+
+        drop(consumer);
+        assert!(ring.consumer().is_some());
+    }
+
+    #[test]
+    fn hook() {
+        define_namespace!(Hooks);
+
+        struct A;
+        struct B;
+
+        fn add((a, b): (u32, u32)) -> u32 {
+            a + b
+        }
+
+        let hook = Hooks::hook::<(u32, u32), u32, A>();
+        assert!(!hook.is_set());
+        assert_eq!(hook.call_if_set((1, 2)), None);
+
+        assert!(hook.set(add).is_none());
+        assert_eq!(hook.call_if_set((1, 2)), Some(3));
+        assert!(Hooks::hook::<(u32, u32), u32, A>().is_set());
+        assert!(!Hooks::hook::<(u32, u32), u32, B>().is_set());
+        assert!(!Hooks::hook::<(u32, u32), u64, A>().is_set());
+
+        let previous = hook.set(|(a, b)| a * b).unwrap();
+        assert_eq!(previous((1, 2)), 3);
+        assert_eq!(hook.get().unwrap()((3, 4)), 12);
+        assert!(hook.take().is_some());
+        assert!(hook.take().is_none());
+        assert_eq!(hook.call_if_set((1, 2)), None);
+    }
+
+    #[test]
+    fn plugins() {
+        define_namespace!(Plugins);
+
+        trait Codec: Sync {
+            fn id(&self) -> u32;
+        }
+
+        #[derive(Default)]
+        struct Plain;
+        impl Codec for Plain {
+            fn id(&self) -> u32 {
+                0
+            }
+        }
+
+        struct Counting(AtomicU32);
+        unsafe impl super::Zeroable for Counting {}
+        impl Codec for Counting {
+            fn id(&self) -> u32 {
+                self.0.fetch_add(1, Ordering::Relaxed) + 1
+            }
+        }
+
+        assert_eq!(Plugins::implementations_of::<dyn Codec>().count(), 0);
+        assert!(Plugins::register_impl::<dyn Codec, Plain>(|| &Plain));
+        assert!(Plugins::register_impl::<dyn Codec, Counting>(|| {
+            Plugins::generic_static::<Counting>()
+        }));
+        assert!(!Plugins::register_impl::<dyn Codec, Plain>(|| &Plain));
+        assert!(Plugins::register_impl::<dyn Send, Plain>(|| &Plain));
+
+        let codecs: Vec<_> = Plugins::implementations_of::<dyn Codec>().collect();
+        assert_eq!(codecs.len(), 2);
+        assert_eq!(codecs[0].type_id(), std::any::TypeId::of::<Counting>());
+        assert_eq!(codecs[1].type_name(), std::any::type_name::<Plain>());
+        assert_eq!(codecs[0].get().id(), 1);
+        assert_eq!(codecs[0].get().id(), 2);
+        assert_eq!(codecs[1].get().id(), 0);
+        assert_eq!(Plugins::implementations_of::<dyn Send>().count(), 1);
+    }
+
+    #[test]
+    fn thread_local() {
+        define_namespace!(Tls);
+
+        let local = Tls::generic_static_thread_local::<AtomicU64>();
+        assert_eq!(local, Tls::generic_static_thread_local::<AtomicU64>());
+        assert_ne!(
+            local.cast::<u8>(),
+            NonNull::from(Tls::generic_static::<AtomicU64>()).cast()
+        );
+        assert_ne!(
+            local.cast::<u8>(),
+            Tls::generic_static_thread_local::<AtomicU32>().cast()
+        );
+        unsafe { local.as_ref() }.store(1, Ordering::Relaxed);
+
+        let other = std::thread::spawn(|| {
+            let local = Tls::generic_static_thread_local::<AtomicU64>();
+            assert_eq!(unsafe { local.as_ref() }.load(Ordering::Relaxed), 0);
+            unsafe { local.as_ref() }.store(2, Ordering::Relaxed);
+            local.addr().get()
+        })
+        .join()
+        .unwrap();
+        assert_ne!(local.addr().get(), other);
+        assert_eq!(unsafe { local.as_ref() }.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn local_key() {
+        use std::cell::{Cell, RefCell};
+
+        define_namespace!(Keys);
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Guard(Cell<u32>);
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::Relaxed);
+                // The value of this key is already dropped.
+                let guard = Keys::generic_local_key::<Guard>(|| Guard(Cell::new(0)));
+                assert!(guard.try_with(|_| ()).is_err());
+            }
+        }
+
+        let count = Keys::generic_local_key::<Cell<u32>>(|| Cell::new(7));
+        assert_eq!(count.get(), 7);
+        count.set(8);
+        assert_eq!(
+            Keys::generic_local_key::<Cell<u32>>(|| Cell::new(0)).get(),
+            8
+        );
+
+        let names = Keys::generic_local_key::<RefCell<Vec<&str>>>(|| RefCell::new(Vec::new()));
+        names.set(vec!["a"]);
+        names.with_borrow_mut(|names| names.push("b"));
+        assert_eq!(names.take(), ["a", "b"]);
+
+        std::thread::spawn(move || {
+            assert_eq!(count.get(), 7);
+            names.with_borrow(|names| assert!(names.is_empty()));
+            let guard = Keys::generic_local_key::<Guard>(|| Guard(Cell::new(1)));
+            guard.with(|guard| guard.0.set(2));
+            assert_eq!(guard.try_with(|guard| guard.0.get()), Ok(2));
+        })
+        .join()
+        .unwrap();
+        assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+        assert_eq!(count.get(), 8);
+    }
+
+    #[test]
+    fn sharded() {
+        use crate::{shard_index, CacheAligned};
+
+        let shards = Test::generic_static_sharded::<AtomicU64, 4>();
+        assert_eq!(shards.as_ptr().addr() % CacheAligned::<AtomicU64>::ALIGN, 0);
+        assert_eq!(
+            std::ptr::addr_of!(shards[1]).addr() - std::ptr::addr_of!(shards[0]).addr(),
+            CacheAligned::<AtomicU64>::ALIGN
+        );
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    assert!(shard_index(4) < 4);
+                    Test::generic_static_shard::<AtomicU64, 4>().fetch_add(1, Ordering::Relaxed);
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        let total: u64 = shards.iter().map(|s| s.load(Ordering::Relaxed)).sum();
+        assert_eq!(total, 8);
+        assert_eq!(shard_index(1), 0);
+    }
+
+    #[test]
+    fn rcu() {
+        use crate::GenericRcu;
+
+        let rcu = Test::generic_static::<GenericRcu<String>>();
+        assert!(rcu.is_empty() && rcu.read().is_none());
+        assert_eq!(rcu.replace("a".to_owned()), None);
+
+        let reader = std::thread::scope(|scope| {
+            let guard = rcu.read().unwrap();
+            let writer = scope.spawn(|| rcu.replace("b".to_owned()));
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            // The writer waits for the guard.
+            assert!(!writer.is_finished());
+            assert_eq!(*guard, "a");
+            drop(guard);
+            writer.join().unwrap()
+        });
+        assert_eq!(reader.as_deref(), Some("a"));
+        assert_eq!(rcu.read().as_deref().map(String::as_str), Some("b"));
+        assert_eq!(rcu.take().as_deref(), Some("b"));
+        assert!(rcu.is_empty());
+    }
+
+    #[cfg(feature = "arc-swap")]
+    #[test]
+    fn arc_swap() {
+        use std::sync::Arc;
+
+        let swap = Test::arc_swap::<u32>();
+        assert!(swap.load().is_none());
+
+        let one = Arc::new(1);
+        assert!(swap
+            .compare_and_swap(&None::<Arc<u32>>, Some(one.clone()))
+            .is_none());
+        assert_eq!(Arc::strong_count(&one), 2);
+        assert_eq!(
+            swap.compare_and_swap(&None::<Arc<u32>>, Some(Arc::new(3)))
+                .as_ref(),
+            Some(&one)
+        );
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    for _ in 0..100 {
+                        Test::arc_swap::<u32>()
+                            .rcu(|value| value.as_deref().map(|v| Arc::new(v + 1)));
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(swap.load().as_deref(), Some(&401));
+        assert_eq!(Arc::strong_count(&one), 1);
+
+        let last = swap.swap(None).unwrap();
+        assert_eq!(Arc::strong_count(&last), 1);
+    }
+
+    #[test]
+    fn generation() {
+        use crate::Stamped;
+
+        struct Tag;
+
+        let generation = Test::generation::<Tag>();
+        assert_eq!(generation.current(), 0);
+        assert_eq!(generation.bump(), 1);
+        assert_eq!(Test::generation::<Tag>().current(), 1);
+        assert_eq!(Test::generation::<()>().current(), 0);
+
+        let mut stamped = Stamped::new();
+        assert!(stamped.get(generation).is_none());
+        assert_eq!(*stamped.get_or_update(generation, || 10), 10);
+        assert_eq!(stamped.generation(), Some(1));
+        assert_eq!(stamped.get(generation), Some(&10));
+
+        generation.bump();
+        assert!(stamped.get(generation).is_none());
+        assert_eq!(*stamped.get_or_update(generation, || 20), 20);
+        stamped.invalidate();
+        assert_eq!(stamped.generation(), None);
     }
 }
@@ -0,0 +1,207 @@
+//! Intrusive lists of `'static` nodes, see [`GenericListHead`].
+
+use std::{fmt, iter::FusedIterator, marker::PhantomData, ops::Deref};
+
+use crate::{
+    sync::atomic::{AtomicBool, AtomicPtr, Ordering},
+    Zeroable,
+};
+
+/// A zero-initializable head of a lock-free intrusive list of `'static` [`ListNode`]s, e.g. for
+/// per-type registration chains.
+///
+/// ```rust
+/// use generic_statics::{define_namespace, GenericListHead, ListNode, Namespace};
+///
+/// define_namespace!(Plugins);
+///
+/// struct Plugin {
+///     name: &'static str,
+/// }
+///
+/// static A: ListNode<Plugin> = ListNode::new(Plugin { name: "a" });
+/// static B: ListNode<Plugin> = ListNode::new(Plugin { name: "b" });
+///
+/// let plugins = Plugins::generic_static::<GenericListHead<Plugin>>();
+/// assert!(plugins.push(&A));
+/// assert!(plugins.push(&B));
+/// assert!(!plugins.push(&A));
+///
+/// let names: Vec<_> = plugins.iter().map(|plugin| plugin.name).collect();
+/// assert_eq!(names, ["b", "a"]);
+/// ```
+///
+/// Nodes can only be pushed and are never unlinked, so the list only holds `'static` nodes and
+/// can be iterated while nodes are pushed concurrently. The head only points to the nodes, so it
+/// isn't address-sensitive and doesn't have to be pinned.
+pub struct GenericListHead<T: 'static> {
+    first: AtomicPtr<ListNode<T>>,
+    // The list hands out `&'static T`, so it is only `Send` and `Sync` if `T` is `Sync`.
+    _nodes: PhantomData<&'static T>,
+}
+
+unsafe impl<T> Zeroable for GenericListHead<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            first: AtomicPtr::zeroed(),
+            _nodes: PhantomData,
+        }
+    }
+}
+
+impl<T> GenericListHead<T> {
+    /// Creates an empty list.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            first: AtomicPtr::new(std::ptr::null_mut()),
+            _nodes: PhantomData,
+        }
+    }
+
+    /// Pushes `node` to the front of the list, returning `false` if it is already linked into a
+    /// list (this one or another one).
+    pub fn push(&self, node: &'static ListNode<T>) -> bool {
+        if node.linked.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+
+        let new = node as *const ListNode<T> as *mut ListNode<T>;
+        let mut first = self.first.load(Ordering::Relaxed);
+        loop {
+            node.next.store(first, Ordering::Relaxed);
+            match self
+                .first
+                .compare_exchange_weak(first, new, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => return true,
+                Err(current) => first = current,
+            }
+        }
+    }
+
+    /// Returns whether the list is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.first.load(Ordering::Acquire).is_null()
+    }
+
+    /// Iterates over the values of the nodes, most recently pushed first.
+    #[inline]
+    pub fn iter(&self) -> ListIter<T> {
+        ListIter {
+            next: self.first.load(Ordering::Acquire),
+        }
+    }
+}
+
+impl<T> Default for GenericListHead<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl<T> fmt::Debug for GenericListHead<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenericListHead")
+            .field("is_empty", &self.is_empty())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> IntoIterator for &GenericListHead<T> {
+    type Item = &'static T;
+    type IntoIter = ListIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> ListIter<T> {
+        self.iter()
+    }
+}
+
+/// A node of a [`GenericListHead`], holding a value and the link to the next node.
+pub struct ListNode<T: 'static> {
+    value: T,
+    linked: AtomicBool,
+    next: AtomicPtr<ListNode<T>>,
+}
+
+unsafe impl<T: Zeroable> Zeroable for ListNode<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            value: T::zeroed(),
+            linked: AtomicBool::zeroed(),
+            next: AtomicPtr::zeroed(),
+        }
+    }
+}
+
+impl<T> ListNode<T> {
+    /// Creates an unlinked node holding `value`.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self {
+            value,
+            linked: AtomicBool::new(false),
+            next: AtomicPtr::new(std::ptr::null_mut()),
+        }
+    }
+
+    /// Returns whether the node has been pushed to a list.
+    #[inline]
+    pub fn is_linked(&self) -> bool {
+        self.linked.load(Ordering::Acquire)
+    }
+}
+
+impl<T> Deref for ListNode<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ListNode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListNode")
+            .field("value", &self.value)
+            .field("is_linked", &self.is_linked())
+            .finish()
+    }
+}
+
+/// Iterator over the values of a [`GenericListHead`], returned by [`GenericListHead::iter`].
+pub struct ListIter<T: 'static> {
+    next: *const ListNode<T>,
+}
+
+// SAFETY: The iterator only hands out shared references to the values.
+unsafe impl<T: Sync> Send for ListIter<T> {}
+unsafe impl<T: Sync> Sync for ListIter<T> {}
+
+impl<T> Iterator for ListIter<T> {
+    type Item = &'static T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'static T> {
+        // SAFETY: Only `'static` nodes are linked into the list and they are never unlinked.
+        let node: &'static ListNode<T> = unsafe { self.next.as_ref()? };
+        self.next = node.next.load(Ordering::Acquire);
+        Some(&node.value)
+    }
+}
+
+impl<T> FusedIterator for ListIter<T> {}
+
+impl<T> fmt::Debug for ListIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListIter").finish_non_exhaustive()
+    }
+}
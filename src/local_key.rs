@@ -0,0 +1,305 @@
+//! Thread-local keys on top of the thread-local storage, see
+//! [`Namespace::generic_local_key`](crate::Namespace::generic_local_key).
+
+use std::{
+    cell::{Cell, RefCell, UnsafeCell},
+    error::Error,
+    fmt,
+    mem::{self, MaybeUninit},
+    ptr::NonNull,
+};
+
+use crate::{Namespace, Zeroable};
+
+const UNINIT: u8 = 0;
+const ALIVE: u8 = 1;
+const DESTROYED: u8 = 2;
+
+/// The thread-local storage of a [`GenericLocalKey`].
+pub(crate) struct Slot<T> {
+    state: Cell<u8>,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+unsafe impl<T> Zeroable for Slot<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            state: Cell::new(UNINIT),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+}
+
+/// Returns the slot of `T` in namespace `N` in the current thread.
+pub(crate) fn slot<N: Namespace, T: 'static>() -> NonNull<Slot<T>> {
+    N::generic_static_thread_local::<Slot<T>>()
+}
+
+/// The destructors of the values initialized in the current thread, which are run when the
+/// thread exits.
+struct Destructors(RefCell<Vec<Destructor>>);
+
+/// A slot and the function dropping its value.
+type Destructor = (NonNull<u8>, unsafe fn(NonNull<u8>));
+
+impl Destructors {
+    fn run(&self) {
+        // The list isn't borrowed while a destructor runs, which might initialize other values.
+        loop {
+            let Some((slot, destroy)) = self.0.borrow_mut().pop() else {
+                break;
+            };
+            unsafe { destroy(slot) };
+        }
+    }
+}
+
+impl Drop for Destructors {
+    fn drop(&mut self) {
+        self.run();
+    }
+}
+
+#[cfg(feature = "loom")]
+loom::thread_local!(static DESTRUCTORS: Destructors = Destructors(RefCell::new(Vec::new())));
+#[cfg(not(feature = "loom"))]
+std::thread_local!(static DESTRUCTORS: Destructors = Destructors(RefCell::new(Vec::new())));
+
+/// Runs the destructors of the values initialized in the current thread (unless they already
+/// ran), for backends whose thread-local storage is freed when the thread exits.
+#[cfg(any(feature = "loom", miri))]
+pub(crate) fn run_destructors() {
+    let _ = DESTRUCTORS.try_with(Destructors::run);
+}
+
+/// Drops the value in the slot of `T` at `slot`.
+unsafe fn destroy<T>(slot: NonNull<u8>) {
+    let slot = slot.cast::<Slot<T>>().as_ref();
+    slot.state.set(DESTROYED);
+    (*slot.value.get()).assume_init_drop();
+}
+
+/// A key to a thread-local value of `T`, like [`std::thread::LocalKey`] but for generic
+/// thread-local statics, returned by
+/// [`Namespace::generic_local_key`](crate::Namespace::generic_local_key).
+///
+/// The value is initialized on the first access in every thread and dropped when the thread
+/// exits (if `T` needs to be dropped). Like with `std::thread::LocalKey`, accessing the value from
+/// the destructor of another thread-local value fails once it is dropped (or if it would have to
+/// be initialized), and values on the main thread might not be dropped.
+pub struct GenericLocalKey<T: 'static> {
+    slot: fn() -> NonNull<Slot<T>>,
+    init: fn() -> T,
+}
+
+impl<T: 'static> GenericLocalKey<T> {
+    #[inline]
+    pub(crate) fn new<N: Namespace>(init: fn() -> T) -> Self {
+        Self {
+            slot: slot::<N, T>,
+            init,
+        }
+    }
+
+    /// Calls `f` with a reference to the value in the current thread, initializing it if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value can't be accessed, see [`GenericLocalKey::try_with`].
+    #[inline]
+    #[track_caller]
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        match self.try_with(f) {
+            Ok(r) => r,
+            Err(err) => panic!("cannot access a generic thread-local value: {err}"),
+        }
+    }
+
+    /// Calls `f` with a reference to the value in the current thread, initializing it if needed.
+    ///
+    /// This fails if the value was dropped because the thread is exiting, or if it would have to
+    /// be initialized after the values of the thread were dropped.
+    #[inline]
+    pub fn try_with<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, AccessError> {
+        self.get_or_init(self.init).map(f).ok_or(AccessError(()))
+    }
+
+    /// Returns the value in the current thread, initializing it with `init` if needed.
+    fn get_or_init(&self, init: impl FnOnce() -> T) -> Option<&T> {
+        let slot = unsafe { (self.slot)().as_ref() };
+        match slot.state.get() {
+            ALIVE => return Some(unsafe { (*slot.value.get()).assume_init_ref() }),
+            DESTROYED => return None,
+            _ => {}
+        }
+
+        let value = init();
+        match slot.state.get() {
+            // `init` accessed the key itself, whose value is replaced (like with `thread_local!`).
+            // No references to it remain, as they are scoped to the closures passed to `with`.
+            ALIVE => drop(mem::replace(
+                unsafe { (*slot.value.get()).assume_init_mut() },
+                value,
+            )),
+            DESTROYED => return None,
+            _ => {
+                if mem::needs_drop::<T>() {
+                    let entry = (NonNull::from(slot).cast(), destroy::<T> as unsafe fn(_));
+                    DESTRUCTORS
+                        .try_with(|destructors| destructors.0.borrow_mut().push(entry))
+                        .ok()?;
+                }
+                unsafe { (*slot.value.get()).write(value) };
+                slot.state.set(ALIVE);
+            }
+        }
+        Some(unsafe { (*slot.value.get()).assume_init_ref() })
+    }
+
+    /// Like [`GenericLocalKey::get_or_init`] but panics if the value can't be accessed.
+    #[track_caller]
+    fn initialize_with(&self, init: impl FnOnce() -> T) -> &T {
+        match self.get_or_init(init) {
+            Some(value) => value,
+            None => panic!(
+                "cannot access a generic thread-local value: {}",
+                AccessError(())
+            ),
+        }
+    }
+}
+
+impl<T: 'static> GenericLocalKey<Cell<T>> {
+    /// Sets the value in the current thread to `value`, like [`std::thread::LocalKey::set`].
+    ///
+    /// If the value isn't initialized yet, it's initialized with `value` instead of the
+    /// initializer of the key.
+    pub fn set(&self, value: T) {
+        let mut value = Some(value);
+        let cell = self.initialize_with(|| Cell::new(value.take().unwrap()));
+        if let Some(value) = value {
+            cell.set(value);
+        }
+    }
+
+    /// Returns a copy of the value in the current thread, initializing it if needed.
+    #[inline]
+    pub fn get(&self) -> T
+    where
+        T: Copy,
+    {
+        self.with(Cell::get)
+    }
+
+    /// Takes the value in the current thread, leaving `T::default()` in its place.
+    #[inline]
+    pub fn take(&self) -> T
+    where
+        T: Default,
+    {
+        self.with(Cell::take)
+    }
+
+    /// Replaces the value in the current thread with `value`, returning the old value.
+    #[inline]
+    pub fn replace(&self, value: T) -> T {
+        self.with(|cell| cell.replace(value))
+    }
+}
+
+impl<T: 'static> GenericLocalKey<RefCell<T>> {
+    /// Calls `f` with a shared borrow of the value in the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is mutably borrowed or can't be accessed.
+    #[inline]
+    #[track_caller]
+    pub fn with_borrow<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.with(|cell| f(&cell.borrow()))
+    }
+
+    /// Calls `f` with a mutable borrow of the value in the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is borrowed or can't be accessed.
+    #[inline]
+    #[track_caller]
+    pub fn with_borrow_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        self.with(|cell| f(&mut cell.borrow_mut()))
+    }
+
+    /// Sets the value in the current thread to `value`, like [`std::thread::LocalKey::set`].
+    ///
+    /// If the value isn't initialized yet, it's initialized with `value` instead of the
+    /// initializer of the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is borrowed or can't be accessed.
+    pub fn set(&self, value: T) {
+        let mut value = Some(value);
+        let cell = self.initialize_with(|| RefCell::new(value.take().unwrap()));
+        if let Some(value) = value {
+            *cell.borrow_mut() = value;
+        }
+    }
+
+    /// Takes the value in the current thread, leaving `T::default()` in its place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is borrowed or can't be accessed.
+    #[inline]
+    pub fn take(&self) -> T
+    where
+        T: Default,
+    {
+        self.with(RefCell::take)
+    }
+
+    /// Replaces the value in the current thread with `value`, returning the old value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is borrowed or can't be accessed.
+    #[inline]
+    pub fn replace(&self, value: T) -> T {
+        self.with(|cell| cell.replace(value))
+    }
+}
+
+impl<T: 'static> Clone for GenericLocalKey<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for GenericLocalKey<T> {}
+
+impl<T: 'static> fmt::Debug for GenericLocalKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenericLocalKey").finish_non_exhaustive()
+    }
+}
+
+/// The error returned by [`GenericLocalKey::try_with`] if the value can't be accessed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct AccessError(());
+
+impl fmt::Debug for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessError").finish()
+    }
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("already destroyed")
+    }
+}
+
+impl Error for AccessError {}
@@ -0,0 +1,35 @@
+//! Deterministic storage backend used with the `loom` feature.
+//!
+//! Instead of reserving static storage with inline assembly, every `(namespace, type)` pair gets
+//! a heap allocated initial value (e.g. [`Zeroable::zeroed`](crate::Zeroable::zeroed)) that
+//! lives for the duration of the current loom execution. This makes sure every explored
+//! interleaving starts from fresh statics.
+
+use std::{
+    any::{Any, TypeId},
+    collections::BTreeMap,
+    ptr::NonNull,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::init::Storage;
+
+struct Slot(Box<dyn Any>);
+
+// SAFETY: The boxed value is only ever accessed through the references handed out by
+// `reserve`, which are subject to the usual `Namespace::generic_static` rules.
+unsafe impl Send for Slot {}
+
+loom::lazy_static! {
+    static ref STATICS: Mutex<BTreeMap<(TypeId, TypeId), Slot>> = Mutex::new(BTreeMap::new());
+}
+
+/// Returns the address of `T`'s storage in namespace `N` for the current loom execution.
+pub(crate) fn reserve<N: 'static, T: Storage>() -> NonNull<T> {
+    let mut statics = STATICS.lock().unwrap_or_else(PoisonError::into_inner);
+    let slot = statics
+        .entry((TypeId::of::<N>(), TypeId::of::<T>()))
+        .or_insert_with(|| Slot(Box::new(T::init())));
+
+    NonNull::from(slot.0.downcast_ref::<T>().unwrap())
+}
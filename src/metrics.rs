@@ -0,0 +1,326 @@
+//! Per-tag metrics (`metrics` feature), see [`Namespace::metric`](crate::Namespace::metric).
+//!
+//! The first access to every metric pushes a [`Node`] onto a global intrusive list, which
+//! [`gather`] walks. Like the metric itself, the node lives in a generic static, but in a private
+//! namespace, so resetting the statics of a namespace doesn't corrupt the list.
+
+use std::{
+    any,
+    cell::UnsafeCell,
+    fmt,
+    marker::PhantomData,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicI64, AtomicPtr, AtomicU64, Ordering},
+};
+
+use crate::{Namespace, NamespaceInfo, Zeroable};
+
+/// The number of buckets of a [`HistogramMetric`].
+pub const HISTOGRAM_BUCKETS: usize = 65;
+
+/// Metrics returned by [`Namespace::metric`](crate::Namespace::metric).
+///
+/// This is implemented by [`CounterMetric`], [`GaugeMetric`] and [`HistogramMetric`].
+pub trait Metric: Zeroable + Sync + 'static + sealed::Sealed {
+    /// Returns the current value of the metric.
+    fn value(&self) -> MetricValue;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A monotonically increasing counter.
+#[derive(Default)]
+pub struct CounterMetric(AtomicU64);
+
+unsafe impl Zeroable for CounterMetric {}
+
+impl CounterMetric {
+    /// Increments the counter by one.
+    #[inline]
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Increments the counter by `n`.
+    #[inline]
+    pub fn add(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Returns the current count.
+    #[inline]
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl sealed::Sealed for CounterMetric {}
+
+impl Metric for CounterMetric {
+    fn value(&self) -> MetricValue {
+        MetricValue::Counter(self.get())
+    }
+}
+
+/// A value that can go up and down.
+#[derive(Default)]
+pub struct GaugeMetric(AtomicI64);
+
+unsafe impl Zeroable for GaugeMetric {}
+
+impl GaugeMetric {
+    /// Sets the gauge to `value`.
+    #[inline]
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    /// Adds `delta` to the gauge.
+    #[inline]
+    pub fn add(&self, delta: i64) {
+        self.0.fetch_add(delta, Ordering::Relaxed);
+    }
+
+    /// Subtracts `delta` from the gauge.
+    #[inline]
+    pub fn sub(&self, delta: i64) {
+        self.0.fetch_sub(delta, Ordering::Relaxed);
+    }
+
+    /// Returns the current value.
+    #[inline]
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl sealed::Sealed for GaugeMetric {}
+
+impl Metric for GaugeMetric {
+    fn value(&self) -> MetricValue {
+        MetricValue::Gauge(self.get())
+    }
+}
+
+/// A histogram of `u64` samples with power-of-two buckets.
+///
+/// Bucket `0` counts the samples equal to zero, bucket `i` those in `2^(i-1)..2^i`.
+pub struct HistogramMetric {
+    count: AtomicU64,
+    sum: AtomicU64,
+    buckets: [AtomicU64; HISTOGRAM_BUCKETS],
+}
+
+unsafe impl Zeroable for HistogramMetric {}
+
+impl HistogramMetric {
+    /// Records the sample `value`.
+    #[inline]
+    pub fn record(&self, value: u64) {
+        let bucket = (u64::BITS - value.leading_zeros()) as usize;
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(value, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of recorded samples.
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the (wrapping) sum of the recorded samples.
+    #[inline]
+    pub fn sum(&self) -> u64 {
+        self.sum.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of samples in every bucket.
+    pub fn buckets(&self) -> [u64; HISTOGRAM_BUCKETS] {
+        std::array::from_fn(|i| self.buckets[i].load(Ordering::Relaxed))
+    }
+}
+
+impl Default for HistogramMetric {
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl sealed::Sealed for HistogramMetric {}
+
+impl Metric for HistogramMetric {
+    fn value(&self) -> MetricValue {
+        MetricValue::Histogram {
+            count: self.count(),
+            sum: self.sum(),
+            buckets: self.buckets(),
+        }
+    }
+}
+
+macro_rules! impl_debug {
+    ($($ty:ty),*) => {
+        $(
+            impl fmt::Debug for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    self.value().fmt(f)
+                }
+            }
+        )*
+    };
+}
+
+impl_debug!(CounterMetric, GaugeMetric, HistogramMetric);
+
+/// The value of a metric, see [`MetricSample`].
+// Samples are gathered rarely, boxing the buckets isn't worth it.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MetricValue {
+    /// The value of a [`CounterMetric`].
+    Counter(u64),
+    /// The value of a [`GaugeMetric`].
+    Gauge(i64),
+    /// The value of a [`HistogramMetric`].
+    Histogram {
+        /// The number of samples.
+        count: u64,
+        /// The (wrapping) sum of the samples.
+        sum: u64,
+        /// The number of samples in every bucket.
+        buckets: [u64; HISTOGRAM_BUCKETS],
+    },
+}
+
+/// A metric and its current value, see [`gather`].
+#[derive(Clone, Debug)]
+pub struct MetricSample {
+    namespace: NamespaceInfo,
+    name: &'static str,
+    value: MetricValue,
+}
+
+impl MetricSample {
+    /// The namespace of the metric.
+    #[inline]
+    pub fn namespace(&self) -> NamespaceInfo {
+        self.namespace
+    }
+
+    /// The type name of the metric's tag.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The value of the metric.
+    #[inline]
+    pub fn value(&self) -> &MetricValue {
+        &self.value
+    }
+}
+
+/// Storage of the metric `M` for `Tag`, so tags don't have to be [`Zeroable`] themselves.
+#[repr(transparent)]
+pub(crate) struct MetricFor<M, Tag>(pub(crate) M, PhantomData<fn() -> Tag>);
+
+unsafe impl<M: Zeroable, Tag> Zeroable for MetricFor<M, Tag> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(M::zeroed(), PhantomData)
+    }
+}
+
+/// An entry of the list of registered metrics.
+struct Node {
+    next: AtomicPtr<Node>,
+    registered: AtomicBool,
+    /// Written once by the thread setting `registered`, before the node is published.
+    sample: UnsafeCell<Option<fn() -> MetricSample>>,
+}
+
+/// Storage of the node of the metric storage `S` in namespace `N`.
+#[repr(transparent)]
+struct NodeOf<N, S>(Node, PhantomData<fn() -> (N, S)>);
+
+unsafe impl<N, S> Zeroable for NodeOf<N, S> {}
+
+/// The most recently registered node.
+static HEAD: AtomicPtr<Node> = AtomicPtr::new(ptr::null_mut());
+
+/// Returns the metric `M` for `Tag` in namespace `N`, registering it on the first access.
+#[inline]
+pub(crate) fn metric<N: Namespace, M: Metric, Tag: 'static>() -> &'static M {
+    let node = unsafe {
+        &crate::reserve_bookkeeping::<N, NodeOf<N, MetricFor<M, Tag>>>()
+            .as_ref()
+            .0
+    };
+    if !node.registered.load(Ordering::Relaxed) {
+        register(node, sample::<N, M, Tag>);
+    }
+    &N::generic_static::<MetricFor<M, Tag>>().0
+}
+
+fn sample<N: Namespace, M: Metric, Tag: 'static>() -> MetricSample {
+    MetricSample {
+        namespace: N::info(),
+        name: any::type_name::<Tag>(),
+        value: N::generic_static::<MetricFor<M, Tag>>().0.value(),
+    }
+}
+
+#[cold]
+fn register(node: &'static Node, sample: fn() -> MetricSample) {
+    if node.registered.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    unsafe { *node.sample.get() = Some(sample) };
+
+    let node = node as *const Node as *mut Node;
+    let mut head = HEAD.load(Ordering::Relaxed);
+    loop {
+        unsafe { (*node).next.store(head, Ordering::Relaxed) };
+        match HEAD.compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed) {
+            Ok(_) => break,
+            Err(current) => head = current,
+        }
+    }
+}
+
+/// Returns the current values of all metrics accessed so far, most recently registered first.
+///
+/// ```rust
+/// use generic_statics::{define_namespace, gather, CounterMetric, MetricValue, Namespace};
+///
+/// define_namespace!(Metrics);
+///
+/// struct Requests;
+///
+/// Metrics::metric::<CounterMetric, Requests>().inc();
+///
+/// let sample = gather()
+///     .into_iter()
+///     .find(|sample| sample.name().ends_with("Requests"))
+///     .unwrap();
+/// assert_eq!(sample.value(), &MetricValue::Counter(1));
+/// ```
+///
+/// This is meant to be called by exporters. Only metrics accessed through the accessors of this
+/// linked image are gathered.
+pub fn gather() -> Vec<MetricSample> {
+    let mut samples = Vec::new();
+    let mut node = HEAD.load(Ordering::Acquire);
+    while let Some(current) = unsafe { node.as_ref() } {
+        // Published nodes have a sample function.
+        if let Some(sample) = unsafe { *current.sample.get() } {
+            samples.push(sample());
+        }
+        node = current.next.load(Ordering::Relaxed);
+    }
+    samples
+}
@@ -0,0 +1,33 @@
+//! Storage backend used under Miri, which cannot execute inline assembly.
+//!
+//! Every `(namespace, type)` pair gets a leaked heap allocation holding the initial value, whose
+//! address is recorded in the canonical registry.
+
+use std::{
+    alloc::{self, Layout},
+    any::TypeId,
+    mem,
+    ptr::NonNull,
+};
+
+use crate::{init::Storage, registry};
+
+/// Returns the address of `T`'s storage in namespace `N`.
+pub(crate) fn reserve<N: 'static, T: Storage>() -> NonNull<T> {
+    let addr = registry::get_or_insert_local(TypeId::of::<N>(), TypeId::of::<T>(), || {
+        // Like the asm backend, reserve at least one byte so that zero-sized types get unique
+        // addresses too.
+        let layout = Layout::from_size_align(mem::size_of::<T>().max(1), mem::align_of::<T>())
+            .expect("invalid layout");
+
+        let addr = unsafe { alloc::alloc_zeroed(layout) };
+        let Some(addr) = NonNull::new(addr) else {
+            alloc::handle_alloc_error(layout);
+        };
+
+        unsafe { addr.cast::<T>().write(T::init()) };
+        addr.cast()
+    });
+
+    addr.cast()
+}
@@ -0,0 +1,45 @@
+//! Locking the storage into memory, see [`Namespace::mlock`](crate::Namespace::mlock).
+
+use std::{io, ptr::NonNull};
+
+/// Locks the pages backing the `len` bytes at `addr` into memory.
+pub(crate) fn lock(addr: NonNull<u8>, len: usize) -> io::Result<()> {
+    if len == 0 {
+        return Ok(());
+    }
+
+    unsafe { sys::lock(addr, len) }
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::{ffi::c_int, io, ptr::NonNull};
+
+    extern "C" {
+        fn mlock(addr: *const u8, len: usize) -> c_int;
+    }
+
+    pub(super) unsafe fn lock(addr: NonNull<u8>, len: usize) -> io::Result<()> {
+        match unsafe { mlock(addr.as_ptr(), len) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::{io, ptr::NonNull};
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn VirtualLock(addr: *mut u8, len: usize) -> i32;
+    }
+
+    pub(super) unsafe fn lock(addr: NonNull<u8>, len: usize) -> io::Result<()> {
+        match unsafe { VirtualLock(addr.as_ptr(), len) } {
+            0 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! A zero-initializable mutex.
+
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    futex,
+    sync::atomic::{AtomicU32, Ordering},
+    Zeroable,
+};
+
+const UNLOCKED: u32 = 0;
+const LOCKED: u32 = 1;
+/// Locked, with other threads (possibly) waiting.
+const CONTENDED: u32 = 2;
+
+/// A mutex whose unlocked state is all zeroes, so it can be used as a generic static.
+///
+/// ```rust
+/// use generic_statics::{define_namespace, GenericMutex, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// let mut histogram = Test::generic_static::<GenericMutex<[u64; 4]>>().lock();
+/// histogram[2] += 1;
+/// ```
+///
+/// Contended threads block on the futex-like primitive of the OS. Unlike `std::sync::Mutex`,
+/// the mutex isn't poisoned if a thread panics while holding the lock.
+pub struct GenericMutex<T> {
+    state: AtomicU32,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: The value is only accessed while holding the lock.
+unsafe impl<T: Send> Sync for GenericMutex<T> {}
+
+unsafe impl<T: Zeroable> Zeroable for GenericMutex<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            state: AtomicU32::zeroed(),
+            value: UnsafeCell::new(T::zeroed()),
+        }
+    }
+}
+
+impl<T> GenericMutex<T> {
+    /// Creates a new, unlocked mutex.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self {
+            state: AtomicU32::new(UNLOCKED),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Acquires the lock, blocking the current thread until it's available.
+    #[inline]
+    pub fn lock(&self) -> GenericMutexGuard<'_, T> {
+        if self
+            .state
+            .compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            self.lock_contended();
+        }
+
+        GenericMutexGuard {
+            mutex: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Acquires the lock if it's available.
+    #[inline]
+    pub fn try_lock(&self) -> Option<GenericMutexGuard<'_, T>> {
+        self.state
+            .compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .ok()?;
+
+        Some(GenericMutexGuard {
+            mutex: self,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns a mutable reference to the value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Consumes the mutex, returning the value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    #[cold]
+    fn lock_contended(&self) {
+        // Once contended, the lock is acquired as `CONTENDED`, as other threads might still be
+        // waiting.
+        while self.state.swap(CONTENDED, Ordering::Acquire) != UNLOCKED {
+            futex::wait(&self.state, CONTENDED);
+        }
+    }
+
+    #[inline]
+    fn unlock(&self) {
+        if self.state.swap(UNLOCKED, Ordering::Release) == CONTENDED {
+            futex::wake_one(&self.state);
+        }
+    }
+}
+
+impl<T: Default> Default for GenericMutex<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            state: AtomicU32::new(UNLOCKED),
+            value: UnsafeCell::new(T::default()),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GenericMutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("GenericMutex");
+        match self.try_lock() {
+            Some(guard) => d.field("data", &&*guard),
+            None => d.field("data", &format_args!("<locked>")),
+        };
+        d.finish_non_exhaustive()
+    }
+}
+
+/// The guard of a locked [`GenericMutex`], which unlocks it on drop.
+#[must_use = "if unused the mutex will immediately unlock"]
+pub struct GenericMutexGuard<'a, T> {
+    mutex: &'a GenericMutex<T>,
+    /// Makes the guard `Send`/`Sync` like the `&mut T` it provides.
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<T> Deref for GenericMutexGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T> DerefMut for GenericMutexGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T> Drop for GenericMutexGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GenericMutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
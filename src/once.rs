@@ -0,0 +1,144 @@
+//! One-shot initialization per tag, see [`Namespace::once`](crate::Namespace::once).
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    futex,
+    sync::atomic::{AtomicU32, Ordering},
+    Zeroable,
+};
+
+const INCOMPLETE: u32 = 0;
+const RUNNING: u32 = 1;
+/// Running, with other threads (possibly) waiting.
+const CONTENDED: u32 = 2;
+const COMPLETE: u32 = 3;
+
+/// A zero-initializable synchronization primitive to run a closure exactly once, like
+/// `std::sync::Once`.
+///
+/// This is returned by [`Namespace::once`](crate::Namespace::once). Unlike `std::sync::Once`, it
+/// isn't poisoned if the closure panics, the next call runs a closure again.
+pub struct GenericOnce {
+    state: AtomicU32,
+}
+
+unsafe impl Zeroable for GenericOnce {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            state: AtomicU32::zeroed(),
+        }
+    }
+}
+
+impl GenericOnce {
+    /// Creates a new, incomplete `GenericOnce`.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU32::new(INCOMPLETE),
+        }
+    }
+
+    /// Runs `f` if this is the first call, blocking until it has completed otherwise.
+    #[inline]
+    pub fn call_once(&self, f: impl FnOnce()) {
+        if !self.is_completed() {
+            self.call_once_slow(f);
+        }
+    }
+
+    /// Returns whether a closure has completed.
+    #[inline]
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+
+    #[cold]
+    fn call_once_slow(&self, f: impl FnOnce()) {
+        let mut state = self.state.load(Ordering::Acquire);
+        loop {
+            match state {
+                INCOMPLETE => {
+                    if let Err(current) = self.state.compare_exchange_weak(
+                        INCOMPLETE,
+                        RUNNING,
+                        Ordering::Acquire,
+                        Ordering::Acquire,
+                    ) {
+                        state = current;
+                        continue;
+                    }
+
+                    let mut finish = Finish {
+                        once: self,
+                        state: INCOMPLETE,
+                    };
+                    f();
+                    finish.state = COMPLETE;
+                    return;
+                }
+                RUNNING => {
+                    if let Err(current) = self.state.compare_exchange_weak(
+                        RUNNING,
+                        CONTENDED,
+                        Ordering::Acquire,
+                        Ordering::Acquire,
+                    ) {
+                        state = current;
+                        continue;
+                    }
+                    state = CONTENDED;
+                }
+                CONTENDED => {
+                    futex::wait(&self.state, CONTENDED);
+                    state = self.state.load(Ordering::Acquire);
+                }
+                _ => return,
+            }
+        }
+    }
+}
+
+/// Sets the state once the closure has returned (or panicked) and wakes waiting threads.
+struct Finish<'a> {
+    once: &'a GenericOnce,
+    state: u32,
+}
+
+impl Drop for Finish<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.once.state.swap(self.state, Ordering::Release) == CONTENDED {
+            futex::wake_all(&self.once.state);
+        }
+    }
+}
+
+impl Default for GenericOnce {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            state: AtomicU32::new(INCOMPLETE),
+        }
+    }
+}
+
+impl fmt::Debug for GenericOnce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenericOnce").finish_non_exhaustive()
+    }
+}
+
+/// Storage of the [`GenericOnce`] for `Tag`, so tags don't have to be [`Zeroable`] themselves.
+#[repr(transparent)]
+pub(crate) struct OnceFor<Tag>(pub(crate) GenericOnce, PhantomData<fn() -> Tag>);
+
+unsafe impl<Tag> Zeroable for OnceFor<Tag> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(GenericOnce::zeroed(), PhantomData)
+    }
+}
@@ -0,0 +1,87 @@
+//! A zero-initializable once cell for arbitrary types.
+
+use std::{fmt, marker::PhantomData, ptr};
+
+use crate::{
+    sync::atomic::{AtomicPtr, Ordering},
+    Zeroable,
+};
+
+/// A cell that can be written to only once, usable as a generic static for any `T: 'static`.
+///
+/// The value is boxed and leaked on first initialization, which makes it possible to store types
+/// that aren't [`Zeroable`], at the cost of one allocation. This is returned by
+/// [`Namespace::generic_once`](crate::Namespace::generic_once):
+///
+/// ```rust
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// let cell = Test::generic_once::<String>();
+/// assert_eq!(cell.get(), None);
+/// assert_eq!(cell.get_or_init(|| "first".to_owned()), "first");
+/// assert_eq!(cell.set("second".to_owned()), Err("second".to_owned()));
+/// ```
+pub struct GenericOnceLock<T> {
+    value: AtomicPtr<T>,
+    _marker: PhantomData<Box<T>>,
+}
+
+// SAFETY: The value is only shared after it has been published, the cell itself owns a `Box<T>`.
+unsafe impl<T: Send + Sync> Sync for GenericOnceLock<T> {}
+unsafe impl<T: Send> Send for GenericOnceLock<T> {}
+
+unsafe impl<T> Zeroable for GenericOnceLock<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            value: AtomicPtr::zeroed(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> GenericOnceLock<T> {
+    /// Returns the value if the cell has been initialized.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        unsafe { self.value.load(Ordering::Acquire).as_ref() }
+    }
+
+    /// Initializes the cell with `value`.
+    ///
+    /// Returns `value` back if the cell has already been initialized.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let value = Box::into_raw(Box::new(value));
+
+        match self.value.compare_exchange(
+            ptr::null_mut(),
+            value,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(*unsafe { Box::from_raw(value) }),
+        }
+    }
+
+    /// Returns the value, initializing the cell with `f` if it's empty.
+    ///
+    /// If multiple threads initialize the cell concurrently, `f` may be called more than once but
+    /// only one of the values is stored, the others are dropped.
+    pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        if let Some(value) = self.get() {
+            return value;
+        }
+
+        let _ = self.set(f());
+        self.get().expect("initialized")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GenericOnceLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("GenericOnceLock").field(&self.get()).finish()
+    }
+}
@@ -0,0 +1,153 @@
+//! Per-CPU statics for kernels, see [`Namespace::generic_static_percpu`].
+//!
+//! The storage of per-CPU statics is emitted into the `generic_statics_percpu` section, which
+//! serves as the template of the per-CPU areas. The kernel allocates an area of [`area_size`]
+//! bytes per CPU, initializes it with [`init_area`] and sets it as the per-CPU base of the CPU
+//! with [`set_base`]. A per-CPU static is then found at the same offset in the area of the
+//! current CPU (after a header of [`AREA_ALIGN`] bytes) as its storage in the template.
+//!
+//! The per-CPU base is held in `IA32_GS_BASE` on `x86_64`, which can't be read without
+//! `FSGSBASE`, so the header starts with a pointer to the area itself, which is read with a
+//! `gs`-relative load. On `aarch64`, the base is held in `TPIDR_EL1`.
+//!
+//! [`Namespace::generic_static_percpu`]: crate::Namespace::generic_static_percpu
+
+use std::ptr;
+
+use crate::define_section;
+
+define_section!(pub(crate) Template in "generic_statics_percpu");
+
+/// The alignment of the per-CPU areas, which is also the size of their header.
+///
+/// The types of per-CPU statics can't be aligned to more than this.
+pub const AREA_ALIGN: usize = 128;
+
+/// Returns the bounds of the template of the per-CPU areas.
+#[inline(always)]
+fn template() -> std::ops::Range<*const u8> {
+    #[allow(unused_assignments)]
+    let (mut start, mut stop): (*const u8, *const u8) = (ptr::null(), ptr::null());
+
+    // The (empty) section makes sure the bounds exist even without any per-CPU statics.
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        std::arch::asm!(
+            ".pushsection generic_statics_percpu,\"awR\",@nobits",
+            ".popsection",
+            ".hidden __start_generic_statics_percpu",
+            ".hidden __stop_generic_statics_percpu",
+            "lea {start}, [rip + __start_generic_statics_percpu]",
+            "lea {stop}, [rip + __stop_generic_statics_percpu]",
+            start = out(reg) start,
+            stop = out(reg) stop,
+            options(pure, nomem, nostack, preserves_flags)
+        );
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        std::arch::asm!(
+            ".pushsection generic_statics_percpu,\"awR\",@nobits",
+            ".popsection",
+            ".hidden __start_generic_statics_percpu",
+            ".hidden __stop_generic_statics_percpu",
+            "adrp {start}, __start_generic_statics_percpu",
+            "add {start}, {start}, :lo12:__start_generic_statics_percpu",
+            "adrp {stop}, __stop_generic_statics_percpu",
+            "add {stop}, {stop}, :lo12:__stop_generic_statics_percpu",
+            start = out(reg) start,
+            stop = out(reg) stop,
+            options(pure, nomem, nostack, preserves_flags)
+        );
+    }
+
+    start..stop
+}
+
+/// Returns the size of a per-CPU area (including its header).
+#[inline]
+pub fn area_size() -> usize {
+    let template = template();
+    AREA_ALIGN + (template.end.addr() - template.start.addr())
+}
+
+/// Initializes the per-CPU area at `area`, zeroing its per-CPU statics.
+///
+/// # Safety
+///
+/// `area` must be valid for writes of [`area_size`] bytes, aligned to [`AREA_ALIGN`] and must
+/// not be in use as per-CPU area.
+pub unsafe fn init_area(area: *mut u8) {
+    debug_assert_eq!(area.addr() % AREA_ALIGN, 0, "misaligned per-CPU area");
+
+    ptr::write_bytes(area, 0, area_size());
+    area.cast::<*mut u8>().write(area);
+}
+
+/// Sets `area` as the per-CPU area of the current CPU.
+///
+/// This writes `IA32_GS_BASE` on `x86_64` (with `wrmsr`, so it has to be called in ring 0, and
+/// before `swapgs` if the kernel uses it) and `TPIDR_EL1` on `aarch64`, which must not be used
+/// by the kernel otherwise.
+///
+/// # Safety
+///
+/// `area` must have been initialized with [`init_area`], must not be deallocated and must not be
+/// used as per-CPU area of another CPU.
+pub unsafe fn set_base(area: *mut u8) {
+    #[cfg(target_arch = "x86_64")]
+    std::arch::asm!(
+        "wrmsr",
+        in("ecx") 0xc000_0101u32,
+        in("eax") area.addr() as u32,
+        in("edx") (area.addr() >> 32) as u32,
+        options(nostack, preserves_flags)
+    );
+
+    #[cfg(target_arch = "aarch64")]
+    std::arch::asm!(
+        "msr tpidr_el1, {area}",
+        area = in(reg) area,
+        options(nostack, preserves_flags)
+    );
+}
+
+/// Returns the per-CPU area of the current CPU.
+#[inline(always)]
+fn base() -> *mut u8 {
+    #[allow(unused_assignments)]
+    let mut base: *mut u8 = ptr::null_mut();
+
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        std::arch::asm!(
+            "mov {base}, qword ptr gs:[0]",
+            base = out(reg) base,
+            options(nostack, readonly, preserves_flags)
+        );
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        std::arch::asm!(
+            "mrs {base}, tpidr_el1",
+            base = out(reg) base,
+            options(nomem, nostack, preserves_flags)
+        );
+    }
+
+    base
+}
+
+/// Returns the copy of the storage at `template` in the per-CPU area of the current CPU.
+///
+/// # Safety
+///
+/// `template` must be storage in the template and the per-CPU area of the current CPU must have
+/// been set with [`set_base`].
+#[inline(always)]
+pub(crate) unsafe fn this_cpu<T>(template: *const T) -> *const T {
+    let offset = template.addr() - self::template().start.addr();
+    base().add(AREA_ALIGN + offset).cast::<T>()
+}
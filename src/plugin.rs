@@ -0,0 +1,119 @@
+//! Registries of trait implementations, see
+//! [`Namespace::register_impl`](crate::Namespace::register_impl).
+//!
+//! Every implementation is stored in a generic static of its own (in a private namespace, keyed
+//! by the namespace, the trait object type and the implementing type). Registered implementations
+//! are linked into an intrusive list per namespace and trait object type, so registration and
+//! iteration don't allocate.
+
+use std::{
+    any::{self, TypeId},
+    fmt, iter,
+    marker::PhantomData,
+    mem, ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, Ordering},
+};
+
+use crate::{define_namespace, Namespace, Zeroable};
+
+define_namespace!(Plugins);
+
+/// A registered implementation of the trait object type `Dyn`, returned by
+/// [`Namespace::implementations_of`](crate::Namespace::implementations_of).
+pub struct Implementation<Dyn: ?Sized + 'static> {
+    type_name: &'static str,
+    type_id: TypeId,
+    /// The `fn() -> &'static Dyn` returning the instance, set on registration.
+    instance: AtomicPtr<()>,
+    registered: AtomicBool,
+    next: AtomicPtr<Implementation<Dyn>>,
+}
+
+impl<Dyn: ?Sized> Implementation<Dyn> {
+    fn new<Impl: 'static>() -> Self {
+        Self {
+            type_name: any::type_name::<Impl>(),
+            type_id: TypeId::of::<Impl>(),
+            instance: AtomicPtr::new(ptr::null_mut()),
+            registered: AtomicBool::new(false),
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// The type name of the implementing type.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// The [`TypeId`] of the implementing type.
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Returns the instance of the implementation, as returned by the function it was registered
+    /// with.
+    #[inline]
+    pub fn get(&self) -> &'static Dyn {
+        let instance = self.instance.load(Ordering::Acquire);
+        debug_assert!(!instance.is_null());
+        // SAFETY: Implementations are only linked into the list after their function was set.
+        let instance = unsafe { mem::transmute::<*mut (), fn() -> &'static Dyn>(instance) };
+        instance()
+    }
+}
+
+impl<Dyn: ?Sized> fmt::Debug for Implementation<Dyn> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Implementation")
+            .field("type_name", &self.type_name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Storage of the implementation of `Dyn` by `Impl` in namespace `N`.
+struct Entry<N, Dyn: ?Sized + 'static, Impl>(Implementation<Dyn>, PhantomData<fn() -> (N, Impl)>);
+
+/// Head of the list of implementations of `Dyn` in namespace `N`.
+struct Head<N, Dyn: ?Sized + 'static>(AtomicPtr<Implementation<Dyn>>, PhantomData<fn() -> N>);
+
+unsafe impl<N, Dyn: ?Sized> Zeroable for Head<N, Dyn> {}
+
+/// Registers `Impl` as an implementation of `Dyn` in namespace `N`, returning whether it wasn't
+/// registered before.
+pub(crate) fn register<N: Namespace, Dyn: ?Sized + 'static, Impl: 'static>(
+    instance: fn() -> &'static Dyn,
+) -> bool {
+    let entry = &Plugins::generic_lazy::<Entry<N, Dyn, Impl>>(|| {
+        Entry(Implementation::new::<Impl>(), PhantomData)
+    })
+    .0;
+    if entry.registered.swap(true, Ordering::AcqRel) {
+        return false;
+    }
+
+    entry.instance.store(instance as *mut (), Ordering::Release);
+    let head = &Plugins::generic_static::<Head<N, Dyn>>().0;
+    let mut next = head.load(Ordering::Relaxed);
+    loop {
+        entry.next.store(next, Ordering::Relaxed);
+        let new = entry as *const Implementation<Dyn> as *mut Implementation<Dyn>;
+        match head.compare_exchange_weak(next, new, Ordering::Release, Ordering::Relaxed) {
+            Ok(_) => return true,
+            Err(current) => next = current,
+        }
+    }
+}
+
+/// Iterates over the implementations of `Dyn` registered in namespace `N`.
+pub(crate) fn implementations<N: Namespace, Dyn: ?Sized + 'static>(
+) -> impl Iterator<Item = &'static Implementation<Dyn>> {
+    let head = Plugins::generic_static::<Head<N, Dyn>>()
+        .0
+        .load(Ordering::Acquire);
+    // SAFETY: The list only links implementations in generic statics, which are never freed.
+    iter::successors(unsafe { head.as_ref() }, |entry| unsafe {
+        entry.next.load(Ordering::Acquire).as_ref()
+    })
+}
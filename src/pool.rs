@@ -0,0 +1,197 @@
+//! Fixed-capacity object pools, see [`GenericPool`].
+
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    mem::{self, MaybeUninit},
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    sync::atomic::{AtomicBool, Ordering},
+    Zeroable,
+};
+
+/// A zero-initializable pool of up to `N` values of `T`, to allocate objects without a heap.
+///
+/// ```rust
+/// use generic_statics::{define_namespace, GenericPool, Namespace};
+///
+/// define_namespace!(Pools);
+///
+/// struct Order {
+///     id: u64,
+/// }
+///
+/// let pool = Pools::generic_static::<GenericPool<Order, 2>>();
+/// let a = pool.alloc(Order { id: 1 }).ok().unwrap();
+/// let b = pool.alloc(Order { id: 2 }).ok().unwrap();
+/// assert!(pool.alloc(Order { id: 3 }).is_err());
+///
+/// assert_eq!(a.id, 1);
+/// assert_eq!(b.free().id, 2);
+/// assert!(pool.alloc(Order { id: 3 }).is_ok());
+/// ```
+///
+/// Every slot has an atomic flag marking it as used, so allocating is lock-free and takes up to
+/// `N` compare-and-swaps. Values are owned by [`PoolBox`] handles, which return their slot to the
+/// pool when they are dropped (or [freed](PoolBox::free)).
+pub struct GenericPool<T, const N: usize> {
+    used: [AtomicBool; N],
+    slots: [UnsafeCell<MaybeUninit<T>>; N],
+}
+
+// SAFETY: A slot is only accessed through the `PoolBox` that claimed it, so the pool can be shared
+// between threads if values can be sent between them.
+unsafe impl<T: Send, const N: usize> Sync for GenericPool<T, N> {}
+
+unsafe impl<T, const N: usize> Zeroable for GenericPool<T, N> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            used: Zeroable::zeroed(),
+            slots: Zeroable::zeroed(),
+        }
+    }
+}
+
+impl<T, const N: usize> GenericPool<T, N> {
+    /// The number of values the pool can hold.
+    pub const CAPACITY: usize = N;
+
+    // Only used as array repeat operands in `new`, which copy the constants.
+    #[cfg(not(feature = "loom"))]
+    #[allow(clippy::declare_interior_mutable_const)]
+    const FREE: AtomicBool = AtomicBool::new(false);
+    #[cfg(not(feature = "loom"))]
+    #[allow(clippy::declare_interior_mutable_const)]
+    const UNINIT: UnsafeCell<MaybeUninit<T>> = UnsafeCell::new(MaybeUninit::uninit());
+
+    /// Creates an empty pool.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            used: [Self::FREE; N],
+            slots: [Self::UNINIT; N],
+        }
+    }
+
+    /// Moves `value` into a free slot, returning it back if the pool is full.
+    pub fn alloc(&self, value: T) -> Result<PoolBox<'_, T, N>, T> {
+        let Some(index) = self.claim() else {
+            return Err(value);
+        };
+
+        unsafe { (*self.slots[index].get()).write(value) };
+        Ok(PoolBox { pool: self, index })
+    }
+
+    /// Returns the number of allocated values.
+    ///
+    /// This checks every slot, and is only a snapshot if values are allocated concurrently.
+    pub fn len(&self) -> usize {
+        self.used
+            .iter()
+            .filter(|used| used.load(Ordering::Relaxed))
+            .count()
+    }
+
+    /// Returns whether no values are allocated, see [`GenericPool::len`].
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn claim(&self) -> Option<usize> {
+        self.used.iter().position(|used| {
+            !used.load(Ordering::Relaxed)
+                && used
+                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+        })
+    }
+}
+
+impl<T, const N: usize> Default for GenericPool<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for GenericPool<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenericPool")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+/// A value allocated from a [`GenericPool`], returned by [`GenericPool::alloc`].
+///
+/// The value is dropped and its slot returned to the pool when the box is dropped.
+pub struct PoolBox<'a, T, const N: usize> {
+    pool: &'a GenericPool<T, N>,
+    index: usize,
+}
+
+// SAFETY: The box owns its value like a `Box<T>`.
+unsafe impl<T: Send, const N: usize> Send for PoolBox<'_, T, N> {}
+unsafe impl<T: Sync, const N: usize> Sync for PoolBox<'_, T, N> {}
+
+impl<T, const N: usize> PoolBox<'_, T, N> {
+    /// Moves the value out and returns its slot to the pool.
+    pub fn free(self) -> T {
+        let value = unsafe { (*self.slot()).assume_init_read() };
+        self.release();
+        mem::forget(self);
+        value
+    }
+
+    /// The index of the slot holding the value.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    #[inline]
+    fn slot(&self) -> *mut MaybeUninit<T> {
+        self.pool.slots[self.index].get()
+    }
+
+    #[inline]
+    fn release(&self) {
+        self.pool.used[self.index].store(false, Ordering::Release);
+    }
+}
+
+impl<T, const N: usize> Deref for PoolBox<'_, T, N> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { (*self.slot()).assume_init_ref() }
+    }
+}
+
+impl<T, const N: usize> DerefMut for PoolBox<'_, T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { (*self.slot()).assume_init_mut() }
+    }
+}
+
+impl<T, const N: usize> Drop for PoolBox<'_, T, N> {
+    fn drop(&mut self) {
+        unsafe { (*self.slot()).assume_init_drop() };
+        self.release();
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for PoolBox<'_, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
@@ -0,0 +1,189 @@
+//! Bounded multi-producer queues, see [`GenericQueue`].
+
+use std::{cell::UnsafeCell, fmt, mem::MaybeUninit};
+
+use crate::{
+    sync::atomic::{AtomicUsize, Ordering},
+    CacheAligned, Zeroable,
+};
+
+/// A zero-initializable bounded queue of up to `N` values of `T`, e.g. to pass per-type events
+/// (log records or samples) from any number of producer threads to a consumer.
+///
+/// ```rust
+/// use generic_statics::{define_namespace, GenericQueue, Namespace};
+///
+/// define_namespace!(Events);
+///
+/// #[derive(Copy, Clone, Debug, PartialEq)]
+/// struct Sample(u32);
+///
+/// let queue = Events::generic_static::<GenericQueue<Sample, 4>>();
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| queue.push(Sample(1)).unwrap());
+///     scope.spawn(|| queue.push(Sample(2)).unwrap());
+/// });
+///
+/// let mut samples = vec![queue.pop().unwrap(), queue.pop().unwrap()];
+/// samples.sort_by_key(|sample| sample.0);
+/// assert_eq!(samples, [Sample(1), Sample(2)]);
+/// assert_eq!(queue.pop(), None);
+/// ```
+///
+/// This is a lock-free array queue with a sequence stamp per slot (after Dmitry Vyukov's bounded
+/// MPMC queue), so it is also safe with multiple consumers. The stamps are stored relative to
+/// their slot index, so the initial state of the queue is all zeroes. `N` must be a power of two
+/// and values are restricted to `Copy` types, so the queue never has to drop them.
+pub struct GenericQueue<T: Copy, const N: usize> {
+    /// The position of the next value to pop.
+    head: CacheAligned<AtomicUsize>,
+    /// The position of the next value to push.
+    tail: CacheAligned<AtomicUsize>,
+    slots: [Slot<T>; N],
+}
+
+struct Slot<T> {
+    /// The sequence number of the slot minus its index, the slot is ready to be written at
+    /// position `pos` if its sequence number is `pos` and to be read if it is `pos + 1`.
+    stamp: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+unsafe impl<T> Zeroable for Slot<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            stamp: AtomicUsize::zeroed(),
+            value: Zeroable::zeroed(),
+        }
+    }
+}
+
+// SAFETY: A slot's value is only accessed by the thread that claimed its position.
+unsafe impl<T: Copy + Send, const N: usize> Sync for GenericQueue<T, N> {}
+
+unsafe impl<T: Copy, const N: usize> Zeroable for GenericQueue<T, N> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            head: Zeroable::zeroed(),
+            tail: Zeroable::zeroed(),
+            slots: Zeroable::zeroed(),
+        }
+    }
+}
+
+impl<T: Copy, const N: usize> GenericQueue<T, N> {
+    /// The number of values the queue can hold.
+    pub const CAPACITY: usize = N;
+
+    // Only used as an array repeat operand in `new`, which copies the constant.
+    #[cfg(not(feature = "loom"))]
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY: Slot<T> = Slot {
+        stamp: AtomicUsize::new(0),
+        value: UnsafeCell::new(MaybeUninit::uninit()),
+    };
+
+    /// Creates an empty queue.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            head: CacheAligned::new(AtomicUsize::new(0)),
+            tail: CacheAligned::new(AtomicUsize::new(0)),
+            slots: [Self::EMPTY; N],
+        }
+    }
+
+    /// Pushes `value` to the back of the queue, returning it back if the queue is full.
+    pub fn push(&self, value: T) -> Result<(), T> {
+        const { assert!(N.is_power_of_two(), "the capacity must be a power of two") };
+
+        let mut pos = self.tail.0.load(Ordering::Relaxed);
+        loop {
+            let index = pos % N;
+            let slot = &self.slots[index];
+            let seq = slot.stamp.load(Ordering::Acquire).wrapping_add(index);
+            match seq.wrapping_sub(pos) as isize {
+                0 => match self.tail.0.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        unsafe { (*slot.value.get()).write(value) };
+                        let seq = pos.wrapping_add(1);
+                        slot.stamp.store(seq.wrapping_sub(index), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => pos = current,
+                },
+                diff if diff < 0 => return Err(value),
+                _ => pos = self.tail.0.load(Ordering::Relaxed),
+            }
+        }
+    }
+
+    /// Pops the value at the front of the queue, if any.
+    pub fn pop(&self) -> Option<T> {
+        const { assert!(N.is_power_of_two(), "the capacity must be a power of two") };
+
+        let mut pos = self.head.0.load(Ordering::Relaxed);
+        loop {
+            let index = pos % N;
+            let slot = &self.slots[index];
+            let seq = slot.stamp.load(Ordering::Acquire).wrapping_add(index);
+            match seq.wrapping_sub(pos.wrapping_add(1)) as isize {
+                0 => match self.head.0.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let value = unsafe { (*slot.value.get()).assume_init() };
+                        let seq = pos.wrapping_add(N);
+                        slot.stamp.store(seq.wrapping_sub(index), Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(current) => pos = current,
+                },
+                diff if diff < 0 => return None,
+                _ => pos = self.head.0.load(Ordering::Relaxed),
+            }
+        }
+    }
+
+    /// Returns the number of values in the queue.
+    ///
+    /// This is only a snapshot if values are pushed or popped concurrently.
+    pub fn len(&self) -> usize {
+        let head = self.head.0.load(Ordering::Relaxed);
+        let tail = self.tail.0.load(Ordering::Relaxed);
+        tail.wrapping_sub(head).min(N)
+    }
+
+    /// Returns whether the queue is empty, see [`GenericQueue::len`].
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Copy, const N: usize> Default for GenericQueue<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl<T: Copy, const N: usize> fmt::Debug for GenericQueue<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenericQueue")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
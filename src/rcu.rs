@@ -0,0 +1,221 @@
+//! Read-copy-update pointers, see [`GenericRcu`].
+
+use std::{fmt, marker::PhantomData, ops::Deref, ptr::NonNull};
+
+use crate::{
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+    Zeroable,
+};
+
+/// A zero-initializable read-copy-update pointer to a boxed `T` (empty when zeroed), e.g. for
+/// per-type configuration that is reloaded at runtime.
+///
+/// ```rust
+/// use generic_statics::{define_namespace, GenericRcu, Namespace};
+///
+/// define_namespace!(Config);
+///
+/// struct Limits {
+///     max_connections: u32,
+/// }
+///
+/// let limits = Config::generic_static::<GenericRcu<Limits>>();
+/// assert!(limits.read().is_none());
+///
+/// limits.replace(Limits { max_connections: 16 });
+/// let current = limits.read().unwrap();
+/// assert_eq!(current.max_connections, 16);
+/// drop(current);
+///
+/// let old = limits.replace(Limits { max_connections: 32 }).unwrap();
+/// assert_eq!(old.max_connections, 16);
+/// ```
+///
+/// Readers hold an [`RcuReadGuard`], which takes three atomic read-modify-write operations (to
+/// register with the current epoch and to leave it) and never waits for writers. Writers are
+/// serialized, publish the new value and then wait for a grace period: They advance the epoch and
+/// wait until the readers of the previous epoch (which might still see the old value) are done,
+/// so the old value is only returned once no reader can access it anymore. Therefore replacing
+/// the value while holding a read guard of the same pointer on the same thread deadlocks.
+pub struct GenericRcu<T> {
+    value: AtomicPtr<T>,
+    epoch: AtomicUsize,
+    /// The number of readers per epoch parity.
+    readers: [AtomicUsize; 2],
+    writer: AtomicBool,
+    _marker: PhantomData<Box<T>>,
+}
+
+// SAFETY: The value is shared between readers and moved out by writers, the pointer owns a
+// `Box<T>`.
+unsafe impl<T: Send + Sync> Sync for GenericRcu<T> {}
+unsafe impl<T: Send> Send for GenericRcu<T> {}
+
+unsafe impl<T> Zeroable for GenericRcu<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            value: AtomicPtr::zeroed(),
+            epoch: AtomicUsize::zeroed(),
+            readers: Zeroable::zeroed(),
+            writer: AtomicBool::zeroed(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> GenericRcu<T> {
+    /// Creates an empty pointer.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            value: AtomicPtr::new(std::ptr::null_mut()),
+            epoch: AtomicUsize::new(0),
+            readers: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            writer: AtomicBool::new(false),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a guard of the current value, or `None` if the pointer is empty.
+    ///
+    /// The value stays valid while the guard is held, even if it's replaced concurrently.
+    pub fn read(&self) -> Option<RcuReadGuard<'_, T>> {
+        let epoch = loop {
+            let epoch = self.epoch.load(Ordering::Relaxed);
+            self.readers[epoch % 2].fetch_add(1, Ordering::Relaxed);
+            // A writer that advanced the epoch in the meantime might not wait for this reader.
+            // This is a read-modify-write, so either it is ordered before the writer advancing
+            // the epoch (which then sees the registration), or it sees the new epoch (and the
+            // new value).
+            if self.epoch.fetch_add(0, Ordering::AcqRel) == epoch {
+                break epoch;
+            }
+            self.readers[epoch % 2].fetch_sub(1, Ordering::Release);
+        };
+
+        match NonNull::new(self.value.load(Ordering::Acquire)) {
+            Some(value) => Some(RcuReadGuard {
+                rcu: self,
+                epoch,
+                value,
+            }),
+            None => {
+                self.readers[epoch % 2].fetch_sub(1, Ordering::Release);
+                None
+            }
+        }
+    }
+
+    /// Returns whether the pointer is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.value.load(Ordering::Relaxed).is_null()
+    }
+
+    /// Replaces the value with `value`, returning the old value once no reader can access it
+    /// anymore.
+    pub fn replace(&self, value: T) -> Option<T> {
+        self.swap(Box::into_raw(Box::new(value)))
+    }
+
+    /// Takes the value, leaving the pointer empty, once no reader can access it anymore.
+    pub fn take(&self) -> Option<T> {
+        self.swap(std::ptr::null_mut())
+    }
+
+    fn swap(&self, new: *mut T) -> Option<T> {
+        while self
+            .writer
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            yield_now();
+        }
+        let _writer = Unlock(&self.writer);
+
+        let old = NonNull::new(self.value.swap(new, Ordering::AcqRel))?;
+        // Readers register with the epoch before loading the value, so the readers that might
+        // have loaded the old value are those of the previous epoch.
+        let epoch = self.epoch.fetch_add(1, Ordering::AcqRel);
+        while self.readers[epoch % 2].load(Ordering::Acquire) != 0 {
+            yield_now();
+        }
+        Some(*unsafe { Box::from_raw(old.as_ptr()) })
+    }
+}
+
+impl<T> Drop for GenericRcu<T> {
+    fn drop(&mut self) {
+        let value = self.value.load(Ordering::Relaxed);
+        if !value.is_null() {
+            drop(unsafe { Box::from_raw(value) });
+        }
+    }
+}
+
+impl<T> Default for GenericRcu<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GenericRcu<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("GenericRcu")
+            .field(&self.read().as_deref())
+            .finish()
+    }
+}
+
+/// Releases the writer lock of a [`GenericRcu`], also if the value panics while it's dropped.
+struct Unlock<'a>(&'a AtomicBool);
+
+impl Drop for Unlock<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// A read guard of the value of a [`GenericRcu`], returned by [`GenericRcu::read`].
+pub struct RcuReadGuard<'a, T> {
+    rcu: &'a GenericRcu<T>,
+    epoch: usize,
+    value: NonNull<T>,
+}
+
+// SAFETY: The guard only gives out shared references to the value.
+unsafe impl<T: Sync> Sync for RcuReadGuard<'_, T> {}
+
+impl<T> Deref for RcuReadGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T> Drop for RcuReadGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.rcu.readers[self.epoch % 2].fetch_sub(1, Ordering::Release);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RcuReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[inline]
+fn yield_now() {
+    #[cfg(feature = "loom")]
+    loom::thread::yield_now();
+    #[cfg(not(feature = "loom"))]
+    std::thread::yield_now();
+}
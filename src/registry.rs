@@ -0,0 +1,133 @@
+#[cfg(all(feature = "check-duplicates", debug_assertions))]
+use std::any;
+use std::{
+    any::TypeId,
+    collections::BTreeMap,
+    marker::PhantomData,
+    ptr::NonNull,
+    sync::{Mutex, PoisonError},
+};
+
+#[cfg(all(feature = "check-duplicates", debug_assertions))]
+use crate::sync::atomic::AtomicBool;
+use crate::{
+    sync::atomic::{AtomicPtr, Ordering},
+    Namespace, Zeroable,
+};
+
+/// Per-copy cache of the canonical address of the storage of `T` in namespace `N`.
+///
+/// This lives in its own generic static so that the registry lock is only taken on the first
+/// resolution of every storage copy.
+struct Canonical<N, T>(AtomicPtr<T>, PhantomData<fn() -> N>);
+
+unsafe impl<N, T> Zeroable for Canonical<N, T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(AtomicPtr::zeroed(), PhantomData)
+    }
+}
+
+struct Addr(NonNull<()>);
+
+// SAFETY: The address is only used as an opaque value by the registry.
+unsafe impl Send for Addr {}
+
+/// Process-wide map of `(namespace, type)` to the first address that was resolved for it.
+static CANONICAL: Mutex<BTreeMap<(TypeId, TypeId), Addr>> = Mutex::new(BTreeMap::new());
+
+/// Marks a storage copy of `T` in namespace `N` as checked by [`check_unique`].
+#[cfg(all(feature = "check-duplicates", debug_assertions))]
+struct Checked<N, T>(AtomicBool, PhantomData<fn() -> (N, T)>);
+
+#[cfg(all(feature = "check-duplicates", debug_assertions))]
+unsafe impl<N, T> Zeroable for Checked<N, T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(AtomicBool::zeroed(), PhantomData)
+    }
+}
+
+/// Map of `(namespace, type)` to the storage address of this linked image, used by
+/// [`check_unique`].
+#[cfg(all(feature = "check-duplicates", debug_assertions))]
+static RESOLVED: Mutex<BTreeMap<(TypeId, TypeId), Addr>> = Mutex::new(BTreeMap::new());
+
+/// Panics if another address has been resolved for `(N, T)` before.
+///
+/// Every storage copy is only checked on its first resolution.
+#[cfg(all(feature = "check-duplicates", debug_assertions))]
+pub(crate) fn check_unique<N: Namespace, T: 'static>(addr: NonNull<T>) {
+    let checked = unsafe { &crate::reserve_bookkeeping::<N, Checked<N, T>>().as_ref().0 };
+    if checked.load(Ordering::Acquire) {
+        return;
+    }
+
+    check_unique_uncached::<N, T>(addr);
+    checked.store(true, Ordering::Release);
+}
+
+#[cfg(all(feature = "check-duplicates", debug_assertions))]
+pub(crate) fn check_unique_uncached<N: 'static, T: 'static>(addr: NonNull<T>) {
+    let mut map = RESOLVED.lock().unwrap_or_else(PoisonError::into_inner);
+    let first = map
+        .entry((TypeId::of::<N>(), TypeId::of::<T>()))
+        .or_insert(Addr(addr.cast()))
+        .0;
+    drop(map);
+
+    assert_eq!(
+        first,
+        addr.cast(),
+        "generic static `{}` in namespace `{}` resolved to multiple addresses",
+        any::type_name::<T>(),
+        any::type_name::<N>(),
+    );
+}
+
+/// Returns the canonical address for `(N, T)`, registering `addr` if there is none yet.
+///
+/// The result is cached in the storage copy `addr` belongs to.
+pub(crate) fn canonicalize_cached<N: Namespace, T: 'static>(addr: NonNull<T>) -> NonNull<T> {
+    let cache = unsafe {
+        &crate::reserve_bookkeeping::<N, Canonical<N, T>>()
+            .as_ref()
+            .0
+    };
+
+    match NonNull::new(cache.load(Ordering::Acquire)) {
+        Some(addr) => addr,
+        None => {
+            let addr = canonicalize::<N, T>(addr);
+            cache.store(addr.as_ptr(), Ordering::Release);
+            addr
+        }
+    }
+}
+
+/// Returns the canonical address for `(N, T)`, registering `addr` if there is none yet.
+pub(crate) fn canonicalize<N: 'static, T: 'static>(addr: NonNull<T>) -> NonNull<T> {
+    // The loom backend hands out a single address per execution already, and its storage must
+    // not outlive the execution.
+    if cfg!(feature = "loom") {
+        return addr;
+    }
+
+    #[cfg(feature = "dylib-dedup")]
+    let addr = crate::dylib::canonicalize(TypeId::of::<N>(), TypeId::of::<T>(), addr.cast());
+    #[cfg(not(feature = "dylib-dedup"))]
+    let addr = get_or_insert_local(TypeId::of::<N>(), TypeId::of::<T>(), || addr.cast());
+
+    addr.cast()
+}
+
+/// Returns the canonical address for `(ns, ty)` of this linked image's registry, registering
+/// the result of `f` if there is none yet.
+pub(crate) fn get_or_insert_local(
+    ns: TypeId,
+    ty: TypeId,
+    f: impl FnOnce() -> NonNull<()>,
+) -> NonNull<()> {
+    let mut map = CANONICAL.lock().unwrap_or_else(PoisonError::into_inner);
+    map.entry((ns, ty)).or_insert_with(|| Addr(f())).0
+}
@@ -0,0 +1,40 @@
+//! Resetting the statics of a namespace to their initial value (`reset` feature), see
+//! [`Namespace::reset_all`](crate::Namespace::reset_all).
+
+use std::{marker::PhantomData, mem, ptr};
+
+use crate::{init::Storage, Namespace};
+
+/// Returns the function resetting the storage of `T`, `None` if it has no initial value.
+pub(crate) fn resetter<T: Storage>() -> Option<unsafe fn(*const ())> {
+    if T::NOINIT {
+        None
+    } else {
+        Some(reset::<T>)
+    }
+}
+
+/// Resets the storage of `T` at `addr` to its initial value.
+///
+/// # Safety
+///
+/// See [`Namespace::reset_all`](crate::Namespace::reset_all).
+unsafe fn reset<T: Storage>(addr: *const ()) {
+    let addr = addr.cast::<u8>().cast_mut();
+    if T::ZEROED {
+        ptr::write_bytes(addr, 0, mem::size_of::<T>());
+    } else {
+        // The words are laid out like the storage emitted from them (all supported targets are
+        // little-endian).
+        ptr::copy_nonoverlapping(T::WORDS.as_ptr().cast::<u8>(), addr, mem::size_of::<T>());
+    }
+}
+
+/// Resets the statics of `N` when dropped.
+pub(crate) struct ResetOnDrop<N: Namespace>(pub(crate) PhantomData<N>);
+
+impl<N: Namespace> Drop for ResetOnDrop<N> {
+    fn drop(&mut self) {
+        unsafe { N::reset_all() };
+    }
+}
@@ -0,0 +1,60 @@
+//! The shared resolver (`shared-resolver` feature), see [`resolve`].
+//!
+//! The accessors of every generic static are inlined into their callers, which only takes a
+//! single instruction to compute the address of the storage, but also inlines the bookkeeping of
+//! features like `dylib-dedup`, `freeze`, `check-duplicates` and `touched-statics` into every
+//! call site. With this backend, call sites pass a [`Resolver`] of the static to the
+//! non-generic [`resolve`] instead, so every instantiation only emits its storage, its
+//! descriptor and two small functions.
+
+use std::ptr::NonNull;
+
+use crate::{
+    init::Storage,
+    sync::atomic::{AtomicPtr, Ordering},
+    Namespace,
+};
+
+/// Describes how to resolve the storage of `T` in namespace `N` (see [`Resolver::of`]).
+pub(crate) struct Resolver {
+    /// Returns the address cache of the storage in this linked image.
+    cache: fn() -> &'static AtomicPtr<()>,
+    /// Resolves the storage on its first access in this linked image.
+    resolve: fn() -> NonNull<()>,
+}
+
+impl Resolver {
+    /// The resolver of the storage of `T` in namespace `N`, which is meant to be promoted to a
+    /// constant.
+    pub(crate) const fn of<N: Namespace, T: Storage>() -> Self {
+        Self {
+            cache: crate::cached::cache::<N, T>,
+            resolve: resolve_storage::<N, T>,
+        }
+    }
+}
+
+#[cold]
+fn resolve_storage<N: Namespace, T: Storage>() -> NonNull<()> {
+    crate::resolve_storage::<N, T>().cast()
+}
+
+/// Returns the address of the storage described by `resolver`, resolving it only on the first
+/// call in this linked image.
+///
+/// This costs a call and an indirect call (returning the address of the cache) compared to the
+/// inlined accessors.
+#[inline(never)]
+pub(crate) fn resolve(resolver: &'static Resolver) -> NonNull<()> {
+    let cache = (resolver.cache)();
+
+    // Pairs with the release store below, so the initialization of storage written by the
+    // resolution (e.g. `Init<T>` in freezable namespaces) is visible (see `crate::cached`).
+    if let Some(addr) = NonNull::new(cache.load(Ordering::Acquire)) {
+        return addr;
+    }
+
+    let addr = (resolver.resolve)();
+    cache.store(addr.as_ptr(), Ordering::Release);
+    addr
+}
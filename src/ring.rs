@@ -0,0 +1,212 @@
+//! Single-producer single-consumer ring buffers, see [`GenericRing`].
+
+use std::{cell::UnsafeCell, fmt, mem::MaybeUninit};
+
+use crate::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    CacheAligned, Zeroable,
+};
+
+/// A zero-initializable single-producer single-consumer ring buffer of up to `N` values of `T`,
+/// e.g. for per-type telemetry streams from an interrupt handler to a drain thread.
+///
+/// ```rust
+/// use generic_statics::{define_namespace, GenericRing, Namespace};
+///
+/// define_namespace!(Telemetry);
+///
+/// let ring = Telemetry::generic_static::<GenericRing<u16, 4>>();
+/// let mut producer = ring.producer().unwrap();
+/// let mut consumer = ring.consumer().unwrap();
+/// assert!(ring.producer().is_none());
+///
+/// producer.push(1).unwrap();
+/// producer.push(2).unwrap();
+/// assert_eq!(consumer.pop(), Some(1));
+/// assert_eq!(consumer.pop(), Some(2));
+/// assert_eq!(consumer.pop(), None);
+/// ```
+///
+/// The producer and the consumer side are claimed as [`RingProducer`] and [`RingConsumer`]
+/// handles, of which only one can exist at a time each (a side can be claimed again once its
+/// handle is dropped). Pushing and popping are wait-free and only take an atomic load and store.
+/// `N` must be a power of two and values are restricted to `Copy` types, so the ring never has to
+/// drop them.
+pub struct GenericRing<T: Copy, const N: usize> {
+    /// The position of the next value to pop.
+    head: CacheAligned<AtomicUsize>,
+    /// The position of the next value to push.
+    tail: CacheAligned<AtomicUsize>,
+    producer: AtomicBool,
+    consumer: AtomicBool,
+    slots: [UnsafeCell<MaybeUninit<T>>; N],
+}
+
+// SAFETY: Every slot is only accessed by the producer until it is published, and by the consumer
+// after that.
+unsafe impl<T: Copy + Send, const N: usize> Sync for GenericRing<T, N> {}
+
+unsafe impl<T: Copy, const N: usize> Zeroable for GenericRing<T, N> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            head: Zeroable::zeroed(),
+            tail: Zeroable::zeroed(),
+            producer: AtomicBool::zeroed(),
+            consumer: AtomicBool::zeroed(),
+            slots: Zeroable::zeroed(),
+        }
+    }
+}
+
+impl<T: Copy, const N: usize> GenericRing<T, N> {
+    /// The number of values the ring can hold.
+    pub const CAPACITY: usize = N;
+
+    // Only used as an array repeat operand in `new`, which copies the constant.
+    #[cfg(not(feature = "loom"))]
+    #[allow(clippy::declare_interior_mutable_const)]
+    const UNINIT: UnsafeCell<MaybeUninit<T>> = UnsafeCell::new(MaybeUninit::uninit());
+
+    /// Creates an empty ring.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            head: CacheAligned::new(AtomicUsize::new(0)),
+            tail: CacheAligned::new(AtomicUsize::new(0)),
+            producer: AtomicBool::new(false),
+            consumer: AtomicBool::new(false),
+            slots: [Self::UNINIT; N],
+        }
+    }
+
+    /// Claims the producer side, returning `None` if it is already claimed.
+    #[inline]
+    pub fn producer(&self) -> Option<RingProducer<'_, T, N>> {
+        const { assert!(N.is_power_of_two(), "the capacity must be a power of two") };
+
+        (!self.producer.swap(true, Ordering::Acquire)).then_some(RingProducer { ring: self })
+    }
+
+    /// Claims the consumer side, returning `None` if it is already claimed.
+    #[inline]
+    pub fn consumer(&self) -> Option<RingConsumer<'_, T, N>> {
+        const { assert!(N.is_power_of_two(), "the capacity must be a power of two") };
+
+        (!self.consumer.swap(true, Ordering::Acquire)).then_some(RingConsumer { ring: self })
+    }
+
+    /// Returns the number of values in the ring.
+    ///
+    /// This is only a snapshot if values are pushed or popped concurrently.
+    pub fn len(&self) -> usize {
+        let head = self.head.0.load(Ordering::Acquire);
+        let tail = self.tail.0.load(Ordering::Acquire);
+        tail.wrapping_sub(head).min(N)
+    }
+
+    /// Returns whether the ring is empty, see [`GenericRing::len`].
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    fn slot(&self, pos: usize) -> *mut MaybeUninit<T> {
+        self.slots[pos % N].get()
+    }
+}
+
+impl<T: Copy, const N: usize> Default for GenericRing<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl<T: Copy, const N: usize> fmt::Debug for GenericRing<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenericRing")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+/// The producer side of a [`GenericRing`], returned by [`GenericRing::producer`].
+pub struct RingProducer<'a, T: Copy, const N: usize> {
+    ring: &'a GenericRing<T, N>,
+}
+
+impl<T: Copy, const N: usize> RingProducer<'_, T, N> {
+    /// Pushes `value` to the back of the ring, returning it back if the ring is full.
+    #[inline]
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        let ring = self.ring;
+        let tail = ring.tail.0.load(Ordering::Relaxed);
+        if tail.wrapping_sub(ring.head.0.load(Ordering::Acquire)) == N {
+            return Err(value);
+        }
+
+        unsafe { (*ring.slot(tail)).write(value) };
+        ring.tail.0.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+}
+
+impl<T: Copy, const N: usize> Drop for RingProducer<'_, T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        self.ring.producer.store(false, Ordering::Release);
+    }
+}
+
+impl<T: Copy, const N: usize> fmt::Debug for RingProducer<'_, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingProducer").finish_non_exhaustive()
+    }
+}
+
+/// The consumer side of a [`GenericRing`], returned by [`GenericRing::consumer`].
+pub struct RingConsumer<'a, T: Copy, const N: usize> {
+    ring: &'a GenericRing<T, N>,
+}
+
+impl<T: Copy, const N: usize> RingConsumer<'_, T, N> {
+    /// Pops the value at the front of the ring, if any.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        let ring = self.ring;
+        let head = ring.head.0.load(Ordering::Relaxed);
+        if head == ring.tail.0.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let value = unsafe { (*ring.slot(head)).assume_init() };
+        ring.head.0.store(head.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+}
+
+impl<T: Copy, const N: usize> Iterator for RingConsumer<'_, T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
+
+impl<T: Copy, const N: usize> Drop for RingConsumer<'_, T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        self.ring.consumer.store(false, Ordering::Release);
+    }
+}
+
+impl<T: Copy, const N: usize> fmt::Debug for RingConsumer<'_, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingConsumer").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,263 @@
+//! A zero-initializable read-write lock.
+
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    futex,
+    sync::atomic::{AtomicU32, Ordering},
+    Zeroable,
+};
+
+/// The state of a write-locked lock, otherwise the state is the number of readers.
+const WRITE_LOCKED: u32 = u32::MAX;
+
+/// A read-write lock whose unlocked state is all zeroes, so it can be used as a generic static.
+///
+/// This is meant for read-mostly data, e.g. per-type registries that are resolved by many
+/// readers but rarely written to:
+///
+/// ```rust
+/// use generic_statics::{define_namespace, GenericRwLock, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// let lock = Test::generic_static::<GenericRwLock<[u32; 4]>>();
+/// lock.write()[0] = 1;
+/// assert_eq!(lock.read()[0], 1);
+/// ```
+///
+/// Blocked threads wait on the futex-like primitive of the OS, like [`GenericMutex`]. There is
+/// no fairness between readers and writers and the lock isn't poisoned if a thread panics while
+/// holding it.
+///
+/// [`GenericMutex`]: crate::GenericMutex
+pub struct GenericRwLock<T> {
+    state: AtomicU32,
+    /// Incremented on every unlock that might unblock other threads, which wait on it.
+    seq: AtomicU32,
+    /// The number of waiting threads.
+    waiters: AtomicU32,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: The value is shared by readers (`T: Sync`) and moved between writers (`T: Send`) only
+// while holding the lock.
+unsafe impl<T: Send + Sync> Sync for GenericRwLock<T> {}
+
+unsafe impl<T: Zeroable> Zeroable for GenericRwLock<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            state: AtomicU32::zeroed(),
+            seq: AtomicU32::zeroed(),
+            waiters: AtomicU32::zeroed(),
+            value: UnsafeCell::new(T::zeroed()),
+        }
+    }
+}
+
+impl<T> GenericRwLock<T> {
+    /// Creates a new, unlocked lock.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self {
+            state: AtomicU32::new(0),
+            seq: AtomicU32::new(0),
+            waiters: AtomicU32::new(0),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Acquires a shared lock, blocking the current thread until there is no writer.
+    #[inline]
+    pub fn read(&self) -> GenericRwLockReadGuard<'_, T> {
+        loop {
+            if let Some(guard) = self.try_read() {
+                return guard;
+            }
+            self.wait(|state| state >= WRITE_LOCKED - 1);
+        }
+    }
+
+    /// Acquires a shared lock if there is no writer.
+    #[inline]
+    pub fn try_read(&self) -> Option<GenericRwLockReadGuard<'_, T>> {
+        let mut state = self.state.load(Ordering::Relaxed);
+        // One below `WRITE_LOCKED` so the number of readers can't overflow into it.
+        while state < WRITE_LOCKED - 1 {
+            match self.state.compare_exchange_weak(
+                state,
+                state + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    return Some(GenericRwLockReadGuard {
+                        lock: self,
+                        _marker: PhantomData,
+                    })
+                }
+                Err(current) => state = current,
+            }
+        }
+        None
+    }
+
+    /// Acquires an exclusive lock, blocking the current thread until there are no readers or
+    /// writers.
+    #[inline]
+    pub fn write(&self) -> GenericRwLockWriteGuard<'_, T> {
+        loop {
+            if let Some(guard) = self.try_write() {
+                return guard;
+            }
+            self.wait(|state| state != 0);
+        }
+    }
+
+    /// Acquires an exclusive lock if there are no readers or writers.
+    #[inline]
+    pub fn try_write(&self) -> Option<GenericRwLockWriteGuard<'_, T>> {
+        self.state
+            .compare_exchange(0, WRITE_LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .ok()?;
+
+        Some(GenericRwLockWriteGuard {
+            lock: self,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns a mutable reference to the value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Consumes the lock, returning the value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    /// Waits for an unlock if the state is `blocked`.
+    #[cold]
+    fn wait(&self, blocked: impl Fn(u32) -> bool) {
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        // Unlocks change the state before incrementing `seq`, so either the state is observed as
+        // unblocked here or the wait returns as soon as `seq` has changed.
+        let seq = self.seq.load(Ordering::SeqCst);
+        if blocked(self.state.load(Ordering::SeqCst)) {
+            futex::wait(&self.seq, seq);
+        }
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Wakes the waiting threads after an unlock.
+    #[inline]
+    fn wake(&self) {
+        self.seq.fetch_add(1, Ordering::SeqCst);
+        if self.waiters.load(Ordering::SeqCst) != 0 {
+            futex::wake_all(&self.seq);
+        }
+    }
+}
+
+impl<T: Default> Default for GenericRwLock<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            state: AtomicU32::new(0),
+            seq: AtomicU32::new(0),
+            waiters: AtomicU32::new(0),
+            value: UnsafeCell::new(T::default()),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GenericRwLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("GenericRwLock");
+        match self.try_read() {
+            Some(guard) => d.field("data", &&*guard),
+            None => d.field("data", &format_args!("<locked>")),
+        };
+        d.finish_non_exhaustive()
+    }
+}
+
+/// The guard of a shared lock of a [`GenericRwLock`], which unlocks it on drop.
+#[must_use = "if unused the lock will immediately unlock"]
+pub struct GenericRwLockReadGuard<'a, T> {
+    lock: &'a GenericRwLock<T>,
+    /// Makes the guard `Send`/`Sync` like the `&T` it provides.
+    _marker: PhantomData<&'a T>,
+}
+
+impl<T> Deref for GenericRwLockReadGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> Drop for GenericRwLockReadGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.lock.state.fetch_sub(1, Ordering::Release) == 1 {
+            self.lock.wake();
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GenericRwLockReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// The guard of an exclusive lock of a [`GenericRwLock`], which unlocks it on drop.
+#[must_use = "if unused the lock will immediately unlock"]
+pub struct GenericRwLockWriteGuard<'a, T> {
+    lock: &'a GenericRwLock<T>,
+    /// Makes the guard `Send`/`Sync` like the `&mut T` it provides.
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<T> Deref for GenericRwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> DerefMut for GenericRwLockWriteGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T> Drop for GenericRwLockWriteGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.lock.state.store(0, Ordering::Release);
+        self.lock.wake();
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GenericRwLockWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
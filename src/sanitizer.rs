@@ -0,0 +1,34 @@
+//! Annotations that keep sanitizers from reporting false positives on the asm-reserved storage.
+//!
+//! The storage is emitted by inline assembly, so it is neither registered with AddressSanitizer's
+//! shadow memory nor known to be initialized by MemorySanitizer.
+
+use std::{ffi::c_void, mem};
+
+#[cfg(sanitize = "address")]
+extern "C" {
+    fn __asan_unpoison_memory_region(addr: *const c_void, size: usize);
+}
+
+#[cfg(sanitize = "memory")]
+extern "C" {
+    fn __msan_unpoison(addr: *const c_void, size: usize);
+}
+
+/// Marks the storage of `T` at `addr` as addressable and initialized.
+#[inline(always)]
+#[allow(unused_variables)]
+pub(crate) fn unpoison<T>(addr: *const T) {
+    let size = mem::size_of::<T>();
+    let addr = addr.cast::<c_void>();
+
+    #[cfg(sanitize = "address")]
+    unsafe {
+        __asan_unpoison_memory_region(addr, size);
+    }
+
+    #[cfg(sanitize = "memory")]
+    unsafe {
+        __msan_unpoison(addr, size);
+    }
+}
@@ -0,0 +1,109 @@
+//! Link sections of individual statics, see
+//! [`Namespace::generic_static_in`](crate::Namespace::generic_static_in).
+//!
+//! The statics placed into a section belong to a child namespace ([`InSection`]) of the
+//! namespace they are accessed through, which reserves its storage in that section. Like the
+//! sections of namespaces (see [`define_namespace`](crate::define_namespace)), the section name
+//! has to be part of the asm, so the storage is reserved by an expansion of [`define_section`] in
+//! the crate defining the marker.
+
+use std::{marker::PhantomData, ptr::NonNull};
+
+use crate::{init::Storage, Identity, Namespace, NamespaceInfo};
+
+/// Marker types naming a link section, defined with [`define_section`].
+///
+/// See [`Namespace::generic_static_in`].
+pub trait SectionName: 'static + Send + Sync + Copy {
+    /// The name of the section.
+    const SECTION: &'static str;
+
+    /// Reserves the storage of `T` in namespace `N` in this section.
+    #[doc(hidden)]
+    fn __reserve<N: Namespace, T: Storage>() -> NonNull<T>;
+}
+
+/// Defines a [`SectionName`] marker type.
+///
+/// ```rust
+/// use generic_statics::define_section;
+///
+/// define_section!(
+///     /// Battery-backed memory, placed by the linker script.
+///     pub BackupRam in ".backup_ram"
+/// );
+/// ```
+///
+/// The section name is passed to the assembler as is (see [`define_namespace`]), statics placed
+/// into the section are emitted as zero-fill (`@nobits`) section.
+///
+/// [`define_namespace`]: crate::define_namespace
+#[macro_export]
+macro_rules! define_section {
+    ($(#[$attr:meta])* $vis:vis $name:ident in $section:literal) => {
+        $(#[$attr])*
+        #[derive(Debug, Copy, Clone)]
+        $vis struct $name;
+
+        impl $crate::SectionName for $name {
+            const SECTION: &'static str = $section;
+
+            #[inline(always)]
+            fn __reserve<__N: $crate::Namespace, __T: $crate::__Storage>(
+            ) -> ::core::ptr::NonNull<__T> {
+                $crate::__reserve!(__N, __T, $section)
+            }
+        }
+    };
+}
+
+/// Fails compilation with a readable error if `section` can't be used as link section of the
+/// storage (see [`define_namespace`](crate::define_namespace)).
+///
+/// On COFF, the linker merges sections named `name$suffix` into the image section `name`, whose
+/// name is limited to 8 bytes in the image (`link.exe` truncates longer names).
+#[doc(hidden)]
+pub const fn check_section(section: &str) {
+    let section = section.as_bytes();
+    assert!(
+        !section.is_empty(),
+        "the name of a link section can't be empty"
+    );
+
+    #[cfg(target_os = "windows")]
+    {
+        let mut len = 0;
+        while len < section.len() && section[len] != b'$' {
+            len += 1;
+        }
+        assert!(
+            len <= 8,
+            "the name of a link section can have at most 8 bytes before its `$` suffix on Windows"
+        );
+    }
+}
+
+/// The namespace of the statics of `N` placed into section `S`.
+pub(crate) struct InSection<N, S>(PhantomData<fn() -> (N, S)>);
+
+impl<N, S> Clone for InSection<N, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N, S> Copy for InSection<N, S> {}
+
+unsafe impl<N: Namespace, S: SectionName> Namespace for InSection<N, S> {
+    const IDENTITY: Identity = N::IDENTITY;
+    const SALT: u64 = N::SALT;
+    const PARENT: Option<fn() -> NamespaceInfo> = Some(N::info);
+    const SECTION: Option<&'static str> = Some(S::SECTION);
+    const WEAK: bool = N::WEAK;
+
+    #[inline(always)]
+    fn __reserve<T: Storage>() -> NonNull<T> {
+        S::__reserve::<Self, T>()
+    }
+}
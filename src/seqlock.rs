@@ -0,0 +1,224 @@
+//! A zero-initializable sequence lock.
+
+use std::{cell::UnsafeCell, fmt, mem::MaybeUninit};
+
+use crate::{
+    futex,
+    sync::atomic::{self, AtomicU32, Ordering},
+    NoUninit, Zeroable,
+};
+
+/// A sequence lock whose initial state is all zeroes, so it can be used as a generic static.
+///
+/// Readers never block writers (or each other): a read copies the value and retries if a write
+/// happened concurrently, which makes this suitable for small, read-mostly values like sampling
+/// rates or feature flags:
+///
+/// ```rust
+/// use generic_statics::{define_namespace, GenericSeqLock, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// let rate = Test::generic_static::<GenericSeqLock<[u32; 2]>>();
+/// rate.write([1, 100]);
+/// rate.update(|[_, per]| *per = 1000);
+/// assert_eq!(rate.read(), [1, 1000]);
+/// ```
+///
+/// Writers are serialized, waiting on the futex-like primitive of the OS like [`GenericMutex`].
+/// The value is copied byte-wise with atomic loads and stores, which is why it must be
+/// [`NoUninit`].
+///
+/// [`GenericMutex`]: crate::GenericMutex
+pub struct GenericSeqLock<T> {
+    /// Odd while a write is in progress.
+    seq: AtomicU32,
+    /// The number of threads waiting for a write to finish.
+    waiters: AtomicU32,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: The value is only copied in and out atomically.
+unsafe impl<T: NoUninit + Send> Sync for GenericSeqLock<T> {}
+
+unsafe impl<T: Zeroable> Zeroable for GenericSeqLock<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self {
+            seq: AtomicU32::zeroed(),
+            waiters: AtomicU32::zeroed(),
+            value: UnsafeCell::new(T::zeroed()),
+        }
+    }
+}
+
+impl<T: NoUninit> GenericSeqLock<T> {
+    /// Creates a new sequence lock.
+    #[cfg(not(feature = "loom"))]
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self {
+            seq: AtomicU32::new(0),
+            waiters: AtomicU32::new(0),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Returns a copy of the value, retrying while it's being written.
+    #[inline]
+    pub fn read(&self) -> T {
+        loop {
+            let seq = self.seq.load(Ordering::Acquire);
+            if seq % 2 == 1 {
+                self.wait(seq);
+                continue;
+            }
+
+            let value = self.load();
+            atomic::fence(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == seq {
+                // SAFETY: The value wasn't written concurrently, so it isn't torn.
+                return unsafe { value.assume_init() };
+            }
+        }
+    }
+
+    /// Stores `value`.
+    #[inline]
+    pub fn write(&self, value: T) {
+        let seq = self.lock();
+        unsafe { self.store(value) };
+        self.unlock(seq);
+    }
+
+    /// Modifies the value with `f`, without other writes in between.
+    #[inline]
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let seq = self.lock();
+        // A panicking `f` leaves the value unchanged.
+        // SAFETY: Writers are serialized, so the value isn't torn.
+        let mut value = unsafe { self.load().assume_init() };
+        let guard = Unlock(self, seq);
+        f(&mut value);
+        unsafe { self.store(value) };
+        drop(guard);
+    }
+
+    /// Returns a mutable reference to the value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Consumes the sequence lock, returning the value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    /// Makes the sequence odd, returning the previous (even) one.
+    #[inline]
+    fn lock(&self) -> u32 {
+        let mut seq = self.seq.load(Ordering::Relaxed);
+        loop {
+            if seq % 2 == 1 {
+                self.wait(seq);
+                seq = self.seq.load(Ordering::Relaxed);
+                continue;
+            }
+
+            match self.seq.compare_exchange_weak(
+                seq,
+                seq.wrapping_add(1),
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(current) => seq = current,
+            }
+        }
+        // Orders the writes of the value after the odd sequence.
+        atomic::fence(Ordering::Release);
+        seq
+    }
+
+    #[inline]
+    fn unlock(&self, seq: u32) {
+        self.seq.store(seq.wrapping_add(2), Ordering::SeqCst);
+        if self.waiters.load(Ordering::SeqCst) != 0 {
+            futex::wake_all(&self.seq);
+        }
+    }
+
+    /// Waits for the write with the (odd) sequence `seq` to finish.
+    #[cold]
+    fn wait(&self, seq: u32) {
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        futex::wait(&self.seq, seq);
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Copies the value out byte-wise, which may race with a write.
+    ///
+    /// The result may be torn (and therefore an invalid `T`, e.g. a `char` made of the bytes of two
+    /// different values), so it may only be assumed initialized if the sequence was unchanged or
+    /// writers are locked out.
+    #[inline]
+    fn load(&self) -> MaybeUninit<T> {
+        let mut value = MaybeUninit::<T>::uninit();
+        let src = self.value.get().cast::<u8>();
+        let dst = value.as_mut_ptr().cast::<u8>();
+        for i in 0..std::mem::size_of::<T>() {
+            // SAFETY: The value is only written byte-wise with atomic stores while shared.
+            unsafe {
+                let byte = std::sync::atomic::AtomicU8::from_ptr(src.add(i));
+                dst.add(i).write(byte.load(Ordering::Relaxed));
+            }
+        }
+        value
+    }
+
+    /// Copies `value` in byte-wise.
+    ///
+    /// # Safety
+    ///
+    /// The sequence must be odd (locked by this thread).
+    #[inline]
+    unsafe fn store(&self, value: T) {
+        let src = (&value as *const T).cast::<u8>();
+        let dst = self.value.get().cast::<u8>();
+        for i in 0..std::mem::size_of::<T>() {
+            let byte = std::sync::atomic::AtomicU8::from_ptr(dst.add(i));
+            byte.store(src.add(i).read(), Ordering::Relaxed);
+        }
+    }
+}
+
+/// Unlocks a write on drop.
+struct Unlock<'a, T: NoUninit>(&'a GenericSeqLock<T>, u32);
+
+impl<T: NoUninit> Drop for Unlock<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.unlock(self.1);
+    }
+}
+
+impl<T: NoUninit + Default> Default for GenericSeqLock<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            seq: AtomicU32::new(0),
+            waiters: AtomicU32::new(0),
+            value: UnsafeCell::new(T::default()),
+        }
+    }
+}
+
+impl<T: NoUninit + fmt::Debug> fmt::Debug for GenericSeqLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenericSeqLock")
+            .field("data", &self.read())
+            .finish_non_exhaustive()
+    }
+}
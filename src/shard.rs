@@ -0,0 +1,63 @@
+//! Sharded statics, see
+//! [`Namespace::generic_static_sharded`](crate::Namespace::generic_static_sharded).
+
+#[cfg(not(target_os = "none"))]
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Returns the shard (out of `shards`) the current thread should use, see
+/// [`Namespace::generic_static_sharded`](crate::Namespace::generic_static_sharded).
+///
+/// On Linux, this is picked from the CPU the thread is running on, so threads on different CPUs
+/// tend to use different shards. Otherwise (and if the CPU can't be determined), every thread is
+/// assigned a shard round-robin on its first call. This is only a hint to avoid contention,
+/// threads might use the same shard and a thread might use different shards over time.
+///
+/// # Panics
+///
+/// Panics if `shards` is zero.
+#[inline]
+pub fn shard_index(shards: usize) -> usize {
+    assert!(shards > 0, "the number of shards must not be zero");
+
+    #[cfg(all(target_os = "linux", not(miri)))]
+    {
+        extern "C" {
+            fn sched_getcpu() -> std::ffi::c_int;
+        }
+
+        if let Ok(cpu) = usize::try_from(unsafe { sched_getcpu() }) {
+            return cpu % shards;
+        }
+    }
+
+    thread_index() % shards
+}
+
+/// Returns the index assigned to the current thread round-robin on its first call.
+#[cfg(not(target_os = "none"))]
+fn thread_index() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+    std::thread_local!(static INDEX: Cell<Option<usize>> = const { Cell::new(None) });
+
+    INDEX
+        .try_with(|index| match index.get() {
+            Some(index) => index,
+            None => {
+                let next = NEXT.fetch_add(1, Ordering::Relaxed);
+                index.set(Some(next));
+                next
+            }
+        })
+        // Threads that are exiting share the first shard.
+        .unwrap_or(0)
+}
+
+/// There's only a single thread (per CPU) without an OS.
+#[cfg(target_os = "none")]
+fn thread_index() -> usize {
+    0
+}
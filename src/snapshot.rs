@@ -0,0 +1,67 @@
+//! Byte-wise copies of the statics of a namespace (`snapshot` feature).
+
+use std::{fmt, mem::MaybeUninit, ptr};
+
+use crate::StaticDescriptor;
+
+/// A copy of the storage of the generic statics of a namespace, see
+/// [`Namespace::snapshot`](crate::Namespace::snapshot).
+pub struct Snapshot {
+    statics: Vec<(StaticDescriptor, Box<[MaybeUninit<u8>]>)>,
+}
+
+impl Snapshot {
+    /// Copies the storage of all `statics`.
+    ///
+    /// # Safety
+    ///
+    /// See [`Namespace::snapshot`](crate::Namespace::snapshot).
+    pub(crate) unsafe fn take(statics: impl Iterator<Item = StaticDescriptor>) -> Self {
+        let statics = statics
+            .map(|descriptor| {
+                let mut bytes = vec![MaybeUninit::uninit(); descriptor.size()].into_boxed_slice();
+                ptr::copy_nonoverlapping(
+                    descriptor.addr().cast::<MaybeUninit<u8>>(),
+                    bytes.as_mut_ptr(),
+                    descriptor.size(),
+                );
+                (descriptor, bytes)
+            })
+            .collect();
+        Self { statics }
+    }
+
+    /// Overwrites the storage of the statics with the copy taken by
+    /// [`Namespace::snapshot`](crate::Namespace::snapshot).
+    ///
+    /// A snapshot can be restored any number of times.
+    ///
+    /// # Safety
+    ///
+    /// No other thread may access the statics of the namespace during the restore, and the statics
+    /// must be valid with their previous bytes. In particular, values owning resources (e.g. the
+    /// value of a [`Namespace::generic_lazy`](crate::Namespace::generic_lazy) static) may be
+    /// duplicated or leaked.
+    pub unsafe fn restore(&self) {
+        for (descriptor, bytes) in &self.statics {
+            ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                descriptor.addr().cast::<MaybeUninit<u8>>().cast_mut(),
+                bytes.len(),
+            );
+        }
+    }
+
+    /// Returns the descriptors of the statics in this snapshot.
+    pub fn statics(&self) -> impl Iterator<Item = &StaticDescriptor> {
+        self.statics.iter().map(|(descriptor, _)| descriptor)
+    }
+}
+
+impl fmt::Debug for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Snapshot")
+            .field("statics", &self.statics.len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,99 @@
+//! Handles to generic statics, see [`Namespace::generic_static_ref`].
+
+use std::{
+    any, fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::Deref,
+    ptr,
+};
+
+use crate::{Namespace, Zeroable};
+
+/// A handle to the generic static for `T` in namespace `N`.
+///
+/// Unlike a plain `&'static T`, handles compare and hash by the address of the static and their
+/// [`Debug`](fmt::Debug) output names the static:
+///
+/// ```rust
+/// use std::sync::atomic::AtomicU32;
+/// use generic_statics::{define_namespace, GenericStaticRef, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// struct Metrics {
+///     requests: GenericStaticRef<Test, AtomicU32>,
+/// }
+///
+/// let metrics = Metrics {
+///     requests: Test::generic_static_ref(),
+/// };
+/// assert_eq!(metrics.requests, Test::generic_static_ref::<AtomicU32>());
+/// ```
+pub struct GenericStaticRef<N, T: 'static> {
+    value: &'static T,
+    _namespace: PhantomData<N>,
+}
+
+impl<N: Namespace, T: 'static + Zeroable + Sync> GenericStaticRef<N, T> {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self {
+            value: N::generic_static::<T>(),
+            _namespace: PhantomData,
+        }
+    }
+}
+
+impl<N, T> GenericStaticRef<N, T> {
+    /// Returns the reference to the static.
+    #[inline]
+    #[must_use]
+    pub fn get(self) -> &'static T {
+        self.value
+    }
+}
+
+impl<N, T> Clone for GenericStaticRef<N, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N, T> Copy for GenericStaticRef<N, T> {}
+
+impl<N, T> Deref for GenericStaticRef<N, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<N, T> PartialEq for GenericStaticRef<N, T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.value, other.value)
+    }
+}
+
+impl<N, T> Eq for GenericStaticRef<N, T> {}
+
+impl<N, T> Hash for GenericStaticRef<N, T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(self.value, state);
+    }
+}
+
+impl<N, T> fmt::Debug for GenericStaticRef<N, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenericStaticRef")
+            .field("namespace", &any::type_name::<N>())
+            .field("type", &any::type_name::<T>())
+            .field("addr", &(self.value as *const T))
+            .finish()
+    }
+}
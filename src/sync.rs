@@ -0,0 +1,12 @@
+//! Synchronization primitives used by this crate.
+//!
+//! With the `loom` feature enabled these are the [`loom`](https://docs.rs/loom) equivalents, so
+//! code built on top of generic statics can be model-checked by importing its atomics from here.
+
+/// Atomic types, either from [`std::sync::atomic`] or from `loom::sync::atomic`.
+pub mod atomic {
+    #[cfg(feature = "loom")]
+    pub use loom::sync::atomic::*;
+    #[cfg(not(feature = "loom"))]
+    pub use std::sync::atomic::*;
+}
@@ -0,0 +1,40 @@
+//! Dropping lazily initialized statics at process exit (`teardown` feature).
+
+use std::ffi::c_int;
+
+use crate::{lazy::Lazy, Namespace, Zeroable};
+
+/// Storage of a static that is dropped at exit, so it doesn't share the storage of
+/// [`Namespace::generic_lazy`].
+pub(crate) struct Teardown<T>(pub(crate) Lazy<T>);
+
+unsafe impl<T> Zeroable for Teardown<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(Lazy::zeroed())
+    }
+}
+
+extern "C" {
+    fn atexit(callback: extern "C" fn()) -> c_int;
+}
+
+/// Returns the static for `T` in namespace `N`, initializing it with `init` and registering it
+/// to be dropped at exit on first access.
+pub(crate) fn get_or_init<N: Namespace, T: 'static + Send + Sync>(init: fn() -> T) -> &'static T {
+    N::generic_static::<Teardown<T>>().0.get_or_init(|| {
+        let value = init();
+        // The value is stored right after this returns, which is before any exit handler runs.
+        assert_eq!(
+            unsafe { atexit(teardown::<N, T>) },
+            0,
+            "failed to register the teardown of a generic static"
+        );
+        value
+    })
+}
+
+/// Drops the static for `T` in namespace `N`, registered with `atexit`.
+extern "C" fn teardown<N: Namespace, T: 'static + Send + Sync>() {
+    drop(N::generic_static::<Teardown<T>>().0.take());
+}
@@ -0,0 +1,269 @@
+//! Thread-local storage, see
+//! [`Namespace::generic_static_thread_local`](crate::Namespace::generic_static_thread_local).
+//!
+//! The storage is emitted into the TLS template of the linked image (`.tbss` on ELF, a
+//! thread-local zero-fill section on Mach-O and `.tls$` on COFF), like the storage of a
+//! `#[thread_local]` static, and resolved with the general TLS access sequence of the target, which
+//! the linker relaxes in executables:
+//!
+//! - ELF `x86_64`: General dynamic (`__tls_get_addr`).
+//! - ELF `aarch64`: TLS descriptors.
+//! - Mach-O: Thread-local variable descriptors (`__tlv_bootstrap`).
+//! - COFF: The TLS index of the image (`_tls_index`) and the TLS array of the thread.
+//!
+//! The symbol and its deduplication are the same as for other storage (see [`reserve`]), but
+//! the storage is reserved for [`ThreadLocal<T>`] to keep it apart from the storage of `T`.
+//!
+//! [`reserve`]: crate::asm::reserve
+
+use std::ptr::NonNull;
+
+#[cfg(not(any(feature = "loom", miri)))]
+use crate::{
+    asm::{crate_static, storage_key, storage_size},
+    Identity,
+};
+use crate::{Namespace, Zeroable};
+
+/// The type the thread-local storage of `T` is reserved for.
+#[cfg(not(any(feature = "loom", miri)))]
+#[repr(transparent)]
+pub(crate) struct ThreadLocal<T>(T);
+
+#[cfg(not(any(feature = "loom", miri)))]
+unsafe impl<T: Zeroable> Zeroable for ThreadLocal<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(T::zeroed())
+    }
+}
+
+/// Emits the thread-local storage labeled with `$sym` and stores its address in the current
+/// thread in `$addr`.
+///
+/// The address is constant for the thread (like the address of a `#[thread_local]` static, which
+/// LLVM also treats as constant within a function), so the asm is `pure` and `nomem`.
+///
+/// `$operand`s must provide the `size` and `align` operands and the operands referenced by
+/// `$sym`.
+#[cfg(not(any(feature = "loom", miri)))]
+macro_rules! emit {
+    ($addr:ident, [$($sym:tt)*], $($operand:tt)*) => {
+        #[cfg(all(target_arch = "x86_64", any(target_os = "linux", target_os = "freebsd")))]
+        unsafe {
+            // The prefixes pad the sequence to the length the linker expects for relaxation.
+            std::arch::asm!(
+                concat!(".ifndef ", $($sym)*),
+                concat!(
+                    ".pushsection .tbss.", $($sym)*, ",\"awTG\",@nobits,", $($sym)*, ",comdat"
+                ),
+                concat!(".weak ", $($sym)*),
+                concat!(".hidden ", $($sym)*),
+                concat!(".type ", $($sym)*, ", @tls_object"),
+                concat!(".size ", $($sym)*, ", {size}"),
+                ".p2align {align}, 0",
+                concat!($($sym)*, ": .zero {size}"),
+                ".popsection",
+                ".endif",
+                ".byte 0x66",
+                concat!("lea rdi, [rip + ", $($sym)*, "@TLSGD]"),
+                ".byte 0x66, 0x66, 0x48",
+                "call __tls_get_addr@PLT",
+                $($operand)*
+                out("rax") $addr,
+                clobber_abi("C"),
+                options(pure, nomem),
+            );
+        }
+
+        // The TLS descriptor resolvers preserve every register but `x0` and `lr`.
+        #[cfg(all(target_arch = "aarch64", any(target_os = "linux", target_os = "freebsd")))]
+        unsafe {
+            std::arch::asm!(
+                concat!(".ifndef ", $($sym)*),
+                concat!(
+                    ".pushsection .tbss.", $($sym)*, ",\"awTG\",@nobits,", $($sym)*, ",comdat"
+                ),
+                concat!(".weak ", $($sym)*),
+                concat!(".hidden ", $($sym)*),
+                concat!(".type ", $($sym)*, ", @tls_object"),
+                concat!(".size ", $($sym)*, ", {size}"),
+                ".p2align {align}, 0",
+                concat!($($sym)*, ": .zero {size}"),
+                ".popsection",
+                ".endif",
+                concat!("adrp x0, :tlsdesc:", $($sym)*),
+                concat!("ldr x1, [x0, :tlsdesc_lo12:", $($sym)*, "]"),
+                concat!("add x0, x0, :tlsdesc_lo12:", $($sym)*),
+                concat!(".tlsdesccall ", $($sym)*),
+                "blr x1",
+                "mrs x1, tpidr_el0",
+                "add x0, x1, x0",
+                $($operand)*
+                out("x0") $addr,
+                out("x1") _,
+                out("x30") _,
+                options(pure, nomem, nostack),
+            );
+        }
+
+        // The initial value is labeled with a local symbol, so only the descriptor is
+        // deduplicated. The accessor of the descriptor preserves every register but `rax` (and
+        // `x0`, `x16`, `x17` and `lr` on `aarch64`).
+        #[cfg(all(
+            target_arch = "x86_64",
+            any(target_os = "macos", target_os = "ios", target_os = "tvos")
+        ))]
+        unsafe {
+            std::arch::asm!(
+                concat!(".ifndef ", $($sym)*),
+                concat!(".tbss ", $($sym)*, ".tlv.init, {size}, {align}"),
+                ".pushsection __DATA,__thread_vars,thread_local_variables",
+                concat!(".globl ", $($sym)*),
+                concat!(".weak_definition ", $($sym)*),
+                concat!(".private_extern ", $($sym)*),
+                concat!($($sym)*, ":"),
+                ".quad __tlv_bootstrap",
+                ".quad 0",
+                concat!(".quad ", $($sym)*, ".tlv.init"),
+                ".popsection",
+                ".endif",
+                concat!("mov rdi, qword ptr [rip + ", $($sym)*, "@TLVP]"),
+                "call qword ptr [rdi]",
+                $($operand)*
+                out("rax") $addr,
+                out("rdi") _,
+                options(pure, nomem),
+            );
+        }
+
+        #[cfg(all(
+            target_arch = "aarch64",
+            any(target_os = "macos", target_os = "ios", target_os = "tvos")
+        ))]
+        unsafe {
+            std::arch::asm!(
+                concat!(".ifndef ", $($sym)*),
+                concat!(".tbss ", $($sym)*, ".tlv.init, {size}, {align}"),
+                ".pushsection __DATA,__thread_vars,thread_local_variables",
+                concat!(".globl ", $($sym)*),
+                concat!(".weak_definition ", $($sym)*),
+                concat!(".private_extern ", $($sym)*),
+                concat!($($sym)*, ":"),
+                ".quad __tlv_bootstrap",
+                ".quad 0",
+                concat!(".quad ", $($sym)*, ".tlv.init"),
+                ".popsection",
+                ".endif",
+                concat!("adrp x0, ", $($sym)*, "@TLVPPAGE"),
+                concat!("ldr x0, [x0, ", $($sym)*, "@TLVPPAGEOFF]"),
+                "ldr x1, [x0]",
+                "blr x1",
+                $($operand)*
+                out("x0") $addr,
+                out("x1") _,
+                out("x16") _,
+                out("x17") _,
+                out("x30") _,
+                options(pure, nomem, nostack),
+            );
+        }
+
+        // The Intel syntax drops the `$` of grouped sections (see `reserve`). The storage is
+        // emitted into `.tls$`, which the linker places between the bounds of the TLS template
+        // (`.tls` and `.tls$ZZZ`).
+        #[cfg(all(target_arch = "x86_64", target_os = "windows"))]
+        unsafe {
+            std::arch::asm!(
+                concat!(".ifndef ", $($sym)*),
+                concat!(".pushsection .tls$,\"dw\",discard,", $($sym)*),
+                concat!(".globl ", $($sym)*),
+                ".p2align {align}, 0",
+                concat!($($sym)*, ": .zero {size}"),
+                ".popsection",
+                ".endif",
+                "movl _tls_index(%rip), %eax",
+                "movq %gs:0x58, %rcx",
+                "movq (%rcx,%rax,8), %rcx",
+                concat!("leaq ", $($sym)*, "@SECREL32(%rcx), %rax"),
+                $($operand)*
+                out("rax") $addr,
+                out("rcx") _,
+                options(att_syntax, pure, nomem, nostack, preserves_flags),
+            );
+        }
+    };
+}
+
+/// Returns the address of the thread-local storage of `T` in namespace `N` in the current
+/// thread.
+#[cfg(not(any(feature = "loom", miri)))]
+#[inline]
+pub(crate) fn reserve<N: Namespace, T: 'static + Zeroable>() -> NonNull<T> {
+    #[cfg(target_os = "windows")]
+    const {
+        assert!(
+            std::mem::align_of::<T>() <= 16,
+            "thread-local generic statics can't be aligned to more than 16 bytes on Windows"
+        )
+    };
+
+    #[allow(unused_assignments)]
+    let mut addr: *mut T = std::ptr::null_mut();
+    match N::IDENTITY {
+        Identity::Process => {
+            emit!(
+                addr,
+                [crate::__storage!(process)],
+                size = const { storage_size::<ThreadLocal<T>>() },
+                align = const { std::mem::align_of::<T>().ilog2() },
+                key = const { storage_key::<N, ThreadLocal<T>>() },
+            );
+        }
+        Identity::Crate => {
+            emit!(
+                addr,
+                [crate::__storage!(crate)],
+                size = const { storage_size::<ThreadLocal<T>>() },
+                align = const { std::mem::align_of::<T>().ilog2() },
+                id = sym crate_static::<N, ThreadLocal<T>>,
+            );
+        }
+    }
+    NonNull::new(addr).expect("unsupported platform")
+}
+
+/// Returns the address of the thread-local storage of `T` in namespace `N` in the current
+/// thread, allocated on the first access in every thread (loom and Miri backends).
+#[cfg(any(feature = "loom", miri))]
+pub(crate) fn reserve<N: Namespace, T: 'static + Zeroable>() -> NonNull<T> {
+    use std::{
+        any::{Any, TypeId},
+        cell::RefCell,
+        collections::BTreeMap,
+        mem::ManuallyDrop,
+    };
+
+    // Like the storage of the asm backend, the values aren't dropped when the thread exits.
+    struct Slots(RefCell<BTreeMap<(TypeId, TypeId), Box<dyn Any>>>);
+
+    impl Drop for Slots {
+        fn drop(&mut self) {
+            // The values of local keys have to be dropped before their storage is freed.
+            crate::local_key::run_destructors();
+        }
+    }
+
+    #[cfg(feature = "loom")]
+    loom::thread_local!(static SLOTS: Slots = Slots(RefCell::new(BTreeMap::new())));
+    #[cfg(not(feature = "loom"))]
+    std::thread_local!(static SLOTS: Slots = Slots(RefCell::new(BTreeMap::new())));
+
+    SLOTS.with(|slots| {
+        let mut slots = slots.0.borrow_mut();
+        let slot = slots
+            .entry((TypeId::of::<N>(), TypeId::of::<T>()))
+            .or_insert_with(|| Box::new(ManuallyDrop::new(T::zeroed())));
+        NonNull::from(&**slot.downcast_ref::<ManuallyDrop<T>>().unwrap())
+    })
+}
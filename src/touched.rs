@@ -0,0 +1,144 @@
+//! Runtime registry of the accessed generic statics (`touched-statics` feature).
+//!
+//! The first resolution of every `(namespace, type)` pair pushes a [`Node`] onto a global
+//! intrusive list. The node lives in a generic static of its own (in a private namespace, so it
+//! isn't frozen with a freezable namespace), keyed by the pair.
+//!
+//! The first access hook of a namespace (see [`Namespace::set_first_access_hook`]) is invoked
+//! once the node is pushed, after logging the access with the `tracing`, `log` and `defmt`
+//! features.
+
+use std::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    mem,
+    mem::MaybeUninit,
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicBool, AtomicPtr, Ordering},
+};
+
+use crate::{descriptor, init::Storage, Namespace, StaticDescriptor, Zeroable};
+
+/// An entry of the list of accessed statics.
+struct Node {
+    next: AtomicPtr<Node>,
+    touched: AtomicBool,
+    /// Written once by the thread setting `touched`, before the node is published.
+    descriptor: UnsafeCell<MaybeUninit<StaticDescriptor>>,
+}
+
+/// Storage of the node of `T` in namespace `N`.
+#[repr(transparent)]
+struct NodeOf<N, T>(Node, PhantomData<fn() -> (N, T)>);
+
+unsafe impl<N, T> Zeroable for NodeOf<N, T> {}
+
+/// Storage of the first access hook of namespace `N`, a `fn(&'static StaticDescriptor)` (or
+/// null).
+#[repr(transparent)]
+struct Hook<N>(AtomicPtr<()>, PhantomData<fn() -> N>);
+
+unsafe impl<N> Zeroable for Hook<N> {}
+
+/// Sets the first access hook of namespace `N`.
+pub(crate) fn set_hook<N: Namespace>(hook: fn(&'static StaticDescriptor)) {
+    let slot = unsafe { &crate::reserve_bookkeeping::<N, Hook<N>>().as_ref().0 };
+    slot.store(hook as *mut (), Ordering::Release);
+}
+
+/// The most recently pushed node.
+static HEAD: AtomicPtr<Node> = AtomicPtr::new(ptr::null_mut());
+
+/// Records the first access to the storage of `T` in namespace `N` at `addr`.
+#[inline]
+pub(crate) fn touch<N: Namespace, T: Storage>(addr: NonNull<T>) {
+    let node = unsafe { &crate::reserve_bookkeeping::<N, NodeOf<N, T>>().as_ref().0 };
+    if !node.touched.load(Ordering::Relaxed) {
+        push::<N, T>(node, addr.cast());
+    }
+}
+
+#[cold]
+fn push<N: Namespace, T: Storage>(node: &'static Node, addr: NonNull<()>) {
+    if node.touched.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    unsafe {
+        (*node.descriptor.get())
+            .write(descriptor::describe::<N, T>().at(addr.as_ptr().cast_const()));
+    }
+
+    let node = node as *const Node as *mut Node;
+    let mut head = HEAD.load(Ordering::Relaxed);
+    loop {
+        unsafe { (*node).next.store(head, Ordering::Relaxed) };
+        match HEAD.compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed) {
+            Ok(_) => break,
+            Err(current) => head = current,
+        }
+    }
+
+    let descriptor = unsafe { &*(*node).descriptor.get().cast::<StaticDescriptor>() };
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        target: "generic_statics",
+        namespace = descriptor.namespace().name(),
+        type_name = descriptor.type_name(),
+        size = descriptor.size(),
+        addr = ?descriptor.addr(),
+        "first access to generic static",
+    );
+    #[cfg(feature = "log")]
+    log::debug!(
+        target: "generic_statics",
+        "first access to generic static `{}` in namespace `{}` ({} bytes at {:p})",
+        descriptor.type_name(),
+        descriptor.namespace().name(),
+        descriptor.size(),
+        descriptor.addr(),
+    );
+
+    #[cfg(feature = "defmt")]
+    defmt::debug!(
+        "first access to generic static `{=str}` in namespace `{=str}` ({=usize} bytes at {})",
+        descriptor.type_name(),
+        descriptor.namespace().name(),
+        descriptor.size(),
+        descriptor.addr(),
+    );
+
+    let hook = unsafe { &crate::reserve_bookkeeping::<N, Hook<N>>().as_ref().0 };
+    let hook = hook.load(Ordering::Acquire);
+    if !hook.is_null() {
+        let hook = unsafe { mem::transmute::<*mut (), fn(&'static StaticDescriptor)>(hook) };
+        hook(descriptor);
+    }
+}
+
+/// Returns the descriptors of the generic statics accessed so far, most recently accessed first.
+///
+/// ```rust
+/// use generic_statics::{define_namespace, Namespace};
+///
+/// define_namespace!(Plugins);
+///
+/// struct Audio;
+///
+/// let _ = Plugins::counter::<Audio>();
+/// assert!(generic_statics::touched_statics()
+///     .any(|s| s.namespace() == Plugins::info() && s.type_name().contains("Audio")));
+/// ```
+///
+/// Only accesses through the accessors of this linked image are recorded (with the
+/// `dylib-dedup` feature, each image records the statics it accessed).
+pub fn touched_statics() -> impl Iterator<Item = StaticDescriptor> {
+    let mut node = HEAD.load(Ordering::Acquire);
+    std::iter::from_fn(move || {
+        let current = unsafe { node.as_ref()? };
+        node = current.next.load(Ordering::Relaxed);
+        // Published nodes have been described.
+        Some(unsafe { (*current.descriptor.get()).assume_init() })
+    })
+}
@@ -0,0 +1,30 @@
+//! Dense per-type indices, see [`Namespace::type_index`].
+//!
+//! The index of every type is stored in a generic static of its own (in a private namespace, so
+//! it isn't frozen with a freezable namespace), keyed by the namespace and the type. The next free
+//! index of a namespace is a counter in the same private namespace.
+
+use std::{marker::PhantomData, sync::atomic::Ordering};
+
+use crate::{define_namespace, Namespace};
+
+define_namespace!(Indices);
+
+/// Storage of the index of `T` in namespace `N`.
+struct Index<N, T>(usize, PhantomData<fn() -> (N, T)>);
+
+/// Returns the index of `T` in namespace `N`, assigning the next free index on first use.
+#[inline]
+pub(crate) fn index<N: Namespace, T: 'static>() -> usize {
+    Indices::generic_lazy::<Index<N, T>>(|| {
+        let index = Indices::counter::<N>().fetch_add(1, Ordering::Relaxed);
+        Index(index as usize, PhantomData)
+    })
+    .0
+}
+
+/// Returns the number of indices assigned in namespace `N`.
+#[inline]
+pub(crate) fn count<N: Namespace>() -> usize {
+    Indices::counter::<N>().load(Ordering::Relaxed) as usize
+}
@@ -0,0 +1,169 @@
+//! Heterogeneous maps keyed by type, see [`TypeMap`].
+//!
+//! A map stores its values in a vector indexed by the dense per-type indices of its namespace
+//! (see [`Namespace::type_index`]), so lookups don't hash.
+
+use std::{any::Any, fmt, marker::PhantomData};
+
+use crate::{Global, Namespace};
+
+/// A map holding at most one value of every type, like a `HashMap<TypeId, Box<dyn Any>>`.
+///
+/// ```rust
+/// use generic_statics::TypeMap;
+///
+/// struct Config {
+///     verbose: bool,
+/// }
+///
+/// let mut map = TypeMap::new();
+/// map.insert(Config { verbose: true });
+/// map.insert(42u32);
+///
+/// assert!(map.get::<Config>().unwrap().verbose);
+/// assert_eq!(map.insert(7u32), Some(42));
+/// assert_eq!(map.remove::<u32>(), Some(7));
+/// assert_eq!(map.get::<u64>(), None);
+/// ```
+///
+/// The values are stored in a vector indexed by [`Namespace::type_index`] of namespace `N`,
+/// which is resolved through a generic static instead of hashing the `TypeId`. So a map's memory
+/// grows with the number of types indexed in its namespace. Libraries should use a namespace
+/// of their own, so their maps aren't sized by the types used by other crates.
+pub struct TypeMap<N: Namespace = Global> {
+    values: Vec<Option<Box<dyn Any + Send + Sync>>>,
+    len: usize,
+    _namespace: PhantomData<N>,
+}
+
+impl TypeMap {
+    /// Creates an empty map in the [`Global`] namespace.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_namespace()
+    }
+}
+
+impl<N: Namespace> TypeMap<N> {
+    /// Creates an empty map in namespace `N`.
+    ///
+    /// ```rust
+    /// use generic_statics::{define_namespace, TypeMap};
+    ///
+    /// define_namespace!(Extensions);
+    ///
+    /// let mut map = TypeMap::<Extensions>::with_namespace();
+    /// map.insert("value");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_namespace() -> Self {
+        Self {
+            values: Vec::new(),
+            len: 0,
+            _namespace: PhantomData,
+        }
+    }
+
+    /// Returns the value of type `T`, if any.
+    #[inline]
+    pub fn get<T: 'static + Send + Sync>(&self) -> Option<&T> {
+        self.values
+            .get(N::type_index::<T>())?
+            .as_ref()?
+            .downcast_ref()
+    }
+
+    /// Returns a mutable reference to the value of type `T`, if any.
+    #[inline]
+    pub fn get_mut<T: 'static + Send + Sync>(&mut self) -> Option<&mut T> {
+        self.values
+            .get_mut(N::type_index::<T>())?
+            .as_mut()?
+            .downcast_mut()
+    }
+
+    /// Returns whether the map contains a value of type `T`.
+    #[inline]
+    pub fn contains<T: 'static + Send + Sync>(&self) -> bool {
+        self.get::<T>().is_some()
+    }
+
+    /// Inserts `value`, returning the previous value of type `T`, if any.
+    pub fn insert<T: 'static + Send + Sync>(&mut self, value: T) -> Option<T> {
+        let previous = self.slot::<T>().replace(Box::new(value));
+        match previous {
+            Some(previous) => Some(*previous.downcast().expect("value of another type")),
+            None => {
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    /// Returns the value of type `T`, inserting the value returned by `f` if there is none.
+    pub fn get_or_insert_with<T: 'static + Send + Sync>(
+        &mut self,
+        f: impl FnOnce() -> T,
+    ) -> &mut T {
+        let slot = self.slot::<T>();
+        if slot.is_none() {
+            *slot = Some(Box::new(f()));
+            self.len += 1;
+        }
+
+        self.get_mut().expect("inserted")
+    }
+
+    /// Removes and returns the value of type `T`, if any.
+    pub fn remove<T: 'static + Send + Sync>(&mut self) -> Option<T> {
+        let value = self.values.get_mut(N::type_index::<T>())?.take()?;
+        self.len -= 1;
+        Some(*value.downcast().expect("value of another type"))
+    }
+
+    /// Returns the number of values in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the map contains no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all values.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.len = 0;
+    }
+
+    /// Returns the slot of `T`, growing the map if necessary.
+    fn slot<T: 'static>(&mut self) -> &mut Option<Box<dyn Any + Send + Sync>> {
+        let index = N::type_index::<T>();
+        if index >= self.values.len() {
+            self.values.resize_with(index + 1, || None);
+        }
+
+        &mut self.values[index]
+    }
+}
+
+impl<N: Namespace> Default for TypeMap<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_namespace()
+    }
+}
+
+impl<N: Namespace> fmt::Debug for TypeMap<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeMap")
+            .field("namespace", &std::any::type_name::<N>())
+            .field("len", &self.len)
+            .finish()
+    }
+}
@@ -0,0 +1,135 @@
+//! Startup verification of the asm-reserved storage (`verify-at-startup` feature).
+//!
+//! Every storage definition emitted by [`crate::asm`] also emits an [`Entry`] into a dedicated
+//! section, keyed by a hash of its `(namespace, type)` pair. A constructor walks these entries
+//! before `main` and aborts if any pair ended up with more than one storage location (e.g. due to
+//! type name collisions).
+//!
+//! Only storage of [`Identity::Process`](crate::Identity::Process) namespaces is registered, and
+//! only the storage of the linked image running the constructor is verified.
+
+use std::{process, ptr, slice};
+
+/// A registered storage definition.
+#[repr(C)]
+pub(crate) struct Entry {
+    /// The hash of the `(namespace, type)` pair, `0` for padding and sentinels.
+    pub(crate) key: u64,
+    /// The address of the storage.
+    pub(crate) addr: *const u8,
+}
+
+// SAFETY: Entries are never mutated.
+unsafe impl Sync for Entry {}
+
+impl Entry {
+    #[allow(dead_code)]
+    const SENTINEL: Self = Self {
+        key: 0,
+        addr: ptr::null(),
+    };
+}
+
+#[cfg(any(target_os = "none", target_os = "linux", target_os = "freebsd"))]
+mod section {
+    use super::Entry;
+
+    extern "C" {
+        #[link_name = "__start_generic_statics_registry"]
+        pub(super) static START: Entry;
+        #[link_name = "__stop_generic_statics_registry"]
+        pub(super) static STOP: Entry;
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos"))]
+mod section {
+    use super::Entry;
+
+    // Makes sure the section (and therefore its bounds) exists even without any storage.
+    #[used]
+    #[link_section = "__DATA,__gs_registry,regular,no_dead_strip"]
+    static ANCHOR: Entry = Entry::SENTINEL;
+
+    extern "C" {
+        #[link_name = "\x01section$start$__DATA$__gs_registry"]
+        pub(super) static START: Entry;
+        #[link_name = "\x01section$end$__DATA$__gs_registry"]
+        pub(super) static STOP: Entry;
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod section {
+    use super::Entry;
+
+    // The linker sorts the `.gsreg$*` sections by their suffix, so the entries (`$m`) end up
+    // between these bounds.
+    #[used]
+    #[link_section = ".gsreg$a"]
+    pub(super) static START: Entry = Entry::SENTINEL;
+    #[used]
+    #[link_section = ".gsreg$z"]
+    pub(super) static STOP: Entry = Entry::SENTINEL;
+}
+
+/// Returns all entries registered in this linked image.
+pub(crate) fn entries() -> &'static [Entry] {
+    // Makes sure the section (and therefore its bounds) exists even without any storage. This
+    // has to live in the same object file as the references to the bounds.
+    #[cfg(any(target_os = "none", target_os = "linux", target_os = "freebsd"))]
+    unsafe {
+        std::arch::asm!(
+            ".pushsection generic_statics_registry,\"awR\",@progbits",
+            ".p2align 3",
+            ".quad 0",
+            ".quad 0",
+            ".popsection",
+            options(nomem, nostack, preserves_flags),
+        );
+    }
+
+    let start = ptr::addr_of!(section::START);
+    let stop = ptr::addr_of!(section::STOP);
+
+    unsafe { slice::from_raw_parts(start, stop.offset_from(start) as usize) }
+}
+
+/// Aborts if any `(namespace, type)` pair has more than one storage location.
+extern "C" fn verify() {
+    let mut entries = entries()
+        .iter()
+        .filter(|entry| entry.key != 0)
+        .map(|entry| (entry.key, entry.addr))
+        .collect::<Vec<_>>();
+    entries.sort_unstable();
+    entries.dedup();
+
+    let mut split = false;
+    for pair in entries.windows(2) {
+        if pair[0].0 == pair[1].0 {
+            eprintln!(
+                "generic-statics: generic static {:#018x} has multiple storage locations ({:p} and \
+                 {:p})",
+                pair[0].0, pair[0].1, pair[1].1,
+            );
+            split = true;
+        }
+    }
+
+    if split {
+        process::abort();
+    }
+}
+
+#[used]
+#[cfg_attr(
+    any(target_os = "none", target_os = "linux", target_os = "freebsd"),
+    link_section = ".init_array"
+)]
+#[cfg_attr(
+    any(target_os = "macos", target_os = "ios", target_os = "tvos"),
+    link_section = "__DATA,__mod_init_func"
+)]
+#[cfg_attr(target_os = "windows", link_section = ".CRT$XCU")]
+static VERIFY: extern "C" fn() = verify;
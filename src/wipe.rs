@@ -0,0 +1,50 @@
+//! Securely zeroing statics (`zeroize` feature), see [`Namespace::wipe`](crate::Namespace::wipe).
+
+use std::{ffi::c_int, marker::PhantomData};
+
+use crate::{
+    sync::atomic::{AtomicBool, Ordering},
+    Namespace, Zeroable,
+};
+
+/// Marks the static for `T` as registered to be wiped at exit, so the handler is only registered
+/// once.
+pub(crate) struct ZeroizeOnExit<T>(AtomicBool, PhantomData<fn() -> T>);
+
+unsafe impl<T> Zeroable for ZeroizeOnExit<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(AtomicBool::zeroed(), PhantomData)
+    }
+}
+
+extern "C" {
+    fn atexit(callback: extern "C" fn()) -> c_int;
+}
+
+/// Zeroes the static for `T` in namespace `N` with writes that aren't optimized away.
+///
+/// # Safety
+///
+/// See [`Namespace::wipe`](crate::Namespace::wipe).
+pub(crate) unsafe fn wipe<N: Namespace, T: 'static + Zeroable>() {
+    let value = unsafe { N::generic_static_not_sync::<T>() };
+    // SAFETY: All zeroes is a valid `T` and the caller guarantees exclusive access.
+    unsafe { zeroize::zeroize_flat_type((value as *const T).cast_mut()) };
+}
+
+/// Registers the static for `T` in namespace `N` to be wiped at exit.
+pub(crate) fn zeroize_on_exit<N: Namespace, T: 'static + Zeroable>() {
+    let registered = &N::generic_static::<ZeroizeOnExit<T>>().0;
+    if !registered.swap(true, Ordering::AcqRel) {
+        assert_eq!(
+            unsafe { atexit(wipe_at_exit::<N, T>) },
+            0,
+            "failed to register the wiping of a generic static"
+        );
+    }
+}
+
+extern "C" fn wipe_at_exit<N: Namespace, T: 'static + Zeroable>() {
+    unsafe { wipe::<N, T>() };
+}
@@ -1,10 +1,21 @@
+#[cfg(feature = "bytemuck")]
+use std::ops::{Deref, DerefMut};
 use std::{
-    cell::UnsafeCell,
-    marker::PhantomData,
-    mem::{ManuallyDrop, MaybeUninit},
-    sync::atomic::{
-        AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicIsize, AtomicPtr, AtomicU16,
-        AtomicU32, AtomicU64, AtomicU8, AtomicUsize,
+    cell::{Cell, SyncUnsafeCell, UnsafeCell},
+    marker::{PhantomData, PhantomPinned},
+    mem::{self, ManuallyDrop, MaybeUninit},
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Saturating, Wrapping,
+    },
+    ptr::NonNull,
+    rc::Rc,
+    sync::{
+        atomic::{
+            AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicIsize, AtomicPtr,
+            AtomicU16, AtomicU32, AtomicU64, AtomicU8, AtomicUsize,
+        },
+        Arc,
     },
 };
 
@@ -22,7 +33,106 @@ use std::{
 /// Integral types (`i32`, `i64`, ...), and some other type that fulfill the above safety
 /// requirements have built-in impls that are provided by this crate.
 ///
-pub unsafe trait Zeroable: Sized {}
+/// Types of these crates are supported behind cargo features of the same name:
+///
+/// - [`portable-atomic`](https://docs.rs/portable-atomic): All atomics (including `AtomicU128`,
+///   `AtomicF32` and `AtomicF64`), so targets without native wide atomics can store per-type
+///   atomic state:
+///
+/// ```rust
+/// # #[cfg(feature = "portable-atomic")]
+/// # {
+/// use generic_statics::{define_namespace, Namespace};
+/// use portable_atomic::{AtomicU128, Ordering};
+///
+/// define_namespace!(Test);
+///
+/// Test::generic_static::<AtomicU128>().fetch_add(1, Ordering::Relaxed);
+/// # }
+/// ```
+///
+/// - [`crossbeam-utils`](https://docs.rs/crossbeam-utils): `CachePadded<T>` and `AtomicCell<T>`.
+/// - [`parking_lot`](https://docs.rs/parking_lot): `Mutex<T>`, `RwLock<T>` and `Once`, whose
+///   unlocked (or incomplete) states are all zeroes.
+/// - [`spin`](https://docs.rs/spin): `Mutex<T>`, `RwLock<T>` and `Once<T>`.
+/// - [`once_cell`](https://docs.rs/once_cell): `race::OnceBox<T>`, `race::OnceNonZeroUsize` and
+///   `race::OnceBool`, e.g. for per-type lazily initialized values:
+///
+/// ```rust
+/// # #[cfg(feature = "once_cell")]
+/// # {
+/// use generic_statics::{define_namespace, Namespace};
+/// use once_cell::race::OnceBox;
+///
+/// define_namespace!(Test);
+///
+/// let config = Test::generic_static::<OnceBox<String>>().get_or_init(|| Box::new("".into()));
+/// # }
+/// ```
+///
+/// - [`arc-swap`](https://docs.rs/arc-swap): `ArcSwapOption<T>`, whose empty state is all zeroes
+///   (see [`Namespace::arc_swap`](crate::Namespace::arc_swap)).
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used as a generic static because it isn't `Zeroable`",
+    label = "`{Self}` doesn't implement `Zeroable`",
+    note = "generic statics are zero-initialized, so their type has to be valid as all zeroes",
+    note = "implement `generic_statics::Zeroable` if all zeroes is a valid `{Self}`, or wrap it in \
+            `MaybeUninit`"
+)]
+pub unsafe trait Zeroable: Sized {
+    /// Returns the all-zeroes value of `Self`.
+    ///
+    /// Implementations only need to override this if they wrap types whose zero value has to be
+    /// constructed at runtime (e.g. `loom` atomics).
+    #[inline]
+    #[must_use]
+    fn zeroed() -> Self {
+        unsafe { mem::zeroed() }
+    }
+}
+
+/// Implements [`Zeroable`] for `#[repr(transparent)]` newtypes by delegating to the wrapped type.
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicU32, AtomicUsize};
+/// use generic_statics::impl_zeroable_transparent;
+///
+/// #[repr(transparent)]
+/// struct Counter(AtomicUsize);
+///
+/// #[repr(transparent)]
+/// struct Flags(pub AtomicU32);
+///
+/// impl_zeroable_transparent!(Counter(AtomicUsize), Flags(AtomicU32));
+/// ```
+///
+/// The newtype must be a tuple struct whose only field is of the given (`Zeroable`) type, which is
+/// checked at compile time together with its layout.
+#[macro_export]
+macro_rules! impl_zeroable_transparent {
+    ($($name:ident($inner:ty)),+ $(,)?) => {
+        $(
+            const _: () = ::core::assert!(
+                ::core::mem::size_of::<$name>() == ::core::mem::size_of::<$inner>()
+                    && ::core::mem::align_of::<$name>() == ::core::mem::align_of::<$inner>(),
+                ::core::concat!(
+                    "`",
+                    ::core::stringify!($name),
+                    "` doesn't have the same layout as `",
+                    ::core::stringify!($inner),
+                    "`"
+                ),
+            );
+
+            unsafe impl $crate::Zeroable for $name {
+                #[inline]
+                fn zeroed() -> Self {
+                    $name(<$inner as $crate::Zeroable>::zeroed())
+                }
+            }
+        )+
+    };
+}
 
 macro_rules! impl_integers {
     ($t:ty) => {
@@ -35,6 +145,7 @@ macro_rules! impl_integers {
 }
 
 impl_integers!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, isize, usize, bool);
+impl_integers!(f32, f64, char, (), PhantomPinned);
 
 impl_integers!(
     AtomicBool,
@@ -50,22 +161,114 @@ impl_integers!(
     AtomicUsize
 );
 
+// `None` is guaranteed to be represented as zero.
+impl_integers!(
+    Option<NonZeroI8>,
+    Option<NonZeroI16>,
+    Option<NonZeroI32>,
+    Option<NonZeroI64>,
+    Option<NonZeroI128>,
+    Option<NonZeroIsize>,
+    Option<NonZeroU8>,
+    Option<NonZeroU16>,
+    Option<NonZeroU32>,
+    Option<NonZeroU64>,
+    Option<NonZeroU128>,
+    Option<NonZeroUsize>
+);
+
 unsafe impl<T> Zeroable for AtomicPtr<T> {}
 
 unsafe impl<T: Sized> Zeroable for *const T {}
 unsafe impl<T: Sized> Zeroable for *mut T {}
 
+// The null pointer optimization guarantees that `None` is represented as zero.
+unsafe impl<T: Sized> Zeroable for Option<&T> {}
+unsafe impl<T: Sized> Zeroable for Option<&mut T> {}
+unsafe impl<T: Sized> Zeroable for Option<NonNull<T>> {}
+unsafe impl<T: Sized> Zeroable for Option<Box<T>> {}
+unsafe impl<T: Sized> Zeroable for Option<Arc<T>> {}
+unsafe impl<T: Sized> Zeroable for Option<Rc<T>> {}
+
+macro_rules! impl_fn_ptrs {
+    () => {
+        impl_fn_ptrs!(@impl);
+    };
+    ($t1:ident $(, $tr:ident)*) => {
+        impl_fn_ptrs!(@impl $t1 $(, $tr)*);
+        impl_fn_ptrs!($($tr),*);
+    };
+    (@impl $($t:ident),*) => {
+        unsafe impl<R, $($t),*> Zeroable for Option<fn($($t),*) -> R> {}
+        unsafe impl<R, $($t),*> Zeroable for Option<unsafe fn($($t),*) -> R> {}
+        unsafe impl<R, $($t),*> Zeroable for Option<extern "C" fn($($t),*) -> R> {}
+        unsafe impl<R, $($t),*> Zeroable for Option<unsafe extern "C" fn($($t),*) -> R> {}
+    };
+}
+
+impl_fn_ptrs!(A, B, C, D, E, F, G, H);
+
 unsafe impl<T> Zeroable for MaybeUninit<T> {}
 
-unsafe impl<T: Zeroable> Zeroable for ManuallyDrop<T> {}
-unsafe impl<T: Zeroable> Zeroable for UnsafeCell<T> {}
+unsafe impl<T: Zeroable> Zeroable for ManuallyDrop<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        ManuallyDrop::new(T::zeroed())
+    }
+}
+
+unsafe impl<T: Zeroable> Zeroable for UnsafeCell<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        UnsafeCell::new(T::zeroed())
+    }
+}
+
+unsafe impl<T: Zeroable> Zeroable for SyncUnsafeCell<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        SyncUnsafeCell::new(T::zeroed())
+    }
+}
+
+unsafe impl<T: Zeroable> Zeroable for Cell<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Cell::new(T::zeroed())
+    }
+}
+
+unsafe impl<T: Zeroable> Zeroable for Wrapping<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Wrapping(T::zeroed())
+    }
+}
+
+unsafe impl<T: Zeroable> Zeroable for Saturating<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Saturating(T::zeroed())
+    }
+}
+
 unsafe impl<T: ?Sized> Zeroable for PhantomData<T> {}
 
-unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {}
+unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {
+    #[inline]
+    fn zeroed() -> Self {
+        std::array::from_fn(|_| T::zeroed())
+    }
+}
 
 macro_rules! impl_tuples {
     ($t1:ident) => {
-        unsafe impl<$t1: Zeroable> Zeroable for ($t1,) {}
+        unsafe impl<$t1: Zeroable> Zeroable for ($t1,) {
+            #[inline]
+            fn zeroed() -> Self {
+                ($t1::zeroed(),)
+            }
+        }
     };
     ($t1:ident, $($tr:ident),+) => {
         impl_tuples!(@impl $t1, $($tr),+);
@@ -75,8 +278,217 @@ macro_rules! impl_tuples {
         unsafe impl<$($t),+> Zeroable for ($($t),+)
         where
             $($t: Zeroable),+
-        {}
+        {
+            #[inline]
+            fn zeroed() -> Self {
+                ($($t::zeroed()),+)
+            }
+        }
     };
 }
 
 impl_tuples!(A, B, C, D, E, F, G, H);
+
+/// Wraps a [`bytemuck::Zeroable`] type to make it [`Zeroable`].
+///
+/// ```rust
+/// use generic_statics::{define_namespace, ByteZeroed, Namespace};
+///
+/// define_namespace!(Test);
+///
+/// #[derive(Clone, Copy)]
+/// struct Point {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// unsafe impl bytemuck::Zeroable for Point {}
+///
+/// let point = Test::generic_static::<ByteZeroed<Point>>();
+/// assert_eq!(point.x, 0.0);
+/// ```
+#[cfg(feature = "bytemuck")]
+#[derive(Debug, Default, Copy, Clone)]
+#[repr(transparent)]
+pub struct ByteZeroed<T>(pub T);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl<T: bytemuck::Zeroable> Zeroable for ByteZeroed<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self(bytemuck::Zeroable::zeroed())
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T> Deref for ByteZeroed<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T> DerefMut for ByteZeroed<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "portable-atomic")]
+impl_integers!(
+    portable_atomic::AtomicBool,
+    portable_atomic::AtomicI8,
+    portable_atomic::AtomicI16,
+    portable_atomic::AtomicI32,
+    portable_atomic::AtomicI64,
+    portable_atomic::AtomicI128,
+    portable_atomic::AtomicIsize,
+    portable_atomic::AtomicU8,
+    portable_atomic::AtomicU16,
+    portable_atomic::AtomicU32,
+    portable_atomic::AtomicU64,
+    portable_atomic::AtomicU128,
+    portable_atomic::AtomicUsize,
+    portable_atomic::AtomicF32,
+    portable_atomic::AtomicF64
+);
+
+#[cfg(feature = "portable-atomic")]
+unsafe impl<T> Zeroable for portable_atomic::AtomicPtr<T> {}
+
+#[cfg(feature = "crossbeam-utils")]
+unsafe impl<T: Zeroable> Zeroable for crossbeam_utils::CachePadded<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new(T::zeroed())
+    }
+}
+
+#[cfg(feature = "crossbeam-utils")]
+unsafe impl<T: Zeroable> Zeroable for crossbeam_utils::atomic::AtomicCell<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new(T::zeroed())
+    }
+}
+
+#[cfg(feature = "parking_lot")]
+unsafe impl<T: Zeroable> Zeroable for parking_lot::Mutex<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new(T::zeroed())
+    }
+}
+
+#[cfg(feature = "parking_lot")]
+unsafe impl<T: Zeroable> Zeroable for parking_lot::RwLock<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new(T::zeroed())
+    }
+}
+
+#[cfg(feature = "parking_lot")]
+unsafe impl Zeroable for parking_lot::Once {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "spin")]
+unsafe impl<T: Zeroable> Zeroable for spin::Mutex<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new(T::zeroed())
+    }
+}
+
+#[cfg(feature = "spin")]
+unsafe impl<T: Zeroable> Zeroable for spin::RwLock<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new(T::zeroed())
+    }
+}
+
+#[cfg(feature = "spin")]
+unsafe impl<T> Zeroable for spin::Once<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "once_cell")]
+unsafe impl<T> Zeroable for once_cell::race::OnceBox<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "once_cell")]
+unsafe impl Zeroable for once_cell::race::OnceNonZeroUsize {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "once_cell")]
+unsafe impl Zeroable for once_cell::race::OnceBool {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "arc-swap")]
+unsafe impl<T> Zeroable for arc_swap::ArcSwapOption<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::const_empty()
+    }
+}
+
+#[cfg(feature = "loom")]
+macro_rules! impl_loom_atomics {
+    ($($t:ident = $zero:expr),+ $(,)?) => {
+        $(
+            unsafe impl Zeroable for loom::sync::atomic::$t {
+                #[inline]
+                fn zeroed() -> Self {
+                    Self::new($zero)
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "loom")]
+impl_loom_atomics!(
+    AtomicBool = false,
+    AtomicI16 = 0,
+    AtomicI32 = 0,
+    AtomicI64 = 0,
+    AtomicI8 = 0,
+    AtomicIsize = 0,
+    AtomicU16 = 0,
+    AtomicU32 = 0,
+    AtomicU64 = 0,
+    AtomicU8 = 0,
+    AtomicUsize = 0,
+);
+
+#[cfg(feature = "loom")]
+unsafe impl<T> Zeroable for loom::sync::atomic::AtomicPtr<T> {
+    #[inline]
+    fn zeroed() -> Self {
+        Self::new(std::ptr::null_mut())
+    }
+}
@@ -0,0 +1,41 @@
+#![cfg(not(feature = "loom"))]
+
+use std::{env, fs};
+
+use generic_statics::{define_namespace, Namespace, MAX_PAGE_SIZE};
+use object::{Object, ObjectSection, ObjectSymbol};
+
+define_namespace!(
+    #[unsafe(identity(process))]
+    Test
+);
+
+/// The alignment of the storage raises the alignment of its section (the `IMAGE_SCN_ALIGN_*`
+/// flags on COFF), so page-aligned storage stays page-aligned in the linked binary.
+#[test]
+fn page_aligned_storage_aligns_its_section() {
+    let buffer = Test::generic_static_page_aligned::<[u8; 10]>();
+    assert_eq!(buffer.as_ptr() as usize % MAX_PAGE_SIZE, 0);
+
+    let data = fs::read(env::current_exe().unwrap()).unwrap();
+    let file = object::File::parse(&*data).unwrap();
+
+    // The storage symbol ends with its size and (log2) alignment.
+    let suffix = format!(".{MAX_PAGE_SIZE}.{}", MAX_PAGE_SIZE.ilog2());
+    let symbol = file
+        .symbols()
+        .find(|symbol| {
+            symbol.name().is_ok_and(|name| {
+                name.trim_start_matches('_').starts_with("generic_statics.")
+                    && name.ends_with(&suffix)
+            })
+        })
+        .expect("the storage symbol wasn't found");
+    assert_eq!(symbol.address() % MAX_PAGE_SIZE as u64, 0);
+
+    let section = file
+        .section_by_index(symbol.section_index().unwrap())
+        .unwrap();
+    assert!(section.align() >= MAX_PAGE_SIZE as u64);
+    assert_eq!(section.address() % MAX_PAGE_SIZE as u64, 0);
+}
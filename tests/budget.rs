@@ -0,0 +1,39 @@
+#![cfg(all(not(feature = "loom"), target_os = "linux"))]
+
+use std::{env, fs};
+
+use generic_statics::{__namespace_key, define_namespace, ConstInit, Namespace};
+use object::{Object, ObjectSection};
+
+define_namespace!(
+    #[budget(64)]
+    Budgeted
+);
+
+struct Limit(u32);
+impl ConstInit for Limit {
+    const INIT: Self = Limit(5);
+}
+
+/// Every static kept in the image takes up its size (at least 4 bytes) in the budget section, the
+/// bookkeeping of the crate isn't accounted.
+#[test]
+fn statics_are_accounted() {
+    assert_eq!(Budgeted::BUDGET, Some(64));
+    assert_eq!(Budgeted::generic_static::<[u64; 3]>(), &[0; 3]);
+    assert_eq!(*Budgeted::generic_static::<u16>(), 0);
+    assert_eq!(*Budgeted::generic_static_strict::<u16>(), 0);
+    assert_eq!(*Budgeted::generic_static_cached::<u16>(), 0);
+    assert_eq!(Budgeted::generic_static_init::<Limit>().0, 5);
+
+    let data = fs::read(env::current_exe().unwrap()).unwrap();
+    let file = object::File::parse(&*data).unwrap();
+    let section = file
+        .section_by_name(&format!(
+            "generic_statics_budget_{}",
+            __namespace_key::<Budgeted>()
+        ))
+        .unwrap();
+    assert_eq!(section.size(), 24 + 4 + 4);
+    assert_eq!(section.address(), 0);
+}
@@ -0,0 +1,87 @@
+#![cfg(all(target_os = "windows", not(feature = "loom")))]
+
+//! Checks where the linker (`link.exe`, `lld-link` or GNU ld for MinGW) placed the storage in the
+//! linked image, as the sections of executables usually have no symbols.
+
+use std::{
+    env, fs,
+    ptr::addr_of,
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+};
+
+use generic_statics::{define_namespace, define_section, ConstInit, Namespace};
+use object::{Object, ObjectSection};
+
+define_namespace!(Test);
+define_namespace!(Custom in ".gstest$m");
+define_section!(Grouped in ".gstest$b");
+
+extern "C" {
+    static __ImageBase: u8;
+}
+
+/// Returns the name and the address (relative to the image base) of the image section containing
+/// `addr`.
+fn section_of<T>(addr: *const T) -> (String, u64) {
+    let rva = (addr as usize - addr_of!(__ImageBase) as usize) as u64;
+
+    let data = fs::read(env::current_exe().unwrap()).unwrap();
+    let file = object::File::parse(&*data).unwrap();
+    let base = file.relative_address_base();
+    let section = file
+        .sections()
+        .find(|section| {
+            let start = section.address() - base;
+            (start..start + section.size()).contains(&rva)
+        })
+        .expect("the address isn't part of the image");
+
+    (section.name().unwrap().to_owned(), section.address() - base)
+}
+
+/// The storage is grouped into the image sections of ordinary statics of the same kind.
+#[test]
+fn default_sections_are_merged() {
+    static ZEROED: AtomicU64 = AtomicU64::new(0);
+    static INITIALIZED: AtomicU32 = AtomicU32::new(5);
+
+    struct Limit(AtomicU32);
+    impl ConstInit for Limit {
+        const INIT: Self = Limit(AtomicU32::new(5));
+    }
+
+    let zeroed = Test::generic_static::<AtomicU64>();
+    let initialized = Test::generic_static_init::<Limit>();
+    assert_eq!(initialized.0.load(Ordering::Relaxed), 5);
+
+    assert_eq!(section_of(zeroed).0, section_of(&ZEROED).0);
+    assert_eq!(section_of(initialized).0, section_of(&INITIALIZED).0);
+}
+
+/// Alignments up to the section alignment of the image are honored.
+#[test]
+fn page_aligned_storage() {
+    let aligned = Test::generic_static_aligned::<u8, 4096>();
+    assert_eq!(aligned as *const u8 as usize % 4096, 0);
+
+    let page = Test::generic_static_page_aligned::<[u8; 10]>();
+    assert_eq!(page.as_ptr() as usize % 4096, 0);
+}
+
+/// Custom sections are merged by the part before `$`, ordered by the suffix.
+#[test]
+fn custom_sections_are_grouped() {
+    let custom = Custom::generic_static::<[u64; 4]>();
+    let grouped = Test::generic_static_in::<[u64; 4], Grouped>();
+
+    let (name, start) = section_of(custom);
+    assert_eq!(name, ".gstest");
+    assert_eq!(section_of(grouped), (name, start));
+    assert!(grouped.as_ptr() < custom.as_ptr());
+}
+
+#[test]
+#[should_panic = "at most 8 bytes"]
+fn long_section_names_are_rejected() {
+    generic_statics::__check_section(".generic_statics$m");
+}
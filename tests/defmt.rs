@@ -0,0 +1,41 @@
+//! Only this test binary defines a `defmt` global logger, run it with
+//! `DEFMT_LOG=debug cargo test --features defmt --test defmt`.
+
+#![cfg(all(feature = "defmt", not(feature = "loom")))]
+
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use generic_statics::{define_namespace, Namespace};
+
+static WRITTEN: AtomicUsize = AtomicUsize::new(0);
+
+#[defmt::global_logger]
+struct Recorder;
+
+unsafe impl defmt::Logger for Recorder {
+    fn acquire() {}
+
+    unsafe fn flush() {}
+
+    unsafe fn release() {}
+
+    unsafe fn write(bytes: &[u8]) {
+        WRITTEN.fetch_add(bytes.len(), Ordering::Relaxed);
+    }
+}
+
+defmt::timestamp!("{=u32}", 0);
+
+#[test]
+fn first_access() {
+    define_namespace!(Logged);
+
+    let _ = Logged::generic_static::<AtomicU32>();
+    let written = WRITTEN.load(Ordering::Relaxed);
+    if option_env!("DEFMT_LOG").is_some() {
+        assert!(written > 0);
+    }
+
+    let _ = Logged::generic_static::<AtomicU32>();
+    assert_eq!(WRITTEN.load(Ordering::Relaxed), written);
+}
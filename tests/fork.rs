@@ -0,0 +1,54 @@
+#![cfg(all(unix, not(any(feature = "loom", miri))))]
+
+use std::{
+    ffi::c_int,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use generic_statics::{define_namespace, Namespace};
+
+define_namespace!(Test);
+
+extern "C" {
+    fn fork() -> c_int;
+    fn waitpid(pid: c_int, status: *mut c_int, options: c_int) -> c_int;
+    fn _exit(status: c_int) -> !;
+}
+
+static PREPARE: AtomicU32 = AtomicU32::new(0);
+static PARENT: AtomicU32 = AtomicU32::new(0);
+static CHILD: AtomicU32 = AtomicU32::new(0);
+
+#[test]
+fn hooks() {
+    Test::on_fork_prepare(|| {
+        PREPARE.fetch_add(1, Ordering::Relaxed);
+    });
+    Test::on_fork_parent(|| {
+        PARENT.fetch_add(1, Ordering::Relaxed);
+    });
+    // Child hooks run in order of registration.
+    Test::on_fork_child(|| {
+        CHILD.store(1, Ordering::Relaxed);
+    });
+    Test::on_fork_child(|| {
+        CHILD.fetch_add(10, Ordering::Relaxed);
+    });
+
+    let pid = unsafe { fork() };
+    assert!(pid >= 0);
+    if pid == 0 {
+        let ok = PREPARE.load(Ordering::Relaxed) == 1
+            && PARENT.load(Ordering::Relaxed) == 0
+            && CHILD.load(Ordering::Relaxed) == 11;
+        unsafe { _exit(if ok { 0 } else { 1 }) };
+    }
+
+    let mut status = 0;
+    assert_eq!(unsafe { waitpid(pid, &mut status, 0) }, pid);
+    assert_eq!(status, 0);
+
+    assert_eq!(PREPARE.load(Ordering::Relaxed), 1);
+    assert_eq!(PARENT.load(Ordering::Relaxed), 1);
+    assert_eq!(CHILD.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,44 @@
+#![cfg(all(feature = "freeze", not(any(feature = "loom", miri))))]
+
+use std::{
+    env,
+    process::Command,
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+};
+
+use generic_statics::{define_namespace, Namespace};
+
+define_namespace!(
+    #[freezable]
+    Config
+);
+
+#[test]
+fn write_after_freeze() {
+    let value = Config::generic_static::<AtomicU32>();
+    value.store(8, Ordering::Relaxed);
+    assert_eq!(
+        value as *const _,
+        Config::generic_static::<AtomicU32>() as *const _
+    );
+
+    Config::freeze();
+    Config::freeze();
+    assert_eq!(value.load(Ordering::Relaxed), 8);
+    assert_eq!(
+        Config::generic_static::<AtomicU64>().load(Ordering::Relaxed),
+        0
+    );
+
+    if env::var_os("GENERIC_STATICS_FREEZE_CHILD").is_some() {
+        value.store(9, Ordering::Relaxed);
+        return;
+    }
+
+    let status = Command::new(env::current_exe().unwrap())
+        .args(["write_after_freeze", "--exact", "--test-threads=1"])
+        .env("GENERIC_STATICS_FREEZE_CHILD", "1")
+        .status()
+        .unwrap();
+    assert!(!status.success());
+}
@@ -0,0 +1,66 @@
+#![cfg(all(not(feature = "loom"), target_os = "linux"))]
+
+use std::{env, fs, mem};
+
+use generic_statics::{__storage_key, __storage_size, define_namespace, Namespace, Zeroable};
+use object::{Object, ObjectSection, ObjectSymbol};
+
+define_namespace!(
+    #[unsafe(identity(process))]
+    Test
+);
+
+struct Reachable([u64; 3]);
+unsafe impl Zeroable for Reachable {}
+
+struct Unreachable([u64; 5]);
+unsafe impl Zeroable for Unreachable {}
+
+/// Codegened (as it's `#[no_mangle]`), but never called and therefore dropped by the linker.
+#[no_mangle]
+pub fn generic_statics_gc_sections_unreachable() -> u64 {
+    Test::generic_static::<Unreachable>().0[0]
+}
+
+fn storage_symbol<T>() -> String {
+    format!(
+        "__generic_statics.{}.{}.{}",
+        __storage_key::<Test, T>(),
+        __storage_size::<T>(),
+        mem::align_of::<T>().ilog2()
+    )
+}
+
+/// Every storage is a section of its own, so the linker drops the storage of unreachable
+/// instantiations.
+#[test]
+fn unreachable_storage_is_dropped() {
+    assert_eq!(Test::generic_static::<Reachable>().0, [0; 3]);
+
+    let data = fs::read(env::current_exe().unwrap()).unwrap();
+    let file = object::File::parse(&*data).unwrap();
+
+    // GNU ld keeps the sections referenced by `__start_`/`__stop_` symbols (and therefore the
+    // storage the entries of these features refer to) without `-z start-stop-gc`.
+    let lld = file
+        .section_by_name(".comment")
+        .and_then(|section| section.data().ok())
+        .is_some_and(|data| data.windows(3).any(|window| window == b"LLD"));
+    if !lld
+        && cfg!(any(
+            feature = "verify-at-startup",
+            feature = "canaries",
+            feature = "introspection"
+        ))
+    {
+        return;
+    }
+
+    let names = file
+        .symbols()
+        .filter_map(|symbol| symbol.name().ok())
+        .collect::<Vec<_>>();
+    assert!(!names.contains(&"generic_statics_gc_sections_unreachable"));
+    assert!(names.contains(&&*storage_symbol::<Reachable>()));
+    assert!(!names.contains(&&*storage_symbol::<Unreachable>()));
+}
@@ -0,0 +1,44 @@
+#![cfg(all(feature = "log", not(feature = "loom")))]
+
+use std::sync::{atomic::AtomicU32, Mutex};
+
+use generic_statics::{define_namespace, Namespace};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct Recorder;
+
+impl Log for Recorder {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target() == "generic_statics" && metadata.level() == Level::Debug
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            RECORDS.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn first_access() {
+    define_namespace!(Logged);
+
+    log::set_logger(&Recorder).unwrap();
+    log::set_max_level(LevelFilter::Debug);
+
+    let _ = Logged::generic_static::<[AtomicU32; 3]>();
+    let _ = Logged::generic_static::<[AtomicU32; 3]>();
+
+    let records = RECORDS.lock().unwrap();
+    let logged = records
+        .iter()
+        .filter(|record| record.contains("Logged"))
+        .collect::<Vec<_>>();
+    assert_eq!(logged.len(), 1);
+    assert!(logged[0].contains("[core::sync::atomic::Atomic"));
+    assert!(logged[0].contains("12 bytes"));
+}
@@ -0,0 +1,232 @@
+#![cfg(feature = "loom")]
+
+use generic_statics::{
+    define_namespace,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    GenericMutex, GenericQueue, GenericRcu, GenericRing, GenericRwLock, GenericSeqLock, Namespace,
+};
+
+define_namespace!(Test);
+
+#[test]
+fn concurrent_increment() {
+    loom::model(|| {
+        let threads: Vec<_> = (0..2)
+            .map(|_| {
+                loom::thread::spawn(|| {
+                    Test::generic_static::<AtomicUsize>().fetch_add(1, Ordering::Relaxed);
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(
+            Test::generic_static::<AtomicUsize>().load(Ordering::Relaxed),
+            2
+        );
+    });
+}
+
+#[test]
+fn fresh_statics_per_execution() {
+    loom::model(|| {
+        let counter = Test::generic_static::<AtomicUsize>();
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+        counter.store(42, Ordering::Relaxed);
+    });
+}
+
+#[test]
+fn concurrent_lazy() {
+    loom::model(|| {
+        let threads: Vec<_> = (0..2)
+            .map(|_| {
+                loom::thread::spawn(|| {
+                    Test::generic_lazy::<AtomicUsize>(|| {
+                        let value = AtomicUsize::new(0);
+                        Test::generic_static::<AtomicUsize>().fetch_add(1, Ordering::Relaxed);
+                        value
+                    })
+                    .fetch_add(1, Ordering::Relaxed);
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        // Initialized exactly once, but incremented by both threads.
+        assert_eq!(
+            Test::generic_static::<AtomicUsize>().load(Ordering::Relaxed),
+            1
+        );
+        assert_eq!(
+            Test::generic_lazy::<AtomicUsize>(|| unreachable!()).load(Ordering::Relaxed),
+            2
+        );
+    });
+}
+
+#[test]
+fn concurrent_mutex() {
+    loom::model(|| {
+        let threads: Vec<_> = (0..2)
+            .map(|_| {
+                loom::thread::spawn(|| {
+                    let mut guard = Test::generic_static::<GenericMutex<(usize, usize)>>().lock();
+                    guard.0 += 1;
+                    guard.1 += 1;
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(
+            *Test::generic_static::<GenericMutex<(usize, usize)>>().lock(),
+            (2, 2)
+        );
+    });
+}
+
+#[test]
+fn concurrent_rwlock() {
+    loom::model(|| {
+        let writer = loom::thread::spawn(|| {
+            let mut guard = Test::generic_static::<GenericRwLock<(usize, usize)>>().write();
+            guard.0 += 1;
+            guard.1 += 1;
+        });
+        let reader = loom::thread::spawn(|| {
+            let guard = Test::generic_static::<GenericRwLock<(usize, usize)>>().read();
+            assert_eq!(guard.0, guard.1);
+        });
+
+        writer.join().unwrap();
+        reader.join().unwrap();
+
+        assert_eq!(
+            *Test::generic_static::<GenericRwLock<(usize, usize)>>().read(),
+            (1, 1)
+        );
+    });
+}
+
+#[test]
+fn concurrent_seqlock() {
+    loom::model(|| {
+        let writer = loom::thread::spawn(|| {
+            Test::generic_static::<GenericSeqLock<[u32; 2]>>().update(|[a, b]| {
+                *a += 1;
+                *b += 1;
+            });
+        });
+        let reader = loom::thread::spawn(|| {
+            let [a, b] = Test::generic_static::<GenericSeqLock<[u32; 2]>>().read();
+            assert_eq!(a, b);
+        });
+
+        writer.join().unwrap();
+        reader.join().unwrap();
+
+        assert_eq!(
+            Test::generic_static::<GenericSeqLock<[u32; 2]>>().read(),
+            [1, 1]
+        );
+    });
+}
+
+#[test]
+fn concurrent_queue() {
+    loom::model(|| {
+        let producers: Vec<_> = (1..=2)
+            .map(|value| {
+                loom::thread::spawn(move || {
+                    Test::generic_static::<GenericQueue<u32, 2>>()
+                        .push(value)
+                        .unwrap();
+                })
+            })
+            .collect();
+
+        let queue = Test::generic_static::<GenericQueue<u32, 2>>();
+        let mut popped = Vec::new();
+        while popped.len() < 2 {
+            match queue.pop() {
+                Some(value) => popped.push(value),
+                None => loom::thread::yield_now(),
+            }
+        }
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        popped.sort_unstable();
+        assert_eq!(popped, [1, 2]);
+        assert_eq!(queue.pop(), None);
+    });
+}
+
+#[test]
+fn concurrent_ring() {
+    loom::model(|| {
+        let producer = loom::thread::spawn(|| {
+            let mut producer = Test::generic_static::<GenericRing<u32, 2>>()
+                .producer()
+                .unwrap();
+            producer.push(1).unwrap();
+            producer.push(2).unwrap();
+        });
+
+        let mut consumer = Test::generic_static::<GenericRing<u32, 2>>()
+            .consumer()
+            .unwrap();
+        let mut popped = Vec::new();
+        while popped.len() < 2 {
+            match consumer.pop() {
+                Some(value) => popped.push(value),
+                None => loom::thread::yield_now(),
+            }
+        }
+
+        producer.join().unwrap();
+        assert_eq!(popped, [1, 2]);
+    });
+}
+
+#[test]
+fn concurrent_rcu() {
+    /// Records in a static that it was dropped.
+    struct Value(u32);
+
+    impl Drop for Value {
+        fn drop(&mut self) {
+            Test::generic_static::<AtomicBool>().store(true, Ordering::Relaxed);
+        }
+    }
+
+    loom::model(|| {
+        let dropped = Test::generic_static::<AtomicBool>();
+        let rcu = Test::generic_static::<GenericRcu<Value>>();
+        rcu.replace(Value(1));
+
+        let reader = loom::thread::spawn(move || {
+            let value = rcu.read().unwrap();
+            // The value read can't have been dropped while the guard is held.
+            assert!(value.0 == 2 || !dropped.load(Ordering::Relaxed));
+        });
+
+        let old = rcu.replace(Value(2)).unwrap();
+        assert_eq!(old.0, 1);
+        drop(old);
+        reader.join().unwrap();
+        drop(rcu.take());
+    });
+}
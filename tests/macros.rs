@@ -0,0 +1,47 @@
+#![cfg(feature = "macros")]
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use generic_statics::Namespace;
+
+#[generic_statics::namespace]
+mod a {}
+
+#[generic_statics::namespace(Named)]
+mod b {}
+
+#[test]
+fn module_namespace() {
+    a::statics::<AtomicU32>().store(1, Ordering::Relaxed);
+    assert_eq!(b::statics::<AtomicU32>().load(Ordering::Relaxed), 0);
+    assert_eq!(
+        a::statics::<AtomicU32>() as *const _,
+        a::Statics::generic_static::<AtomicU32>() as *const _
+    );
+    assert_eq!(
+        b::statics::<AtomicU32>() as *const _,
+        b::Named::generic_static::<AtomicU32>() as *const _
+    );
+}
+
+generic_statics::define_namespace!(Test);
+
+/// A static per `T`.
+#[generic_statics::generic_static_accessor(Test)]
+fn per_type<T>() -> &'static AtomicU32;
+
+#[generic_statics::generic_static_accessor(Test)]
+pub(crate) fn buffer<T: generic_statics::Zeroable + Sync>() -> &'static [T; 4];
+
+#[test]
+fn accessor() {
+    per_type::<u8>().store(1, Ordering::Relaxed);
+    assert_eq!(per_type::<u16>().load(Ordering::Relaxed), 0);
+    assert_eq!(per_type::<u8>().load(Ordering::Relaxed), 1);
+    assert_eq!(buffer::<u64>(), &[0; 4]);
+    assert_eq!(buffer::<u64>() as *const _, buffer::<u64>() as *const _);
+    assert_ne!(
+        buffer::<u64>() as *const _,
+        Test::generic_static::<[u64; 4]>() as *const _
+    );
+}
@@ -0,0 +1,16 @@
+#![cfg(not(feature = "loom"))]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use generic_statics::Namespace;
+use upstream::Shared;
+
+#[test]
+fn shared_across_crates() {
+    let a = upstream::counter();
+    let b = Shared::generic_static::<AtomicUsize>();
+    assert_eq!(a as *const _, b as *const _);
+
+    a.store(42, Ordering::Relaxed);
+    assert_eq!(b.load(Ordering::Relaxed), 42);
+}
@@ -0,0 +1,34 @@
+#![cfg(all(feature = "teardown", not(feature = "loom")))]
+
+use std::{env, process::Command};
+
+use generic_statics::{define_namespace, Namespace};
+
+define_namespace!(Test);
+
+struct Loud(&'static str);
+
+impl Drop for Loud {
+    fn drop(&mut self) {
+        println!("dropped {}", self.0);
+    }
+}
+
+#[test]
+fn drop_at_exit() {
+    if env::var_os("GENERIC_STATICS_TEARDOWN_CHILD").is_some() {
+        let value = unsafe { Test::generic_lazy_teardown::<Loud>(|| Loud("loud")) };
+        assert_eq!(value.0, "loud");
+        return;
+    }
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["drop_at_exit", "--exact", "--nocapture", "--test-threads=1"])
+        .env("GENERIC_STATICS_TEARDOWN_CHILD", "1")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("dropped loud"), "{stdout}");
+}
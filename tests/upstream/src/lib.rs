@@ -0,0 +1,15 @@
+//! Helper crate for the multi-crate integration tests.
+
+use std::sync::atomic::AtomicUsize;
+
+use generic_statics::{define_namespace, Namespace};
+
+define_namespace!(
+    #[unsafe(identity(process))]
+    pub Shared
+);
+
+#[inline(never)]
+pub fn counter() -> &'static AtomicUsize {
+    Shared::generic_static::<AtomicUsize>()
+}
@@ -0,0 +1,91 @@
+#![cfg(not(any(feature = "loom", miri)))]
+
+use std::{
+    arch::global_asm,
+    mem,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use generic_statics::{
+    __storage, __storage_key, __storage_size, define_namespace, Namespace, Zeroable,
+};
+
+define_namespace!(
+    #[unsafe(identity(process))]
+    #[weak]
+    Handoff
+);
+
+#[repr(C)]
+struct Provided(AtomicU64);
+unsafe impl Zeroable for Provided {}
+
+// The strong definition of the storage of `Provided`, as provided by another object file (e.g. of
+// a bootloader).
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+global_asm!(
+    ".pushsection .data.generic_statics_weak,\"aw\",@progbits",
+    concat!(".globl ", __storage!(process)),
+    ".p2align {align}",
+    concat!(__storage!(process), ": .quad 42"),
+    ".popsection",
+    key = const __storage_key::<Handoff, Provided>(),
+    size = const __storage_size::<Provided>(),
+    align = const mem::align_of::<Provided>().ilog2(),
+);
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos"))]
+global_asm!(
+    ".pushsection __DATA,__data",
+    concat!(".globl ", __storage!(process)),
+    ".p2align {align}",
+    concat!(__storage!(process), ": .quad 42"),
+    ".popsection",
+    key = const __storage_key::<Handoff, Provided>(),
+    size = const __storage_size::<Provided>(),
+    align = const mem::align_of::<Provided>().ilog2(),
+);
+
+#[cfg(target_os = "windows")]
+global_asm!(
+    ".pushsection .data$gt,\"dw\"",
+    concat!(".globl ", __storage!(process)),
+    ".p2align {align}",
+    concat!(__storage!(process), ": .quad 42"),
+    ".popsection",
+    key = const __storage_key::<Handoff, Provided>(),
+    size = const __storage_size::<Provided>(),
+    align = const mem::align_of::<Provided>().ilog2(),
+    options(att_syntax),
+);
+
+#[test]
+fn strong_definition_takes_precedence() {
+    let provided = Handoff::generic_static::<Provided>();
+    assert_eq!(provided.0.load(Ordering::Relaxed), 42);
+    assert_eq!(
+        provided as *const Provided,
+        Handoff::generic_static::<Provided>() as *const Provided
+    );
+}
+
+#[test]
+fn weak_definition_is_the_default() {
+    let counter = Handoff::generic_static::<AtomicU64>();
+    assert_eq!(counter.fetch_add(1, Ordering::Relaxed), 0);
+    assert_eq!(
+        Handoff::generic_static::<AtomicU64>().load(Ordering::Relaxed),
+        1
+    );
+}
+
+#[test]
+fn storage_symbol() {
+    assert_eq!(
+        Handoff::storage_symbol::<Provided>().unwrap(),
+        format!(
+            "__generic_statics.{}.8.3",
+            __storage_key::<Handoff, Provided>()
+        )
+    );
+}
@@ -0,0 +1,42 @@
+#![cfg(all(not(feature = "loom"), not(target_os = "windows")))]
+
+use std::{env, fs};
+
+use generic_statics::{define_namespace, Namespace};
+use object::{Object, ObjectSection, ObjectSymbol, SectionKind};
+
+define_namespace!(
+    #[unsafe(identity(process))]
+    Test
+);
+
+const SIZE: usize = 1 << 20;
+
+/// Zeroed storage is emitted into a zero-fill section, so it doesn't take up space in the binary.
+#[test]
+fn zeroed_storage_is_zero_fill() {
+    let buffer = Test::generic_static::<[u8; SIZE]>();
+    assert_eq!(buffer[SIZE - 1], 0);
+
+    let data = fs::read(env::current_exe().unwrap()).unwrap();
+    let file = object::File::parse(&*data).unwrap();
+
+    // The storage symbol ends with its size and (log2) alignment.
+    let suffix = format!(".{SIZE}.0");
+    let symbol = file
+        .symbols()
+        .find(|symbol| {
+            symbol.name().is_ok_and(|name| {
+                name.trim_start_matches('_').starts_with("generic_statics.")
+                    && name.ends_with(&suffix)
+            })
+        })
+        .expect("the storage symbol wasn't found");
+
+    let section = file
+        .section_by_index(symbol.section_index().unwrap())
+        .unwrap();
+    assert_eq!(section.kind(), SectionKind::UninitializedData);
+    assert!(section.size() >= SIZE as u64);
+    assert_eq!(section.file_range(), None);
+}